@@ -0,0 +1,494 @@
+//! Token Inspection and Device Login
+//!
+//! `vkt auth` talks to the forge before any repository is involved: it asks
+//! the current-user endpoint who a token belongs to, and runs the OAuth 2.0
+//! device authorization grant (RFC 8628) on GitHub. Forges report the scopes
+//! and expiry of a token differently:
+//! - GitHub: `X-OAuth-Scopes` and `GitHub-Authentication-Token-Expiration` headers
+//! - GitLab: `GET /personal_access_tokens/self`
+//! - Gitea, Gitee and GitCode do not expose them
+
+use std::time::{Duration, Instant};
+
+use reqwest::{Method, Response};
+use serde::Deserialize;
+
+use crate::config::{NetworkConfig, ProviderType};
+use crate::error::{Result, VktError};
+
+use super::rest::{self, authorize};
+use super::transport::{Transport, client_builder};
+
+/// OAuth scope requested by the GitHub device login
+const GITHUB_DEVICE_SCOPE: &str = "repo";
+
+/// Grant type of the device token request
+const DEVICE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Account and token details reported by the forge
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenInfo {
+    /// Login name of the token owner
+    pub login: String,
+    /// Display name, if the account has one
+    pub name: Option<String>,
+    /// Granted scopes, or None if the forge does not report them
+    pub scopes: Option<Vec<String>>,
+    /// Expiry as reported by the forge, or None if the token does not expire
+    /// (or the forge does not say)
+    pub expires_at: Option<String>,
+}
+
+/// Current-user response (fields differ between forges)
+#[derive(Debug, Deserialize)]
+struct UserResponse {
+    login: Option<String>,
+    username: Option<String>,
+    name: Option<String>,
+    full_name: Option<String>,
+}
+
+/// GitLab `GET /personal_access_tokens/self` response
+#[derive(Debug, Deserialize)]
+struct GitLabTokenResponse {
+    #[serde(default)]
+    scopes: Vec<String>,
+    expires_at: Option<String>,
+}
+
+/// Create a transport for auth requests
+///
+/// Responses are never cached: a cached answer would hide a revoked token.
+fn auth_transport(token: &str, network: &NetworkConfig) -> Result<Transport> {
+    let client = client_builder(network)?
+        .build()
+        .map_err(|e| VktError::Network(e.to_string()))?;
+    Transport::from_env(client, token)
+}
+
+/// Fields of an error body that may hold the message, across forges and OAuth endpoints
+const ERROR_FIELDS: &[&str] = &["message", "error_description", "error"];
+
+/// A response header as a string
+fn header(response: &Response, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_string())
+}
+
+/// Ask the forge who a token belongs to, and what it may do
+///
+/// # Arguments
+/// * `provider` - Forge behind `api_url` (resolve `auto` first)
+/// * `api_url` - The API root
+/// * `token` - Access token to inspect
+/// * `network` - Proxy, TLS and user agent settings
+///
+/// # Errors
+/// `AuthInvalid` if the forge rejects the token, or a configuration error for
+/// providers without accounts (local repositories)
+pub async fn token_info(
+    provider: &ProviderType,
+    api_url: &str,
+    token: &str,
+    network: &NetworkConfig,
+) -> Result<TokenInfo> {
+    if matches!(
+        provider,
+        ProviderType::Local | ProviderType::Auto | ProviderType::Unknown(_)
+    ) {
+        return Err(VktError::Config(format!(
+            "Provider {} has no accounts to authenticate",
+            provider.as_str()
+        )));
+    }
+
+    let base = api_url.trim().trim_end_matches('/');
+    let transport = auth_transport(token, network)?;
+    let request = transport
+        .request(Method::GET, &format!("{}/user", base))
+        .header("Accept", "application/json");
+    let response = rest::error_for_status(
+        transport.send(authorize(provider, request, token)).await?,
+        ERROR_FIELDS,
+    )
+    .await?;
+
+    let mut info = TokenInfo::default();
+    if *provider == ProviderType::GitHub {
+        // Fine-grained tokens carry no OAuth scopes and omit the header
+        info.scopes = header(&response, "x-oauth-scopes").map(|scopes| {
+            scopes
+                .split(',')
+                .map(|scope| scope.trim().to_string())
+                .filter(|scope| !scope.is_empty())
+                .collect()
+        });
+        info.expires_at = header(&response, "github-authentication-token-expiration");
+    }
+
+    let user: UserResponse = response
+        .json()
+        .await
+        .map_err(|e| VktError::Api(format!("Failed to parse user response: {}", e)))?;
+    info.login = user.login.or(user.username).unwrap_or_default();
+    info.name = user
+        .name
+        .or(user.full_name)
+        .filter(|name| !name.is_empty() && *name != info.login);
+
+    if *provider == ProviderType::GitLab {
+        // Only personal, project and group access tokens can describe themselves
+        let request = transport
+            .request(
+                Method::GET,
+                &format!("{}/personal_access_tokens/self", base),
+            )
+            .header("Accept", "application/json");
+        let described = match transport.send(authorize(provider, request, token)).await {
+            Ok(response) => rest::error_for_status(response, ERROR_FIELDS).await,
+            Err(e) => Err(e),
+        };
+        match described {
+            Ok(response) => {
+                if let Ok(token) = response.json::<GitLabTokenResponse>().await {
+                    info.scopes = Some(token.scopes);
+                    info.expires_at = token.expires_at;
+                }
+            }
+            Err(e) => tracing::debug!("Token details unavailable: {}", e),
+        }
+    }
+
+    Ok(info)
+}
+
+/// Code the user enters on the forge to approve a device login
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceCode {
+    /// Code identifying this login to the token endpoint
+    pub device_code: String,
+    /// Code the user types on the verification page
+    pub user_code: String,
+    /// Page where the user enters `user_code`
+    pub verification_uri: String,
+    /// Seconds until the codes expire
+    pub expires_in: u64,
+    /// Minimum number of seconds between polls
+    #[serde(default = "default_poll_interval")]
+    pub interval: u64,
+}
+
+fn default_poll_interval() -> u64 {
+    5
+}
+
+/// Token endpoint response: a token, or why there is none yet
+#[derive(Debug, Deserialize)]
+struct DeviceTokenResponse {
+    access_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// OAuth device login against GitHub or GitHub Enterprise Server
+///
+/// The OAuth app (`client_id`) must have device flow enabled.
+#[derive(Debug, Clone)]
+pub struct DeviceLogin {
+    transport: Transport,
+    web_url: String,
+    client_id: String,
+}
+
+impl DeviceLogin {
+    /// Prepare a device login for the forge at `api_url`
+    ///
+    /// # Errors
+    /// Fails for forges other than GitHub, which have no device login vkt can use
+    pub fn new(
+        provider: &ProviderType,
+        api_url: &str,
+        client_id: &str,
+        network: &NetworkConfig,
+    ) -> Result<Self> {
+        if *provider != ProviderType::GitHub {
+            return Err(VktError::Validation(format!(
+                "Device login is only available for GitHub, not {}. Paste a personal access token instead",
+                provider.as_str()
+            )));
+        }
+        Ok(Self {
+            transport: auth_transport("", network)?,
+            web_url: github_web_url(api_url)?,
+            client_id: client_id.to_string(),
+        })
+    }
+
+    /// Request a device and user code
+    pub async fn start(&self) -> Result<DeviceCode> {
+        let request = self
+            .transport
+            .request(Method::POST, &format!("{}/login/device/code", self.web_url))
+            .header("Accept", "application/json")
+            .form(&[
+                ("client_id", self.client_id.as_str()),
+                ("scope", GITHUB_DEVICE_SCOPE),
+            ]);
+        let response =
+            rest::error_for_status(self.transport.send(request).await?, ERROR_FIELDS).await?;
+        response
+            .json()
+            .await
+            .map_err(|e| VktError::Api(format!("Failed to parse device code response: {}", e)))
+    }
+
+    /// Wait until the user approved the login and return the access token
+    ///
+    /// # Errors
+    /// `AuthInvalid` if the user denied the login or the code expired
+    pub async fn wait(&self, code: &DeviceCode) -> Result<String> {
+        let deadline = Instant::now() + Duration::from_secs(code.expires_in);
+        let mut interval = Duration::from_secs(code.interval);
+        loop {
+            tokio::time::sleep(interval).await;
+            if Instant::now() > deadline {
+                return Err(VktError::AuthInvalid(
+                    "the device code expired before the login was approved".to_string(),
+                ));
+            }
+
+            let request = self
+                .transport
+                .request(
+                    Method::POST,
+                    &format!("{}/login/oauth/access_token", self.web_url),
+                )
+                .header("Accept", "application/json")
+                .form(&[
+                    ("client_id", self.client_id.as_str()),
+                    ("device_code", code.device_code.as_str()),
+                    ("grant_type", DEVICE_GRANT_TYPE),
+                ]);
+            let response: DeviceTokenResponse = self
+                .transport
+                .send(request)
+                .await?
+                .json()
+                .await
+                .map_err(|e| VktError::Api(format!("Failed to parse token response: {}", e)))?;
+
+            if let Some(token) = response.access_token.filter(|token| !token.is_empty()) {
+                return Ok(token);
+            }
+            match response.error.as_deref() {
+                Some("authorization_pending") => {}
+                Some("slow_down") => interval += Duration::from_secs(5),
+                Some("access_denied") => {
+                    return Err(VktError::AuthInvalid("the login was denied".to_string()));
+                }
+                Some("expired_token") => {
+                    return Err(VktError::AuthInvalid(
+                        "the device code expired before the login was approved".to_string(),
+                    ));
+                }
+                error => {
+                    return Err(VktError::Api(format!(
+                        "Device login failed: {}",
+                        response
+                            .error_description
+                            .as_deref()
+                            .or(error)
+                            .unwrap_or("no token in response")
+                    )));
+                }
+            }
+        }
+    }
+}
+
+/// Web root of a GitHub API URL
+///
+/// `https://api.github.com` serves github.com; GitHub Enterprise Server
+/// serves its API below `/api/v3` of the web root.
+fn github_web_url(api_url: &str) -> Result<String> {
+    let url = reqwest::Url::parse(api_url.trim())
+        .map_err(|e| VktError::Config(format!("Invalid API URL {}: {}", api_url, e)))?;
+    let host = url.host_str().unwrap_or_default();
+    if let Some(web_host) = host.strip_prefix("api.") {
+        return Ok(format!("{}://{}", url.scheme(), web_host));
+    }
+    let base = api_url.trim().trim_end_matches('/');
+    Ok(base.strip_suffix("/api/v3").unwrap_or(base).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_string_contains, header as header_eq, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_github_token_info() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/user"))
+            .and(header_eq("Authorization", "Bearer ghp_test"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-OAuth-Scopes", "repo, workflow")
+                    .insert_header(
+                        "GitHub-Authentication-Token-Expiration",
+                        "2026-12-31 00:00:00 UTC",
+                    )
+                    .set_body_json(serde_json::json!({"login": "alice", "name": "Alice"})),
+            )
+            .mount(&server)
+            .await;
+
+        let info = token_info(
+            &ProviderType::GitHub,
+            &server.uri(),
+            "ghp_test",
+            &NetworkConfig::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(info.login, "alice");
+        assert_eq!(info.name.as_deref(), Some("Alice"));
+        assert_eq!(
+            info.scopes,
+            Some(vec!["repo".to_string(), "workflow".to_string()])
+        );
+        assert_eq!(info.expires_at.as_deref(), Some("2026-12-31 00:00:00 UTC"));
+    }
+
+    #[tokio::test]
+    async fn test_gitlab_token_info() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/user"))
+            .and(header_eq("PRIVATE-TOKEN", "glpat-test"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"username": "bob", "name": "bob"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/personal_access_tokens/self"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"scopes": ["api", "read_user"], "expires_at": "2026-11-01"}),
+            ))
+            .mount(&server)
+            .await;
+
+        let info = token_info(
+            &ProviderType::GitLab,
+            &format!("{}/api/v4", server.uri()),
+            "glpat-test",
+            &NetworkConfig::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(info.login, "bob");
+        assert_eq!(info.name, None);
+        assert_eq!(
+            info.scopes,
+            Some(vec!["api".to_string(), "read_user".to_string()])
+        );
+        assert_eq!(info.expires_at.as_deref(), Some("2026-11-01"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_token_is_auth_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/user"))
+            .respond_with(
+                ResponseTemplate::new(401)
+                    .set_body_json(serde_json::json!({"message": "token is invalid"})),
+            )
+            .mount(&server)
+            .await;
+
+        let err = token_info(
+            &ProviderType::Gitea,
+            &format!("{}/api/v1", server.uri()),
+            "stale",
+            &NetworkConfig::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, VktError::AuthInvalid(ref m) if m == "token is invalid"));
+    }
+
+    #[tokio::test]
+    async fn test_github_device_login() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/login/device/code"))
+            .and(body_string_contains("client_id=Iv1.test"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "device_code": "dev-123",
+                "user_code": "ABCD-1234",
+                "verification_uri": "https://github.com/login/device",
+                "expires_in": 900,
+                "interval": 0
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/login/oauth/access_token"))
+            .and(body_string_contains("device_code=dev-123"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"error": "authorization_pending"})),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/login/oauth/access_token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"access_token": "gho_device", "token_type": "bearer"}),
+            ))
+            .mount(&server)
+            .await;
+
+        let login = DeviceLogin::new(
+            &ProviderType::GitHub,
+            &format!("{}/api/v3", server.uri()),
+            "Iv1.test",
+            &NetworkConfig::default(),
+        )
+        .unwrap();
+        let code = login.start().await.unwrap();
+        assert_eq!(code.user_code, "ABCD-1234");
+        assert_eq!(login.wait(&code).await.unwrap(), "gho_device");
+
+        assert!(
+            DeviceLogin::new(
+                &ProviderType::Gitea,
+                "https://codeberg.org/api/v1",
+                "id",
+                &NetworkConfig::default()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_github_web_url() {
+        assert_eq!(
+            github_web_url("https://api.github.com").unwrap(),
+            "https://github.com"
+        );
+        assert_eq!(
+            github_web_url("https://ghe.corp.example/api/v3/").unwrap(),
+            "https://ghe.corp.example"
+        );
+    }
+}
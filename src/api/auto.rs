@@ -0,0 +1,565 @@
+//! Automatic Provider Detection
+//!
+//! Resolves `provider = "auto"` by identifying the forge behind an API URL.
+//! Well-known public hosts are recognised by name; anything else is probed
+//! through the GitHub `/meta` and GitLab/Gitea `/version` endpoints. Probe
+//! results are cached on disk per API URL so later runs skip the network.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{Method, Response};
+use tokio::sync::OnceCell;
+
+use crate::config::{Config, NetworkConfig, ProviderType};
+use crate::error::{Result, VktError};
+
+use super::factory::create_provider;
+use super::traits::ForgeProvider;
+use super::transport::{Transport, client_builder};
+use super::types::{
+    Branch, Capabilities, FileCommitResponse, FileInfo, PermissionReport, PullRequest,
+    RepositoryInfo, TreeItem,
+};
+
+/// Public hosts whose provider is known without probing
+const KNOWN_HOSTS: &[(&str, ProviderType)] = &[
+    ("gitcode.com", ProviderType::GitCode),
+    ("api.gitcode.com", ProviderType::GitCode),
+    ("gitee.com", ProviderType::Gitee),
+    ("github.com", ProviderType::GitHub),
+    ("api.github.com", ProviderType::GitHub),
+    ("gitlab.com", ProviderType::GitLab),
+    ("gitea.com", ProviderType::Gitea),
+    ("codeberg.org", ProviderType::Gitea),
+];
+
+/// Normalize an API URL for use as a cache key
+fn cache_key(api_url: &str) -> String {
+    api_url.trim().trim_end_matches('/').to_lowercase()
+}
+
+/// Identify a well-known public host (or a file:// URL) without network access
+pub fn known_provider(api_url: &str) -> Option<ProviderType> {
+    let url = reqwest::Url::parse(api_url.trim()).ok()?;
+    if url.scheme() == "file" {
+        return Some(ProviderType::Local);
+    }
+    let host = url.host_str()?.to_lowercase();
+    KNOWN_HOSTS
+        .iter()
+        .find(|(known, _)| *known == host)
+        .map(|(_, provider)| provider.clone())
+}
+
+/// On-disk cache of probed providers, keyed by API URL
+#[derive(Debug, Clone)]
+pub struct ProviderCache {
+    path: PathBuf,
+}
+
+impl ProviderCache {
+    /// Cache file in the user's cache directory (~/.cache/vkt/providers.json)
+    pub fn default_location() -> Option<Self> {
+        dirs::cache_dir().map(|dir| Self::at(dir.join("vkt").join("providers.json")))
+    }
+
+    /// Cache stored at a specific path
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Path of the cache file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self) -> BTreeMap<String, String> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Look up the cached provider for an API URL
+    pub fn get(&self, api_url: &str) -> Option<ProviderType> {
+        self.read()
+            .get(&cache_key(api_url))
+            .map(|provider| ProviderType::parse(provider))
+    }
+
+    /// Remember the provider for an API URL
+    pub fn insert(&self, api_url: &str, provider: &ProviderType) -> Result<()> {
+        let mut entries = self.read();
+        entries.insert(cache_key(api_url), provider.as_str().to_string());
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(&entries)
+            .map_err(|e| VktError::Api(format!("Failed to serialize provider cache: {}", e)))?;
+        std::fs::write(&self.path, json)?;
+        Ok(())
+    }
+}
+
+/// Read a JSON object from a probe response, if it is one
+async fn json_body(response: Response) -> Option<serde_json::Value> {
+    if !response.status().is_success() {
+        return None;
+    }
+    response
+        .json::<serde_json::Value>()
+        .await
+        .ok()
+        .filter(|json| json.is_object())
+}
+
+/// Identify the forge behind an API URL by probing well-known endpoints
+///
+/// # Arguments
+/// * `api_url` - The API root (e.g. `https://git.corp.example/api/v4`)
+/// * `token` - Access token; GitLab only answers `/version` when authenticated
+/// * `network` - Proxy, TLS and user agent settings
+///
+/// # Errors
+/// Returns a network error if the server is unreachable, or a configuration
+/// error if no known forge answered.
+pub async fn probe_provider(
+    api_url: &str,
+    token: &str,
+    network: &NetworkConfig,
+) -> Result<ProviderType> {
+    let base = api_url.trim().trim_end_matches('/');
+    if let Some(provider) = known_provider(base) {
+        return Ok(provider);
+    }
+
+    let client = client_builder(network)?
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| VktError::Network(e.to_string()))?;
+    let transport = Transport::from_env(client, token)?;
+
+    // GitHub and GitHub Enterprise serve a public /meta endpoint and tag every response
+    let response = transport
+        .send(
+            transport
+                .request(Method::GET, &format!("{}/meta", base))
+                .header("Accept", "application/vnd.github+json"),
+        )
+        .await?;
+    let headers = response.headers();
+    if headers.contains_key("x-github-request-id")
+        || headers.contains_key("x-github-enterprise-version")
+    {
+        return Ok(ProviderType::GitHub);
+    }
+    if json_body(response)
+        .await
+        .is_some_and(|json| json.get("verifiable_password_authentication").is_some())
+    {
+        return Ok(ProviderType::GitHub);
+    }
+
+    // GitLab and Gitea/Forgejo both serve /version; only GitLab reports a revision
+    let mut request = transport
+        .request(Method::GET, &format!("{}/version", base))
+        .header("Accept", "application/json");
+    if !token.is_empty() {
+        request = request
+            .header("PRIVATE-TOKEN", token)
+            .header("Authorization", format!("token {}", token));
+    }
+    let response = transport.send(request).await?;
+    if response.headers().contains_key("x-gitlab-meta") {
+        return Ok(ProviderType::GitLab);
+    }
+    if let Some(json) = json_body(response).await {
+        if json.get("revision").is_some() {
+            return Ok(ProviderType::GitLab);
+        }
+        if json.get("version").is_some() {
+            return Ok(ProviderType::Gitea);
+        }
+    }
+
+    // GitCode and Gitee v5 APIs have no version endpoint; fall back to the path
+    if base.to_lowercase().ends_with("/api/v5") {
+        return Ok(ProviderType::GitCode);
+    }
+
+    Err(VktError::Config(format!(
+        "Could not identify the forge at {}. Set remote.provider explicitly",
+        api_url
+    )))
+}
+
+/// Resolve the provider for an API URL: known host, then cache, then probe
+///
+/// A successful probe is written back to `cache`; cache write failures are ignored.
+pub async fn resolve_provider(
+    api_url: &str,
+    token: &str,
+    network: &NetworkConfig,
+    cache: Option<&ProviderCache>,
+) -> Result<ProviderType> {
+    if let Some(provider) = known_provider(api_url) {
+        return Ok(provider);
+    }
+    if let Some(provider) = cache.and_then(|cache| cache.get(api_url)) {
+        return Ok(provider);
+    }
+
+    let provider = probe_provider(api_url, token, network).await?;
+    if let Some(cache) = cache {
+        if let Err(e) = cache.insert(api_url, &provider) {
+            tracing::debug!("Failed to cache provider for {}: {}", api_url, e);
+        }
+    }
+    Ok(provider)
+}
+
+/// Provider for `provider = "auto"`
+///
+/// Identifies the forge on first use and then delegates every call to the
+/// matching provider.
+pub struct AutoProvider {
+    config: Config,
+    cache: Option<ProviderCache>,
+    inner: OnceCell<Box<dyn ForgeProvider>>,
+}
+
+impl AutoProvider {
+    /// Create an auto-detecting provider using the default cache location
+    pub fn new(config: &Config) -> Self {
+        Self {
+            config: config.clone(),
+            cache: ProviderCache::default_location(),
+            inner: OnceCell::new(),
+        }
+    }
+
+    /// Use a different cache (or none)
+    pub fn with_cache(mut self, cache: Option<ProviderCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// The resolved provider, detecting it on first use
+    async fn provider(&self) -> Result<&dyn ForgeProvider> {
+        let provider = self
+            .inner
+            .get_or_try_init(|| async {
+                let mut config = self.config.clone();
+                let api_url = &self.config.remote.api_url;
+                let detected = if self.config.cache.offline {
+                    // Probing needs the network, so only a known or cached provider will do
+                    known_provider(api_url)
+                        .or_else(|| self.cache.as_ref().and_then(|cache| cache.get(api_url)))
+                        .ok_or_else(|| {
+                            VktError::Offline(format!(
+                                "cannot detect the provider for {} (set remote.provider)",
+                                api_url
+                            ))
+                        })?
+                } else {
+                    // Resolve the token once for both the probe and the provider
+                    config.remote.token = config.access_token()?;
+                    resolve_provider(
+                        api_url,
+                        &config.remote.token,
+                        &config.network,
+                        self.cache.as_ref(),
+                    )
+                    .await?
+                };
+                tracing::debug!("Detected provider {} for {}", detected.as_str(), api_url);
+
+                config.remote.provider = detected.as_str().to_string();
+                create_provider(&config)
+            })
+            .await?;
+        Ok(provider.as_ref())
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for AutoProvider {
+    async fn list_repository_tree(
+        &self,
+        path: Option<&str>,
+        recursive: bool,
+        ref_branch: Option<&str>,
+    ) -> Result<Vec<TreeItem>> {
+        self.provider()
+            .await?
+            .list_repository_tree(path, recursive, ref_branch)
+            .await
+    }
+
+    async fn get_file_content(&self, file_path: &str, ref_branch: Option<&str>) -> Result<Vec<u8>> {
+        self.provider()
+            .await?
+            .get_file_content(file_path, ref_branch)
+            .await
+    }
+
+    async fn get_file_info(&self, file_path: &str, ref_branch: Option<&str>) -> Result<FileInfo> {
+        self.provider()
+            .await?
+            .get_file_info(file_path, ref_branch)
+            .await
+    }
+
+    async fn create_branch(&self, branch_name: &str, source_branch: &str) -> Result<Branch> {
+        self.provider()
+            .await?
+            .create_branch(branch_name, source_branch)
+            .await
+    }
+
+    async fn create_or_update_file(
+        &self,
+        file_path: &str,
+        content: &str,
+        branch: &str,
+        message: &str,
+        author_name: &str,
+        author_email: &str,
+    ) -> Result<FileCommitResponse> {
+        self.provider()
+            .await?
+            .create_or_update_file(
+                file_path,
+                content,
+                branch,
+                message,
+                author_name,
+                author_email,
+            )
+            .await
+    }
+
+    async fn create_pull_request(
+        &self,
+        title: &str,
+        head_branch: &str,
+        base_branch: &str,
+        body: Option<&str>,
+    ) -> Result<PullRequest> {
+        self.provider()
+            .await?
+            .create_pull_request(title, head_branch, base_branch, body)
+            .await
+    }
+
+    async fn assign_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        self.provider()
+            .await?
+            .assign_reviewers(pr_number, reviewers)
+            .await
+    }
+
+    async fn get_repository_info(&self) -> Result<RepositoryInfo> {
+        self.provider().await?.get_repository_info().await
+    }
+
+    async fn capabilities(&self) -> Result<Capabilities> {
+        self.provider().await?.capabilities().await
+    }
+
+    async fn check_permissions(
+        &self,
+        head_branch: &str,
+        base_branch: &str,
+    ) -> Result<PermissionReport> {
+        self.provider()
+            .await?
+            .check_permissions(head_branch, base_branch)
+            .await
+    }
+
+    async fn file_exists(&self, file_path: &str, ref_branch: Option<&str>) -> Result<bool> {
+        self.provider()
+            .await?
+            .file_exists(file_path, ref_branch)
+            .await
+    }
+
+    fn stale_since(&self) -> Option<DateTime<Utc>> {
+        self.inner.get().and_then(|provider| provider.stale_since())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_known_provider() {
+        assert_eq!(
+            known_provider("https://api.github.com"),
+            Some(ProviderType::GitHub)
+        );
+        assert_eq!(
+            known_provider("https://codeberg.org/api/v1"),
+            Some(ProviderType::Gitea)
+        );
+        assert_eq!(
+            known_provider("file:///srv/git/repo.git"),
+            Some(ProviderType::Local)
+        );
+        // Substrings are not enough: this could be any forge
+        assert_eq!(known_provider("https://gitlab.corp.example/api/v1"), None);
+    }
+
+    #[tokio::test]
+    async fn test_probe_github_enterprise() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/meta"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-github-enterprise-version", "3.12.0")
+                    .set_body_json(serde_json::json!({"installed_version": "3.12.0"})),
+            )
+            .mount(&server)
+            .await;
+
+        let provider = probe_provider(
+            &format!("{}/api/v3", server.uri()),
+            "t",
+            &NetworkConfig::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(provider, ProviderType::GitHub);
+    }
+
+    #[tokio::test]
+    async fn test_probe_gitlab_and_gitea_version() {
+        let gitlab = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/version"))
+            .and(header("PRIVATE-TOKEN", "secret"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"version": "16.8.1", "revision": "a1b2"})),
+            )
+            .mount(&gitlab)
+            .await;
+        let provider = probe_provider(
+            &format!("{}/api/v4/", gitlab.uri()),
+            "secret",
+            &NetworkConfig::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(provider, ProviderType::GitLab);
+
+        let gitea = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/version"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"version": "7.0.0+gitea-1.22.0"})),
+            )
+            .mount(&gitea)
+            .await;
+        let provider = probe_provider(
+            &format!("{}/api/v1", gitea.uri()),
+            "secret",
+            &NetworkConfig::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(provider, ProviderType::Gitea);
+    }
+
+    #[tokio::test]
+    async fn test_probe_unknown_server() {
+        let server = MockServer::start().await;
+        let result = probe_provider(
+            &format!("{}/api", server.uri()),
+            "t",
+            &NetworkConfig::default(),
+        )
+        .await;
+        assert!(matches!(result, Err(VktError::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_uses_and_fills_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ProviderCache::at(dir.path().join("providers.json"));
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/version"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"version": "1.21"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api_url = format!("{}/api/v1", server.uri());
+        for _ in 0..2 {
+            let provider = resolve_provider(&api_url, "t", &NetworkConfig::default(), Some(&cache))
+                .await
+                .unwrap();
+            assert_eq!(provider, ProviderType::Gitea);
+        }
+        assert_eq!(
+            cache.get(&format!("{}/", api_url.to_uppercase())),
+            Some(ProviderType::Gitea)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auto_provider_delegates_to_detected_provider() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/version"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"version": "1.21"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/repos/owner/repo"))
+            .and(header("Authorization", "token test-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 7,
+                "name": "repo",
+                "full_name": "owner/repo",
+                "private": false,
+                "owner": {"id": 1, "login": "owner"},
+                "html_url": "https://git.corp.example/owner/repo",
+                "description": null,
+                "default_branch": "main",
+                "clone_url": "https://git.corp.example/owner/repo.git",
+                "ssh_url": "git@git.corp.example:owner/repo.git"
+            })))
+            .mount(&server)
+            .await;
+
+        let config = Config::for_tests("auto", &format!("{}/api/v1", server.uri()));
+
+        let provider = AutoProvider::new(&config).with_cache(None);
+        let info = provider.get_repository_info().await.unwrap();
+        assert_eq!(info.full_name, "owner/repo");
+        assert!(
+            provider
+                .capabilities()
+                .await
+                .unwrap()
+                .supports(crate::api::Capability::RawDownloads)
+        );
+    }
+}
@@ -0,0 +1,345 @@
+//! HTTP Response Cache
+//!
+//! Successful GET responses are stored under the user's cache directory
+//! (~/.cache/vkt/http), one file per URL and credential, grouped by host. The
+//! URL holds the repository, ref and path; the credential is a fingerprint of
+//! the token the response was fetched with, so a token never reads entries
+//! fetched with another one.
+//!
+//! When an entry has an `ETag` or `Last-Modified` validator, the next request
+//! for the same URL is sent with `If-None-Match`/`If-Modified-Since` and a
+//! `304 Not Modified` is answered from the cache, so online the server still
+//! checks access. Offline mode serves entries without asking the server and
+//! regardless of their age: a token that has lost access since can still read
+//! what it fetched before, and `list`/`get` end their output with the time the
+//! oldest answer was fetched.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::CacheConfig;
+use crate::error::{Result, VktError};
+
+/// Response headers kept with a cache entry
+const STORED_HEADERS: &[&str] = &["content-type", "etag", "last-modified"];
+
+/// A cached response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// Request URL (secrets redacted)
+    pub url: String,
+    /// Fingerprint of the credentials the response was fetched with (empty if none)
+    #[serde(default)]
+    pub credential: String,
+    /// HTTP status of the stored response
+    pub status: u16,
+    /// Selected response headers (lower-case names)
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Base64-encoded response body
+    pub body: String,
+    /// When the response was fetched from the server
+    pub fetched_at: DateTime<Utc>,
+    /// When the server last confirmed the entry (or it was fetched)
+    pub validated_at: DateTime<Utc>,
+}
+
+impl CacheEntry {
+    /// The `ETag` validator, if the server sent one
+    pub fn etag(&self) -> Option<&str> {
+        self.headers.get("etag").map(String::as_str)
+    }
+
+    /// The `Last-Modified` validator, if the server sent one
+    pub fn last_modified(&self) -> Option<&str> {
+        self.headers.get("last-modified").map(String::as_str)
+    }
+
+    /// Decode the stored body
+    pub fn body(&self) -> Result<Vec<u8>> {
+        base64::engine::general_purpose::STANDARD
+            .decode(&self.body)
+            .map_err(|e| VktError::Api(format!("Corrupt cache entry for {}: {}", self.url, e)))
+    }
+}
+
+/// Summary of the cache contents
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheStats {
+    /// Number of cached responses
+    pub entries: usize,
+    /// Total size of the cache files in bytes
+    pub bytes: u64,
+    /// Number of distinct hosts
+    pub hosts: usize,
+    /// Oldest fetch time
+    pub oldest: Option<DateTime<Utc>>,
+    /// Newest fetch time
+    pub newest: Option<DateTime<Utc>>,
+}
+
+/// On-disk response cache
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+impl ResponseCache {
+    /// Cache in the user's cache directory (~/.cache/vkt/http)
+    pub fn default_location() -> Option<Self> {
+        dirs::cache_dir().map(|dir| Self::at(dir.join("vkt").join("http")))
+    }
+
+    /// Cache described by the configuration, or None if caching is disabled
+    pub fn from_config(config: &CacheConfig) -> Option<Self> {
+        if !config.enabled {
+            None
+        } else if config.dir.is_empty() {
+            Self::default_location()
+        } else {
+            Some(Self::at(&config.dir))
+        }
+    }
+
+    /// Cache stored in a specific directory
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Directory holding the cache
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// File holding the entry for a URL: `<host>/<sha256 of credential and url>.json`
+    fn entry_path(&self, url: &str, credential: &str) -> PathBuf {
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| {
+                u.host_str().map(|host| match u.port() {
+                    Some(port) => format!("{}_{}", host, port),
+                    None => host.to_string(),
+                })
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        self.dir.join(host).join(format!(
+            "{:x}.json",
+            Sha256::digest(format!("{}\n{}", credential, url))
+        ))
+    }
+
+    /// Look up the entry for a URL fetched with a credential
+    pub fn get(&self, url: &str, credential: &str) -> Option<CacheEntry> {
+        let content = std::fs::read_to_string(self.entry_path(url, credential)).ok()?;
+        serde_json::from_str::<CacheEntry>(&content)
+            .ok()
+            .filter(|entry| entry.url == url && entry.credential == credential)
+    }
+
+    /// Store a response for a URL fetched with a credential
+    pub fn insert(
+        &self,
+        url: &str,
+        credential: &str,
+        status: u16,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<()> {
+        let now = Utc::now();
+        let headers = STORED_HEADERS
+            .iter()
+            .filter_map(|name| {
+                let value = headers.get(*name)?.to_str().ok()?;
+                Some((name.to_string(), value.to_string()))
+            })
+            .collect();
+        self.write(&CacheEntry {
+            url: url.to_string(),
+            credential: credential.to_string(),
+            status,
+            headers,
+            body: base64::engine::general_purpose::STANDARD.encode(body),
+            fetched_at: now,
+            validated_at: now,
+        })
+    }
+
+    /// Record that the server confirmed an entry is still current
+    pub fn touch(&self, entry: &CacheEntry) -> Result<()> {
+        self.write(&CacheEntry {
+            validated_at: Utc::now(),
+            ..entry.clone()
+        })
+    }
+
+    fn write(&self, entry: &CacheEntry) -> Result<()> {
+        let path = self.entry_path(&entry.url, &entry.credential);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string(entry)
+            .map_err(|e| VktError::Api(format!("Failed to serialize cache entry: {}", e)))?;
+
+        // Write atomically so concurrent downloads never read a partial entry
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// All cache files with their parsed entries (None if unreadable)
+    fn files(&self) -> Result<Vec<(PathBuf, u64, Option<CacheEntry>)>> {
+        let mut files = Vec::new();
+        let hosts = match std::fs::read_dir(&self.dir) {
+            Ok(hosts) => hosts,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(files),
+            Err(e) => return Err(e.into()),
+        };
+        for host in hosts {
+            let host = host?;
+            if !host.file_type()?.is_dir() {
+                continue;
+            }
+            for file in std::fs::read_dir(host.path())? {
+                let file = file?;
+                let size = file.metadata()?.len();
+                let entry = std::fs::read_to_string(file.path())
+                    .ok()
+                    .and_then(|content| serde_json::from_str(&content).ok());
+                files.push((file.path(), size, entry));
+            }
+        }
+        Ok(files)
+    }
+
+    /// Summarize the cache contents
+    pub fn stats(&self) -> Result<CacheStats> {
+        let files = self.files()?;
+        let mut hosts: Vec<&Path> = files
+            .iter()
+            .filter_map(|(path, _, _)| path.parent())
+            .collect();
+        hosts.dedup();
+        let fetched = files
+            .iter()
+            .filter_map(|(_, _, entry)| entry.as_ref().map(|e| e.fetched_at));
+
+        Ok(CacheStats {
+            entries: files.len(),
+            bytes: files.iter().map(|(_, size, _)| size).sum(),
+            hosts: hosts.len(),
+            oldest: fetched.clone().min(),
+            newest: fetched.max(),
+        })
+    }
+
+    /// Remove every entry
+    ///
+    /// # Returns
+    /// The number of entries removed
+    pub fn clear(&self) -> Result<usize> {
+        let count = self.files()?.len();
+        match std::fs::remove_dir_all(&self.dir) {
+            Ok(()) => Ok(count),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Remove entries not confirmed by the server within `max_age`, and
+    /// unreadable files
+    ///
+    /// # Returns
+    /// The number of entries removed
+    pub fn prune(&self, max_age: Duration) -> Result<usize> {
+        let cutoff =
+            Utc::now() - chrono::Duration::from_std(max_age).unwrap_or(chrono::Duration::MAX);
+        let mut removed = 0;
+        for (path, _, entry) in self.files()? {
+            if entry.is_none_or(|entry| entry.validated_at < cutoff) {
+                std::fs::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_insert_and_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::at(dir.path());
+        let url = "https://forge.example/api/v5/repos/o/r/contents/a.sh?ref=main";
+        assert!(cache.get(url, "tok1").is_none());
+
+        let mut headers = HeaderMap::new();
+        headers.insert("etag", HeaderValue::from_static("\"abc\""));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("10"));
+        cache
+            .insert(url, "tok1", 200, &headers, b"echo hi")
+            .unwrap();
+
+        let entry = cache.get(url, "tok1").unwrap();
+        assert_eq!(entry.etag(), Some("\"abc\""));
+        assert!(entry.last_modified().is_none());
+        assert!(!entry.headers.contains_key("x-ratelimit-remaining"));
+        assert_eq!(entry.body().unwrap(), b"echo hi");
+        assert!(
+            cache
+                .get(
+                    "https://forge.example/api/v5/repos/o/r/contents/a.sh?ref=dev",
+                    "tok1"
+                )
+                .is_none()
+        );
+        // Another token (or none) does not see the entry
+        assert!(cache.get(url, "tok2").is_none());
+        assert!(cache.get(url, "").is_none());
+    }
+
+    #[test]
+    fn test_stats_prune_and_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::at(dir.path());
+        assert_eq!(cache.stats().unwrap(), CacheStats::default());
+
+        let headers = HeaderMap::new();
+        cache
+            .insert("https://a.example/x", "", 200, &headers, b"1")
+            .unwrap();
+        cache
+            .insert("https://a.example/y", "", 200, &headers, b"2")
+            .unwrap();
+        cache
+            .insert("https://b.example:8443/z", "", 200, &headers, b"3")
+            .unwrap();
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.entries, 3);
+        assert_eq!(stats.hosts, 2);
+        assert!(stats.bytes > 0);
+        assert!(stats.oldest.unwrap() <= stats.newest.unwrap());
+
+        // Backdate one entry so it falls outside the retention window
+        let mut old = cache.get("https://a.example/x", "").unwrap();
+        old.validated_at = Utc::now() - chrono::Duration::days(60);
+        cache.write(&old).unwrap();
+        assert_eq!(cache.prune(Duration::from_secs(30 * 86400)).unwrap(), 1);
+        assert!(cache.get("https://a.example/x", "").is_none());
+
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert_eq!(cache.stats().unwrap().entries, 0);
+    }
+}
@@ -3,19 +3,27 @@
 //! Provides API compatibility with the existing ApiClient interface
 //! while using the new ForgeProvider trait internally.
 
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
 use crate::config::Config;
 use crate::error::Result;
 
 use super::factory::create_provider;
 use super::traits::ForgeProvider;
-use super::types::{Branch, FileCommitResponse, FileInfo, PullRequest, TreeItem};
+use super::types::{
+    Branch, Capabilities, FileCommitResponse, FileInfo, PermissionReport, PullRequest, TreeItem,
+};
 
 /// API Client (backwards compatible)
 ///
 /// This struct maintains the same interface as the original ApiClient
 /// but delegates to a ForgeProvider implementation internally.
+/// Clones share the same provider.
+#[derive(Clone)]
 pub struct ApiClient {
-    provider: Box<dyn ForgeProvider>,
+    provider: Arc<dyn ForgeProvider>,
 }
 
 impl std::fmt::Debug for ApiClient {
@@ -36,7 +44,17 @@ impl ApiClient {
     /// A new ApiClient instance
     pub fn new(config: &Config) -> Result<Self> {
         let provider = create_provider(config)?;
-        Ok(Self { provider })
+        Ok(Self::from_provider(provider))
+    }
+
+    /// Create an API client around an existing provider
+    ///
+    /// # Arguments
+    /// * `provider` - The provider to delegate to (e.g. a `MockProvider` in tests)
+    pub fn from_provider(provider: Box<dyn ForgeProvider>) -> Self {
+        Self {
+            provider: Arc::from(provider),
+        }
     }
 
     /// List repository contents
@@ -173,6 +191,32 @@ impl ApiClient {
         self.provider.get_repository_info().await
     }
 
+    /// Get the optional features supported by the provider
+    ///
+    /// # Returns
+    /// The set of supported capabilities
+    pub async fn capabilities(&self) -> Result<Capabilities> {
+        self.provider.capabilities().await
+    }
+
+    /// Check, without writing, whether a submission may go ahead
+    ///
+    /// # Arguments
+    /// * `head_branch` - Branch that will be created
+    /// * `base_branch` - Branch the pull request will target
+    ///
+    /// # Returns
+    /// The permissions the forge confirmed or denied
+    pub async fn check_permissions(
+        &self,
+        head_branch: &str,
+        base_branch: &str,
+    ) -> Result<PermissionReport> {
+        self.provider
+            .check_permissions(head_branch, base_branch)
+            .await
+    }
+
     /// Check if a file exists
     ///
     /// # Arguments
@@ -184,6 +228,14 @@ impl ApiClient {
     pub async fn file_exists(&self, file_path: &str, ref_branch: Option<&str>) -> Result<bool> {
         self.provider.file_exists(file_path, ref_branch).await
     }
+
+    /// Fetch time of the oldest cached answer given in offline mode
+    ///
+    /// # Returns
+    /// None if every answer so far came from the forge itself
+    pub fn stale_since(&self) -> Option<DateTime<Utc>> {
+        self.provider.stale_since()
+    }
 }
 
 #[cfg(test)]
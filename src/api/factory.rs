@@ -5,7 +5,13 @@
 use crate::config::{Config, ProviderType};
 use crate::error::{Result, VktError};
 
+use super::auto::AutoProvider;
 use super::gitcode::GitCodeProvider;
+use super::gitea::GiteaProvider;
+use super::gitee::GiteeProvider;
+use super::github::GitHubProvider;
+use super::gitlab::GitLabProvider;
+use super::local::LocalProvider;
 use super::traits::ForgeProvider;
 
 /// Create a provider based on configuration
@@ -24,39 +30,39 @@ pub fn create_provider(config: &Config) -> Result<Box<dyn ForgeProvider>> {
             let provider = GitCodeProvider::new(config)?;
             Ok(Box::new(provider))
         }
-        ProviderType::GitLab => Err(VktError::Config(
-            "GitLab provider not yet implemented".to_string(),
-        )),
-        ProviderType::GitHub => Err(VktError::Config(
-            "GitHub provider not yet implemented".to_string(),
-        )),
+        ProviderType::GitLab => {
+            let provider = GitLabProvider::new(config)?;
+            Ok(Box::new(provider))
+        }
+        ProviderType::GitHub => {
+            let provider = GitHubProvider::new(config)?;
+            Ok(Box::new(provider))
+        }
+        ProviderType::Gitea => {
+            let provider = GiteaProvider::new(config)?;
+            Ok(Box::new(provider))
+        }
+        ProviderType::Gitee => {
+            let provider = GiteeProvider::new(config)?;
+            Ok(Box::new(provider))
+        }
+        ProviderType::Local => {
+            let provider = LocalProvider::new(config)?;
+            Ok(Box::new(provider))
+        }
+        ProviderType::Auto => {
+            let provider = AutoProvider::new(config);
+            Ok(Box::new(provider))
+        }
         ProviderType::Unknown(provider) => Err(VktError::Config(format!(
-            "Unknown provider: {}. Supported providers: GitCode, GitLab, GitHub",
+            "Unknown provider: {}. Supported providers: GitCode, GitLab, GitHub, Gitea, Gitee, Local, Auto",
             provider
         ))),
     }
 }
 
-/// Auto-detect provider type from API URL
-///
-/// # Arguments
-/// * `api_url` - The API URL to analyze
-///
-/// # Returns
-/// The detected ProviderType
-pub fn detect_provider(api_url: &str) -> ProviderType {
-    let url_lower = api_url.to_lowercase();
-
-    if url_lower.contains("gitcode.com") {
-        ProviderType::GitCode
-    } else if url_lower.contains("gitlab") || url_lower.contains("git-lab") {
-        ProviderType::GitLab
-    } else if url_lower.contains("github.com") {
-        ProviderType::GitHub
-    } else {
-        ProviderType::Unknown("unknown".to_string())
-    }
-}
+/// Guess the provider type from an API URL (shared with the config module)
+pub use crate::config::detect_provider;
 
 #[cfg(test)]
 mod tests {}
@@ -2,28 +2,41 @@
 //!
 //! Implements the ForgeProvider trait for GitCode's GitHub-compatible API.
 
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use async_trait::async_trait;
-use reqwest::{Client, Method, Response, StatusCode};
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use reqwest::{Method, StatusCode};
 
+use crate::api::pagination::{Cursor, Page, next_from_headers, paginate};
+use crate::api::rest;
 use crate::api::traits::ForgeProvider;
+use crate::api::transport::Transport;
 use crate::api::types::{
-    Branch, Commit, FileCommitResponse, FileInfo, PullRequest, RepositoryInfo, TreeItem,
+    Branch, Capabilities, Capability, FileCommitResponse, FileInfo, PermissionReport, PullRequest,
+    RepositoryInfo, TreeItem,
 };
-use crate::config::Config;
+use crate::config::{Config, ProviderType};
 use crate::error::{Result, VktError};
 
-mod types;
+pub(crate) mod types;
 
 use types::{
     CreateBranchRequest, CreateFileRequest, CreatePullRequest, GitCodeBranchResponse,
-    GitCodeCommitResponse, GitCodeContentResponse, GitCodePullResponse, GitCodeRepoResponse, GitTreeResponse, RequestReviewers, path_to_tree_item,
+    GitCodeCommitResponse, GitCodeContentResponse, GitCodePullResponse, GitCodeRepoResponse,
+    GitTreeResponse, RequestReviewers, path_to_tree_item,
 };
 
+/// Fields of a GitCode error body that may hold the message
+const ERROR_FIELDS: &[&str] = &["message", "error_message"];
+
 /// GitCode API Provider
 #[derive(Debug, Clone)]
 pub struct GitCodeProvider {
-    /// HTTP client
-    client: Client,
+    /// HTTP transport (live, recording or replaying)
+    transport: Transport,
     /// Base API URL
     base_url: String,
     /// API token
@@ -34,107 +47,40 @@ pub struct GitCodeProvider {
     repo: String,
     /// Default branch name
     default_branch: String,
+    /// Set once the instance answered the reviewer endpoint with 404 (shared by clones)
+    reviewers_unsupported: Arc<AtomicBool>,
 }
 
 impl GitCodeProvider {
     /// Create a new GitCode provider from configuration
     pub fn new(config: &Config) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .map_err(|e| VktError::Network(e.to_string()))?;
+        let (owner, repo) = rest::parse_project_id(&config.repo.project_id)?;
 
-        let (owner, repo) = Self::parse_project_id(&config.repo.project_id)?;
+        let token = config.access_token()?;
 
         Ok(Self {
-            client,
+            transport: Transport::from_config(config)?.with_secret(&token),
             base_url: config.remote.api_url.trim_end_matches('/').to_string(),
-            token: config.remote.token.clone(),
+            token,
             owner,
             repo,
             default_branch: config.repo.default_branch.clone(),
+            reviewers_unsupported: Arc::new(AtomicBool::new(false)),
         })
     }
 
-    /// Parse project ID in "owner/repo" format
-    fn parse_project_id(project_id: &str) -> Result<(String, String)> {
-        let parts: Vec<&str> = project_id.split('/').collect();
-        if parts.len() != 2 {
-            return Err(VktError::Config(
-                "Project ID format should be 'owner/repo'".to_string(),
-            ));
-        }
-        Ok((parts[0].to_string(), parts[1].to_string()))
-    }
-
-    /// Build full API URL
-    fn build_url(&self, path: &str) -> String {
-        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    /// Replace the HTTP transport (e.g. to replay a recorded cassette)
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = transport.with_secret(&self.token);
+        self
     }
 
     /// Build authenticated request
     fn build_request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
-        let url = self.build_url(path);
-        self.client
-            .request(method, &url)
-            .header("Authorization", format!("Bearer {}", self.token))
+        let url = rest::build_url(&self.base_url, path);
+        let request = self.transport.request(method, &url);
+        rest::authorize(&ProviderType::GitCode, request, &self.token)
             .header("Accept", "application/vnd.github+json")
-            .header("User-Agent", "vkt/0.1.0")
-    }
-
-    /// Handle API response with proper error mapping
-    async fn handle_response<T: serde::de::DeserializeOwned>(
-        &self,
-        response: Response,
-    ) -> Result<T> {
-        let status = response.status();
-        if status.is_success() {
-            // Get the response text first for better error messages
-            let text = response
-                .text()
-                .await
-                .map_err(|e| VktError::Api(format!("Failed to read response body: {}", e)))?;
-
-            // Parse JSON with detailed error message
-            serde_json::from_str(&text).map_err(|e| {
-                VktError::Api(format!(
-                    "Failed to parse response: {}. Body: {}",
-                    e,
-                    if text.len() > 200 {
-                        format!("{}...", &text[..200])
-                    } else {
-                        text
-                    }
-                ))
-            })
-        } else {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-
-            Err(match status {
-                StatusCode::UNAUTHORIZED => {
-                    VktError::AuthInvalid(format!("Authentication failed: {}", error_text))
-                }
-                StatusCode::FORBIDDEN => {
-                    if error_text.to_lowercase().contains("rate")
-                        || error_text.to_lowercase().contains("limit")
-                    {
-                        VktError::RateLimited(format!("Rate limited: {}", error_text))
-                    } else {
-                        VktError::PermissionDenied(format!("Permission denied: {}", error_text))
-                    }
-                }
-                StatusCode::NOT_FOUND => {
-                    VktError::ApiNotFound(format!("Resource not found: {}", error_text))
-                }
-                StatusCode::CONFLICT => {
-                    VktError::Conflict(format!("Resource conflict: {}", error_text))
-                }
-                _ => VktError::Api(format!("API error (HTTP {}): {}", status, error_text)),
-            })
-        }
     }
 
     /// Process API paths into TreeItems
@@ -274,8 +220,20 @@ impl ForgeProvider for GitCodeProvider {
             api_path = format!("{}?{}", api_path, params.join("&"));
         }
 
-        let response = self.build_request(Method::GET, &api_path).send().await?;
-        let paths: GitTreeResponse = self.handle_response(response).await?;
+        // Large repositories are split into pages linked through the `Link` header
+        let api_path = &api_path;
+        let first = Cursor::Url(rest::build_url(&self.base_url, api_path));
+        let paths: GitTreeResponse = paginate(first, |cursor| async move {
+            let response = self
+                .transport
+                .send(self.build_request(Method::GET, &cursor.apply(api_path)))
+                .await?;
+            let next = next_from_headers(response.headers());
+            let paths: GitTreeResponse = rest::handle_response(response, ERROR_FIELDS).await?;
+            Ok(Page::new(paths, next))
+        })
+        .try_collect()
+        .await?;
 
         // Process paths to extract immediate children (ls-like behavior)
         let items = self.process_paths(paths, path, recursive);
@@ -294,7 +252,10 @@ impl ForgeProvider for GitCodeProvider {
             api_path = format!("{}?ref={}", api_path, urlencoding::encode(branch));
         }
 
-        let response = self.build_request(Method::GET, &api_path).send().await?;
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &api_path))
+            .await?;
 
         if response.status() == StatusCode::NOT_FOUND {
             return Err(VktError::ApiNotFound(format!(
@@ -303,7 +264,8 @@ impl ForgeProvider for GitCodeProvider {
             )));
         }
 
-        let content_response: GitCodeContentResponse = self.handle_response(response).await?;
+        let content_response: GitCodeContentResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
 
         // Decode base64 content
         use base64::Engine;
@@ -323,7 +285,10 @@ impl ForgeProvider for GitCodeProvider {
             api_path = format!("{}?ref={}", api_path, urlencoding::encode(branch));
         }
 
-        let response = self.build_request(Method::GET, &api_path).send().await?;
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &api_path))
+            .await?;
 
         if response.status() == StatusCode::NOT_FOUND {
             return Err(VktError::ApiNotFound(format!(
@@ -332,7 +297,8 @@ impl ForgeProvider for GitCodeProvider {
             )));
         }
 
-        let content_response: GitCodeContentResponse = self.handle_response(response).await?;
+        let content_response: GitCodeContentResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
         Ok(content_response.into())
     }
 
@@ -345,56 +311,13 @@ impl ForgeProvider for GitCodeProvider {
         };
 
         let response = self
-            .build_request(Method::POST, &path)
-            .json(&body)
-            .send()
+            .transport
+            .send(self.build_request(Method::POST, &path).json(&body))
             .await?;
 
-        let branch_info: GitCodeBranchResponse = self.handle_response(response).await?;
-
-        // Extract commit info from nested structure
-        let commit_sha = branch_info
-            .commit
-            .sha
-            .or_else(|| branch_info.commit.id.clone())
-            .or_else(|| {
-                branch_info
-                    .commit
-                    .commit
-                    .as_ref()
-                    .and_then(|c| c.sha.clone())
-            })
-            .ok_or_else(|| {
-                VktError::Api("Could not extract commit SHA from branch response".to_string())
-            })?;
-
-        let commit_message = branch_info
-            .commit
-            .message
-            .or_else(|| {
-                branch_info
-                    .commit
-                    .commit
-                    .as_ref()
-                    .and_then(|c| c.message.clone())
-            })
-            .unwrap_or_default();
-
-        let authored_date = branch_info
-            .commit
-            .commit
-            .as_ref()
-            .and_then(|c| c.authored_date.clone());
-
-        Ok(Branch {
-            name: branch_info.name,
-            commit: Commit {
-                id: commit_sha,
-                message: commit_message,
-                author: None,
-                timestamp: authored_date,
-            },
-        })
+        let branch_info: GitCodeBranchResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
+        branch_info.try_into()
     }
 
     async fn create_or_update_file(
@@ -455,9 +378,13 @@ impl ForgeProvider for GitCodeProvider {
             Method::POST
         };
 
-        let response = self.build_request(method, &path).json(&body).send().await?;
+        let response = self
+            .transport
+            .send(self.build_request(method, &path).json(&body))
+            .await?;
 
-        let commit_response: GitCodeCommitResponse = self.handle_response(response).await?;
+        let commit_response: GitCodeCommitResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
         Ok(commit_response.into())
     }
 
@@ -477,16 +404,27 @@ impl ForgeProvider for GitCodeProvider {
         };
 
         let response = self
-            .build_request(Method::POST, &path)
-            .json(&body)
-            .send()
+            .transport
+            .send(self.build_request(Method::POST, &path).json(&body))
             .await?;
 
-        let pull_response: GitCodePullResponse = self.handle_response(response).await?;
+        let pull_response: GitCodePullResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
         Ok(pull_response.into())
     }
 
     async fn assign_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        if !self
+            .capabilities()
+            .await?
+            .supports(Capability::ReviewerAssignment)
+        {
+            tracing::warn!(
+                "Reviewer assignment is not supported by this GitCode instance, skipping"
+            );
+            return Ok(());
+        }
+
         // GitCode/Gitee uses different endpoints for reviewer assignment
         // Try both GitHub-style and GitLab-style endpoints
 
@@ -500,27 +438,43 @@ impl ForgeProvider for GitCodeProvider {
         };
 
         let response = self
-            .build_request(Method::POST, &github_path)
-            .json(&body)
-            .send()
+            .transport
+            .send(self.build_request(Method::POST, &github_path).json(&body))
             .await?;
 
-        if response.status().is_success() {
-            let _: GitCodePullResponse = self.handle_response(response).await?;
-            return Ok(());
+        // Instances without the endpoint answer 404; remember that so later calls skip it
+        if matches!(
+            response.status(),
+            StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+        ) {
+            self.reviewers_unsupported.store(true, Ordering::Relaxed);
+            return Err(VktError::ApiNotFound(
+                "this GitCode instance has no reviewer endpoint, assign reviewers in the web interface"
+                    .to_string(),
+            ));
         }
 
-        // If GitHub-style fails, this might need GitLab-style API
-        // For now, treat reviewer assignment as optional - PR is already created
-        println!("⚠️  Reviewer assignment endpoint not supported by this GitCode instance");
-        println!("   You can manually assign reviewers via the web interface");
+        let _: GitCodePullResponse = rest::handle_response(response, ERROR_FIELDS).await?;
         Ok(())
     }
 
+    async fn capabilities(&self) -> Result<Capabilities> {
+        // Contents are base64 JSON; reviewers are dropped once the endpoint answered 404
+        if self.reviewers_unsupported.load(Ordering::Relaxed) {
+            Ok(Capabilities::default())
+        } else {
+            Ok(Capabilities::new([Capability::ReviewerAssignment]))
+        }
+    }
+
     async fn get_repository_info(&self) -> Result<RepositoryInfo> {
         let path = format!("repos/{}/{}", self.owner, self.repo);
-        let response = self.build_request(Method::GET, &path).send().await?;
-        let repo_response: GitCodeRepoResponse = self.handle_response(response).await?;
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        let repo_response: GitCodeRepoResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
         Ok(repo_response.into())
     }
 
@@ -543,16 +497,40 @@ impl ForgeProvider for GitCodeProvider {
             api_path = format!("{}?{}", api_path, params.join("&"));
         }
 
-        let response = self.build_request(Method::GET, &api_path).send().await?;
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &api_path))
+            .await?;
 
         if response.status() == StatusCode::NOT_FOUND {
             return Ok(false);
         }
 
-        let paths: GitTreeResponse = self.handle_response(response).await?;
+        let paths: GitTreeResponse = rest::handle_response(response, ERROR_FIELDS).await?;
 
         // Check if the file path is in the returned list
-        Ok(paths.iter().any(|p| p.trim_end_matches('/') == file_path.trim_end_matches('/')))
+        Ok(paths
+            .iter()
+            .any(|p| p.trim_end_matches('/') == file_path.trim_end_matches('/')))
+    }
+
+    async fn check_permissions(
+        &self,
+        _head_branch: &str,
+        _base_branch: &str,
+    ) -> Result<PermissionReport> {
+        let path = format!("repos/{}/{}", self.owner, self.repo);
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        let repo_response: GitCodeRepoResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(repo_response.permission_report())
+    }
+
+    fn stale_since(&self) -> Option<DateTime<Utc>> {
+        self.transport.stale_since()
     }
 }
 
@@ -563,12 +541,13 @@ mod tests {
     fn create_test_provider() -> GitCodeProvider {
         // Create a minimal provider for testing process_paths logic
         GitCodeProvider {
-            client: Client::new(),
+            transport: Transport::new(reqwest::Client::new()),
             base_url: "https://api.gitcode.com/api/v5".to_string(),
             token: "test".to_string(),
             owner: "test".to_string(),
             repo: "test".to_string(),
             default_branch: "main".to_string(),
+            reviewers_unsupported: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -645,4 +624,131 @@ mod tests {
         let items = provider.process_paths(paths.clone(), None, true);
         assert_eq!(items.len(), 3);
     }
+
+    #[tokio::test]
+    async fn test_replay_create_branch_nested_commit_shape() {
+        let cassette = crate::api::Cassette::from_json(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/cassettes/gitcode_create_branch.json"
+        )))
+        .unwrap();
+        let provider = GitCodeProvider {
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            ..create_test_provider()
+        }
+        .with_transport(Transport::replay(cassette));
+
+        // POST /branches nests the SHA under commit.commit.sha
+        let branch = provider
+            .create_branch("feature/docs", "main")
+            .await
+            .unwrap();
+        assert_eq!(branch.name, "feature/docs");
+        assert_eq!(branch.commit.id, "5d1a0f3c9e8b7a6d5c4b3a29180f7e6d5c4b3a21");
+        assert_eq!(branch.commit.message, "Initial commit");
+
+        let missing = provider
+            .get_file_info("docs/missing.md", Some("feature/docs"))
+            .await;
+        assert!(matches!(missing, Err(VktError::ApiNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_failed_reviewer_probe_is_remembered_per_instance() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/pulls/7/requested_reviewers"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = GitCodeProvider {
+            base_url: server.uri(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            ..create_test_provider()
+        };
+        let reviewers = vec!["alice".to_string()];
+
+        let capabilities = provider.capabilities().await.unwrap();
+        assert!(capabilities.supports(Capability::ReviewerAssignment));
+        assert!(!capabilities.supports(Capability::Labels));
+
+        // The caller is told the request failed rather than seeing a silent success
+        let err = provider.assign_reviewers(7, &reviewers).await.unwrap_err();
+        assert!(err.is_not_found());
+        assert!(
+            !provider
+                .capabilities()
+                .await
+                .unwrap()
+                .supports(Capability::ReviewerAssignment)
+        );
+
+        // Clones share the finding, so the second call skips the endpoint
+        provider
+            .clone()
+            .assign_reviewers(7, &reviewers)
+            .await
+            .unwrap();
+
+        // A separate provider for the same host probes again
+        let other = GitCodeProvider {
+            base_url: server.uri(),
+            ..create_test_provider()
+        };
+        assert!(
+            other
+                .capabilities()
+                .await
+                .unwrap()
+                .supports(Capability::ReviewerAssignment)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_repository_tree_follows_link_pages() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let next = format!(
+            "<{}/repos/owner/repo/file_list?page=2>; rel=\"next\"",
+            server.uri()
+        );
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/file_list"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(["src/lib.rs"]))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/file_list"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("link", next.as_str())
+                    .set_body_json(["Cargo.toml", "src/main.rs"]),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+
+        let provider = GitCodeProvider {
+            base_url: server.uri(),
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            ..create_test_provider()
+        };
+        let items = provider
+            .list_repository_tree(None, true, None)
+            .await
+            .unwrap();
+        let paths: Vec<&str> = items.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["Cargo.toml", "src/main.rs", "src/lib.rs"]);
+    }
 }
@@ -6,32 +6,14 @@
 use serde::{Deserialize, Serialize};
 
 use crate::api::types::{
-    Author, Branch, Commit, FileCommitResponse, FileContent, FileInfo, PullRequest, PullRequestRef,
-    RepositoryInfo, RepositoryRef, TreeItem,
+    Author, Branch, Commit, FileCommitResponse, FileContent, FileInfo, Permission,
+    PermissionReport, PullRequest, PullRequestRef, RepositoryInfo, RepositoryRef, TreeItem,
 };
+use crate::error::{Result, VktError};
 
 /// Git tree listing response - file_list API returns a simple array of paths
 pub type GitTreeResponse = Vec<String>;
 
-/// Individual tree entry from GitCode API
-#[derive(Debug, Clone, Deserialize)]
-pub struct GitTreeItem {
-    /// Item path
-    pub path: String,
-    /// Item mode (e.g., "100644", "040000")
-    pub mode: String,
-    /// Item type ("blob" for file, "tree" for directory, "commit" for submodule)
-    #[serde(rename = "type")]
-    pub item_type: String,
-    /// Object SHA
-    pub sha: String,
-    /// Size in bytes (only for blobs)
-    pub size: Option<u64>,
-    /// API URL (optional, not always returned by GitCode)
-    #[serde(default)]
-    pub url: Option<String>,
-}
-
 /// Convert a file path string to TreeItem
 /// Determines type based on path (trailing slash = directory)
 pub fn path_to_tree_item(path: String) -> TreeItem {
@@ -161,6 +143,56 @@ impl From<GitRefResponse> for Branch {
     }
 }
 
+impl TryFrom<GitCodeBranchResponse> for Branch {
+    type Error = VktError;
+
+    fn try_from(branch_info: GitCodeBranchResponse) -> Result<Self> {
+        let nested_commit = branch_info.commit.commit.as_ref();
+
+        // Extract commit info from nested structure
+        let commit_sha = branch_info
+            .commit
+            .sha
+            .clone()
+            .or_else(|| branch_info.commit.id.clone())
+            .or_else(|| nested_commit.and_then(|c| c.sha.clone()))
+            .ok_or_else(|| {
+                VktError::Api("Could not extract commit SHA from branch response".to_string())
+            })?;
+
+        let commit_message = branch_info
+            .commit
+            .message
+            .clone()
+            .or_else(|| nested_commit.and_then(|c| c.message.clone()))
+            .unwrap_or_default();
+
+        let authored_date = nested_commit.and_then(|c| c.authored_date.clone());
+
+        Ok(Branch {
+            name: branch_info.name,
+            commit: Commit {
+                id: commit_sha,
+                message: commit_message,
+                author: None,
+                timestamp: authored_date,
+            },
+        })
+    }
+}
+
+/// Contents API response
+///
+/// The contents endpoint returns an object for files and an array for directories.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum GitCodeContents {
+    /// A single file
+    File(GitCodeContentResponse),
+    /// A directory listing
+    Dir(Vec<serde_json::Value>),
+}
+
 /// File content response from GitCode
 #[derive(Debug, Clone, Deserialize)]
 pub struct GitCodeContentResponse {
@@ -408,6 +440,12 @@ pub struct GitCodeRepoInfo {
     /// SSH URL (optional)
     #[serde(default)]
     pub ssh_url: Option<String>,
+    /// Access of the authenticated user (Gitea: `permissions`, Gitee: `permission`)
+    #[serde(default, alias = "permission")]
+    pub permissions: Option<GitCodeRepoPermissions>,
+    /// Pull requests enabled (Gitea: `has_pull_requests`, Gitee: `pull_requests_enabled`)
+    #[serde(default, alias = "pull_requests_enabled")]
+    pub has_pull_requests: Option<bool>,
 }
 
 /// Repository response wrapper
@@ -433,6 +471,58 @@ pub struct GitCodeRepoResponse {
     pub clone_url: String,
     /// SSH URL
     pub ssh_url: String,
+    /// Access of the authenticated user (Gitea: `permissions`, Gitee: `permission`)
+    #[serde(default, alias = "permission")]
+    pub permissions: Option<GitCodeRepoPermissions>,
+    /// Pull requests enabled (Gitea: `has_pull_requests`, Gitee: `pull_requests_enabled`)
+    #[serde(default, alias = "pull_requests_enabled")]
+    pub has_pull_requests: Option<bool>,
+}
+
+/// Repository permissions of the authenticated user
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitCodeRepoPermissions {
+    /// Can push (write access)
+    #[serde(default)]
+    pub push: bool,
+}
+
+/// Permission report from a repository's permission and pull request fields
+fn repo_permission_report(
+    full_name: &str,
+    permissions: Option<&GitCodeRepoPermissions>,
+    has_pull_requests: Option<bool>,
+) -> PermissionReport {
+    let mut report = PermissionReport::from_push_access(permissions.map(|p| p.push), full_name);
+    if has_pull_requests == Some(false) {
+        report.deny(
+            Permission::PullRequest,
+            format!("pull requests are disabled for {}", full_name),
+        );
+    }
+    report
+}
+
+impl GitCodeRepoResponse {
+    /// What the authenticated user may do in the repository
+    pub fn permission_report(&self) -> PermissionReport {
+        repo_permission_report(
+            &self.full_name,
+            self.permissions.as_ref(),
+            self.has_pull_requests,
+        )
+    }
+}
+
+impl GitCodeRepoInfo {
+    /// What the authenticated user may do in the repository
+    pub fn permission_report(&self) -> PermissionReport {
+        repo_permission_report(
+            &self.full_name,
+            self.permissions.as_ref(),
+            self.has_pull_requests,
+        )
+    }
 }
 
 impl From<GitCodePullResponse> for PullRequest {
@@ -514,6 +604,21 @@ impl From<GitCodeRepoResponse> for RepositoryInfo {
     }
 }
 
+impl From<GitCodeRepoInfo> for RepositoryInfo {
+    fn from(repo: GitCodeRepoInfo) -> Self {
+        RepositoryInfo {
+            id: repo.id,
+            full_name: repo.full_name,
+            description: repo.description,
+            default_branch: repo.default_branch.unwrap_or_default(),
+            private: repo.private,
+            html_url: repo.html_url,
+            clone_url: repo.clone_url,
+            ssh_url: repo.ssh_url,
+        }
+    }
+}
+
 /// Request body for creating a branch (GitCode/Gitee API)
 #[derive(Debug, Clone, Serialize)]
 pub struct CreateBranchRequest {
@@ -543,15 +648,6 @@ pub struct CreateFileRequest {
     pub author_email: Option<String>,
 }
 
-/// Committer information for requests
-#[derive(Debug, Clone, Serialize)]
-pub struct GitCodeCommitter {
-    /// Committer name
-    pub name: String,
-    /// Committer email
-    pub email: String,
-}
-
 /// Request body for creating a pull request
 #[derive(Debug, Clone, Serialize)]
 pub struct CreatePullRequest {
@@ -0,0 +1,720 @@
+//! Gitea/Forgejo Provider Implementation
+//!
+//! Implements the ForgeProvider trait for the Gitea REST API (v1), which is
+//! also served unchanged by Forgejo (e.g. Codeberg).
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use reqwest::{Method, StatusCode};
+
+use crate::api::gitcode::types::{
+    CreatePullRequest, GitCodeBranchResponse, GitCodeCommitResponse, GitCodeContents,
+    GitCodePullResponse, GitCodeRepoResponse, RequestReviewers,
+};
+use crate::api::github::types::{CreateFileRequest, GitHubAuthor, GitTreeResponse};
+use crate::api::pagination::{Cursor, Page, paginate};
+use crate::api::rest;
+use crate::api::traits::ForgeProvider;
+use crate::api::transport::Transport;
+use crate::api::types::{
+    Branch, Capabilities, Capability, FileCommitResponse, FileInfo, Permission, PermissionReport,
+    PullRequest, RepositoryInfo, TreeItem, branch_matches, filter_tree,
+};
+use crate::config::{Config, ProviderType};
+use crate::error::{Result, VktError};
+
+mod types;
+
+use types::{CreateBranchRequest, GiteaBranchProtection};
+
+/// Page size requested from the git trees API (the server may cap it lower)
+const TREE_PER_PAGE: u32 = 1000;
+
+/// Fields of a Gitea error body that may hold the message
+const ERROR_FIELDS: &[&str] = &["message"];
+
+/// Gitea/Forgejo API Provider
+#[derive(Debug, Clone)]
+pub struct GiteaProvider {
+    /// HTTP transport (live, recording or replaying)
+    transport: Transport,
+    /// Base API URL
+    base_url: String,
+    /// API token
+    token: String,
+    /// Repository owner
+    owner: String,
+    /// Repository name
+    repo: String,
+    /// Default branch name
+    default_branch: String,
+}
+
+impl GiteaProvider {
+    /// Create a new Gitea provider from configuration
+    pub fn new(config: &Config) -> Result<Self> {
+        let (owner, repo) = rest::parse_project_id(&config.repo.project_id)?;
+
+        let token = config.access_token()?;
+
+        Ok(Self {
+            transport: Transport::from_config(config)?.with_secret(&token),
+            base_url: config.remote.api_url.trim_end_matches('/').to_string(),
+            token,
+            owner,
+            repo,
+            default_branch: config.repo.default_branch.clone(),
+        })
+    }
+
+    /// Replace the HTTP transport (e.g. to replay a recorded cassette)
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = transport.with_secret(&self.token);
+        self
+    }
+
+    /// Build authenticated request
+    fn build_request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        let url = rest::build_url(&self.base_url, path);
+        let request = self.transport.request(method, &url);
+        rest::authorize(&ProviderType::Gitea, request, &self.token)
+            .header("Accept", "application/json")
+    }
+
+    /// Version reported by the instance, None if it does not tell
+    async fn server_version(&self) -> Option<String> {
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, "version"))
+            .await
+            .ok()?;
+        rest::handle_response::<rest::VersionResponse>(response, ERROR_FIELDS)
+            .await
+            .ok()
+            .map(|v| v.version)
+    }
+
+    /// Resolve a ref to a commit SHA for the trees API
+    ///
+    /// Branch names are looked up explicitly; anything else (tags, SHAs) is passed through.
+    async fn resolve_tree_sha(&self, tree_ref: &str) -> Result<String> {
+        let path = format!(
+            "repos/{}/{}/branches/{}",
+            self.owner,
+            self.repo,
+            rest::encode_path(tree_ref)
+        );
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        match rest::handle_response::<GitCodeBranchResponse>(response, ERROR_FIELDS).await {
+            Ok(branch) => Ok(Branch::try_from(branch)?.commit.id),
+            Err(e) if e.is_not_found() => Ok(tree_ref.to_string()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for GiteaProvider {
+    /// List repository tree items
+    /// https://gitea.com/api/swagger#/repository/GetTree
+    ///
+    /// Gitea paginates recursive trees and sets `truncated` while pages remain.
+    async fn list_repository_tree(
+        &self,
+        path: Option<&str>,
+        recursive: bool,
+        ref_branch: Option<&str>,
+    ) -> Result<Vec<TreeItem>> {
+        let sha = self
+            .resolve_tree_sha(ref_branch.unwrap_or(&self.default_branch))
+            .await?;
+
+        let base_path = format!(
+            "repos/{}/{}/git/trees/{}?recursive=true&per_page={}",
+            self.owner,
+            self.repo,
+            urlencoding::encode(&sha),
+            TREE_PER_PAGE
+        );
+        let base_path = &base_path;
+        let items: Vec<TreeItem> = paginate(Cursor::first(), |cursor| async move {
+            let response = self
+                .transport
+                .send(self.build_request(Method::GET, &cursor.apply(base_path)))
+                .await?;
+            let tree: GitTreeResponse = rest::handle_response(response, ERROR_FIELDS).await?;
+
+            let next = if tree.truncated {
+                cursor.next_page()
+            } else {
+                None
+            };
+            Ok(Page::new(
+                tree.tree.into_iter().map(TreeItem::from).collect(),
+                next,
+            ))
+        })
+        .try_collect()
+        .await?;
+
+        Ok(filter_tree(items, path, recursive))
+    }
+
+    /// Download raw file contents
+    /// https://gitea.com/api/swagger#/repository/repoGetRawFile
+    async fn get_file_content(&self, file_path: &str, ref_branch: Option<&str>) -> Result<Vec<u8>> {
+        let mut api_path = format!(
+            "repos/{}/{}/raw/{}",
+            self.owner,
+            self.repo,
+            rest::encode_path(file_path)
+        );
+
+        if let Some(branch) = ref_branch {
+            api_path = format!("{}?ref={}", api_path, urlencoding::encode(branch));
+        }
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &api_path))
+            .await?;
+        let response = rest::check_status(response, ERROR_FIELDS)
+            .await
+            .map_err(|e| {
+                if e.is_not_found() {
+                    VktError::ApiNotFound(format!("File not found: {}", file_path))
+                } else {
+                    e
+                }
+            })?;
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| VktError::Api(format!("Failed to read response body: {}", e)))?;
+        Ok(bytes.to_vec())
+    }
+
+    async fn get_file_info(&self, file_path: &str, ref_branch: Option<&str>) -> Result<FileInfo> {
+        let mut api_path = format!(
+            "repos/{}/{}/contents/{}",
+            self.owner,
+            self.repo,
+            rest::encode_path(file_path)
+        );
+
+        if let Some(branch) = ref_branch {
+            api_path = format!("{}?ref={}", api_path, urlencoding::encode(branch));
+        }
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &api_path))
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(VktError::ApiNotFound(format!(
+                "File not found: {}",
+                file_path
+            )));
+        }
+
+        match rest::handle_response(response, ERROR_FIELDS).await? {
+            GitCodeContents::File(content) => Ok(content.into()),
+            GitCodeContents::Dir(entries) => Err(VktError::ApiNotFound(format!(
+                "Not a file: {} is a directory with {} entries",
+                file_path,
+                entries.len()
+            ))),
+        }
+    }
+
+    /// Create a branch
+    /// https://gitea.com/api/swagger#/repository/repoCreateBranch
+    async fn create_branch(&self, branch_name: &str, source_branch: &str) -> Result<Branch> {
+        let path = format!("repos/{}/{}/branches", self.owner, self.repo);
+        let body = CreateBranchRequest {
+            new_branch_name: branch_name.to_string(),
+            old_branch_name: source_branch.to_string(),
+        };
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::POST, &path).json(&body))
+            .await?;
+
+        let branch_info: GitCodeBranchResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
+        branch_info.try_into()
+    }
+
+    /// Create or update a file (POST creates, PUT updates with the blob SHA)
+    /// https://gitea.com/api/swagger#/repository/repoCreateFile
+    async fn create_or_update_file(
+        &self,
+        file_path: &str,
+        content: &str,
+        branch: &str,
+        message: &str,
+        author_name: &str,
+        author_email: &str,
+    ) -> Result<FileCommitResponse> {
+        let path = format!(
+            "repos/{}/{}/contents/{}",
+            self.owner,
+            self.repo,
+            rest::encode_path(file_path)
+        );
+
+        let existing_sha = match self.get_file_info(file_path, Some(branch)).await {
+            Ok(file_info) => file_info.sha,
+            Err(e) if e.is_not_found() => None,
+            Err(e) => return Err(e),
+        };
+        let method = if existing_sha.is_some() {
+            Method::PUT
+        } else {
+            Method::POST
+        };
+
+        let identity = GitHubAuthor {
+            name: author_name.to_string(),
+            email: author_email.to_string(),
+            date: None,
+        };
+        let body = CreateFileRequest {
+            message: message.to_string(),
+            content: content.to_string(),
+            branch: branch.to_string(),
+            sha: existing_sha,
+            author: identity.clone(),
+            committer: identity,
+        };
+
+        let response = self
+            .transport
+            .send(self.build_request(method, &path).json(&body))
+            .await?;
+
+        let commit_response: GitCodeCommitResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(commit_response.into())
+    }
+
+    /// Create a pull request
+    /// https://gitea.com/api/swagger#/repository/repoCreatePullRequest
+    async fn create_pull_request(
+        &self,
+        title: &str,
+        head_branch: &str,
+        base_branch: &str,
+        body: Option<&str>,
+    ) -> Result<PullRequest> {
+        let path = format!("repos/{}/{}/pulls", self.owner, self.repo);
+        let body = CreatePullRequest {
+            title: title.to_string(),
+            head: head_branch.to_string(),
+            base: base_branch.to_string(),
+            body: body.map(|s| s.to_string()),
+        };
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::POST, &path).json(&body))
+            .await?;
+
+        let pull_response: GitCodePullResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(pull_response.into())
+    }
+
+    /// Request reviews from the given users
+    /// https://gitea.com/api/swagger#/repository/repoCreatePullReviewRequests
+    async fn assign_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        if reviewers.is_empty() {
+            return Ok(());
+        }
+
+        let path = format!(
+            "repos/{}/{}/pulls/{}/requested_reviewers",
+            self.owner, self.repo, pr_number
+        );
+        let body = RequestReviewers {
+            reviewers: reviewers.to_vec(),
+        };
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::POST, &path).json(&body))
+            .await?;
+
+        rest::check_status(response, ERROR_FIELDS).await?;
+        Ok(())
+    }
+
+    async fn capabilities(&self) -> Result<Capabilities> {
+        // Files come from the /raw endpoint; reviewer requests arrived in Gitea 1.14
+        let capabilities =
+            Capabilities::new([Capability::ReviewerAssignment, Capability::RawDownloads]);
+        let Some(version) = self.server_version().await else {
+            return Ok(capabilities);
+        };
+        // Forgejo reports its own version with the Gitea one appended ("7.0.0+gitea-1.21.11")
+        let gitea = version
+            .split_once("gitea-")
+            .map_or(version.as_str(), |(_, gitea)| gitea);
+        if rest::parse_version(gitea).is_some_and(|v| v < (1, 14)) {
+            Ok(capabilities.without(Capability::ReviewerAssignment))
+        } else {
+            Ok(capabilities)
+        }
+    }
+
+    async fn get_repository_info(&self) -> Result<RepositoryInfo> {
+        let path = format!("repos/{}/{}", self.owner, self.repo);
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        let repo_response: GitCodeRepoResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(repo_response.into())
+    }
+
+    async fn check_permissions(
+        &self,
+        head_branch: &str,
+        _base_branch: &str,
+    ) -> Result<PermissionReport> {
+        let path = format!("repos/{}/{}", self.owner, self.repo);
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        let repo_response: GitCodeRepoResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
+        let mut report = repo_response.permission_report();
+
+        let path = format!("repos/{}/{}/branch_protections", self.owner, self.repo);
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        match rest::handle_response::<Vec<GiteaBranchProtection>>(response, ERROR_FIELDS).await {
+            Ok(rules) => {
+                if let Some(rule) = rules
+                    .iter()
+                    .find(|rule| !rule.enable_push && branch_matches(rule.pattern(), head_branch))
+                {
+                    report.deny(
+                        Permission::CreateBranch,
+                        format!(
+                            "{} is protected by '{}', which disables pushing",
+                            head_branch,
+                            rule.pattern()
+                        ),
+                    );
+                }
+            }
+            // Listing branch protections needs admin access
+            Err(e) => tracing::debug!("Branch protections unavailable: {}", e),
+        }
+        Ok(report)
+    }
+
+    fn stale_since(&self) -> Option<DateTime<Utc>> {
+        self.transport.stale_since()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn create_test_config(base_url: String) -> Config {
+        let mut config = Config::for_tests("Gitea", &base_url);
+        config.repo.project_id = "lab/scripts".to_string();
+        config
+    }
+
+    async fn create_test_provider() -> (MockServer, GiteaProvider) {
+        let server = MockServer::start().await;
+        let provider =
+            GiteaProvider::new(&create_test_config(format!("{}/api/v1", server.uri()))).unwrap();
+        (server, provider)
+    }
+
+    async fn mount_main_branch(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/api/v1/repos/lab/scripts/branches/main"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "name": "main",
+                "commit": {"id": "c0ffee", "message": "init"}
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_list_repository_tree_paginates() {
+        let (server, provider) = create_test_provider().await;
+        mount_main_branch(&server).await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/repos/lab/scripts/git/trees/c0ffee"))
+            .and(query_param("page", "1"))
+            .and(header("Authorization", "token test-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "sha": "c0ffee",
+                "truncated": true,
+                "tree": [
+                    {"path": "qemu", "mode": "040000", "type": "tree", "sha": "t1"},
+                    {"path": "qemu/run.sh", "mode": "100755", "type": "blob", "sha": "b1"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/repos/lab/scripts/git/trees/c0ffee"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "sha": "c0ffee",
+                "truncated": false,
+                "tree": [
+                    {"path": "README.md", "mode": "100644", "type": "blob", "sha": "b2"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let root = provider
+            .list_repository_tree(None, false, None)
+            .await
+            .unwrap();
+        assert_eq!(root.len(), 2);
+
+        let all = provider
+            .list_repository_tree(None, true, None)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_list_repository_tree_with_sha_ref() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/repos/lab/scripts/git/trees/abc123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "sha": "abc123", "truncated": false, "tree": []
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let items = provider
+            .list_repository_tree(None, false, Some("abc123"))
+            .await
+            .unwrap();
+        assert!(items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_file_content_raw() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/repos/lab/scripts/raw/qemu/run.sh"))
+            .and(query_param("ref", "main"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"qemu-system\n".to_vec()))
+            .mount(&server)
+            .await;
+
+        let content = provider
+            .get_file_content("qemu/run.sh", Some("main"))
+            .await
+            .unwrap();
+        assert_eq!(content, b"qemu-system\n");
+    }
+
+    #[tokio::test]
+    async fn test_create_branch() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/repos/lab/scripts/branches"))
+            .and(body_partial_json(serde_json::json!({
+                "new_branch_name": "feat/x",
+                "old_branch_name": "main"
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "name": "feat/x",
+                "commit": {"id": "c0ffee", "message": "init\n"}
+            })))
+            .mount(&server)
+            .await;
+
+        let branch = provider.create_branch("feat/x", "main").await.unwrap();
+        assert_eq!(branch.name, "feat/x");
+        assert_eq!(branch.commit.id, "c0ffee");
+    }
+
+    #[tokio::test]
+    async fn test_update_existing_file_uses_put_with_sha() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/repos/lab/scripts/contents/qemu/run.sh"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "type": "file",
+                "encoding": "base64",
+                "size": 2,
+                "name": "run.sh",
+                "path": "qemu/run.sh",
+                "content": "aGk=",
+                "sha": "oldblob",
+                "url": "http://x",
+                "download_url": "http://x/raw"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/api/v1/repos/lab/scripts/contents/qemu/run.sh"))
+            .and(body_partial_json(serde_json::json!({
+                "sha": "oldblob",
+                "branch": "feat/x",
+                "committer": {"name": "Test", "email": "test@example.com"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": {
+                    "name": "run.sh", "path": "qemu/run.sh", "sha": "newblob",
+                    "size": 3, "html_url": "http://x/run.sh"
+                },
+                "commit": {
+                    "sha": "c1",
+                    "message": "update",
+                    "author": {"name": "Test", "email": "test@example.com", "date": "2024-01-01T00:00:00Z"},
+                    "committer": {"name": "Test", "email": "test@example.com", "date": "2024-01-01T00:00:00Z"}
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let response = provider
+            .create_or_update_file(
+                "qemu/run.sh",
+                "aGkh",
+                "feat/x",
+                "update",
+                "Test",
+                "test@example.com",
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.commit.id, "c1");
+        assert_eq!(response.content.sha, "newblob");
+    }
+
+    #[tokio::test]
+    async fn test_create_pull_request_and_reviewers() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/repos/lab/scripts/pulls"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "number": 5,
+                "title": "[T] add",
+                "state": "open",
+                "html_url": "https://codeberg.org/lab/scripts/pulls/5",
+                "head": {"ref": "feat/x", "sha": "c1"},
+                "base": {"ref": "main", "sha": "c0ffee"}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(
+                "/api/v1/repos/lab/scripts/pulls/5/requested_reviewers",
+            ))
+            .and(body_partial_json(
+                serde_json::json!({"reviewers": ["alice"]}),
+            ))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let pr = provider
+            .create_pull_request("[T] add", "feat/x", "main", None)
+            .await
+            .unwrap();
+        assert_eq!(pr.number, 5);
+        assert_eq!(pr.base.unwrap().ref_branch, "main");
+        provider
+            .assign_reviewers(5, &["alice".to_string()])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_follow_server_version() {
+        let (server, provider) = create_test_provider().await;
+        // No /version endpoint: assume a current instance
+        let capabilities = provider.capabilities().await.unwrap();
+        assert!(capabilities.supports(Capability::ReviewerAssignment));
+        assert!(capabilities.supports(Capability::RawDownloads));
+        assert!(!capabilities.supports(Capability::Labels));
+        assert!(!capabilities.supports(Capability::AutoMerge));
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/version"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"version": "1.13.7"})),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/version"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"version": "7.0.0+gitea-1.21.11"})),
+            )
+            .mount(&server)
+            .await;
+
+        let old = provider.capabilities().await.unwrap();
+        assert!(!old.supports(Capability::ReviewerAssignment));
+        assert!(old.supports(Capability::RawDownloads));
+
+        // Forgejo 7 is judged by the Gitea version it is based on
+        let forgejo = provider.capabilities().await.unwrap();
+        assert!(forgejo.supports(Capability::ReviewerAssignment));
+    }
+
+    #[tokio::test]
+    async fn test_error_mapping() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/repos/lab/scripts/branches"))
+            .respond_with(ResponseTemplate::new(409).set_body_json(serde_json::json!({
+                "message": "The branch already exists.",
+                "url": "https://codeberg.org/api/swagger"
+            })))
+            .mount(&server)
+            .await;
+        let err = provider.create_branch("feat/x", "main").await.unwrap_err();
+        assert!(matches!(err, VktError::Conflict(ref m) if m == "The branch already exists."));
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/repos/lab/scripts"))
+            .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+                "message": "token does not have at least one of required scope(s)"
+            })))
+            .mount(&server)
+            .await;
+        let err = provider.get_repository_info().await.unwrap_err();
+        assert!(matches!(err, VktError::PermissionDenied(_)));
+    }
+}
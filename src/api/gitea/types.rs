@@ -0,0 +1,40 @@
+//! Gitea/Forgejo API Types
+//!
+//! Gitea's v1 API returns GitHub-shaped payloads for contents, commits, pulls
+//! and repositories, so the provider reuses the GitCode response types and the
+//! GitHub tree/file request types. Only the Gitea-specific payloads live here.
+
+use serde::{Deserialize, Serialize};
+
+/// Request body for creating a branch (POST /repos/{owner}/{repo}/branches)
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateBranchRequest {
+    /// New branch name
+    pub new_branch_name: String,
+    /// Branch to fork from
+    pub old_branch_name: String,
+}
+
+/// Branch protection rule (GET /repos/{owner}/{repo}/branch_protections)
+#[derive(Debug, Clone, Deserialize)]
+pub struct GiteaBranchProtection {
+    /// Branch name or glob pattern (Gitea 1.19+)
+    #[serde(default)]
+    pub rule_name: Option<String>,
+    /// Branch name (older releases)
+    #[serde(default)]
+    pub branch_name: String,
+    /// Whether anyone (or the push whitelist) may push to matching branches
+    #[serde(default)]
+    pub enable_push: bool,
+}
+
+impl GiteaBranchProtection {
+    /// Branch name or pattern the rule applies to
+    pub fn pattern(&self) -> &str {
+        self.rule_name.as_deref().unwrap_or(&self.branch_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {}
@@ -0,0 +1,519 @@
+//! Gitee Provider Implementation
+//!
+//! Implements the ForgeProvider trait for the Gitee REST API (v5).
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{Method, StatusCode};
+
+use crate::api::gitcode::types::{
+    CreateBranchRequest, CreateFileRequest, CreatePullRequest, GitCodeBranchResponse,
+    GitCodeCommitResponse, GitCodeContents, GitCodePullResponse, GitCodeRepoInfo,
+};
+use crate::api::github::types::GitTreeResponse;
+use crate::api::rest;
+use crate::api::traits::ForgeProvider;
+use crate::api::transport::Transport;
+use crate::api::types::{
+    Branch, Capabilities, Capability, FileCommitResponse, FileInfo, PermissionReport, PullRequest,
+    RepositoryInfo, TreeItem, filter_tree,
+};
+use crate::config::{Config, ProviderType};
+use crate::error::{Result, VktError};
+
+mod types;
+
+use types::AssigneesRequest;
+
+/// Fields of a Gitee error body that may hold the message
+const ERROR_FIELDS: &[&str] = &["message"];
+
+/// Gitee API Provider
+#[derive(Debug, Clone)]
+pub struct GiteeProvider {
+    /// HTTP transport (live, recording or replaying)
+    transport: Transport,
+    /// Base API URL
+    base_url: String,
+    /// API token
+    token: String,
+    /// Repository owner
+    owner: String,
+    /// Repository name
+    repo: String,
+    /// Default branch name
+    default_branch: String,
+}
+
+impl GiteeProvider {
+    /// Create a new Gitee provider from configuration
+    pub fn new(config: &Config) -> Result<Self> {
+        let (owner, repo) = rest::parse_project_id(&config.repo.project_id)?;
+
+        let token = config.access_token()?;
+
+        Ok(Self {
+            transport: Transport::from_config(config)?.with_secret(&token),
+            base_url: config.remote.api_url.trim_end_matches('/').to_string(),
+            token,
+            owner,
+            repo,
+            default_branch: config.repo.default_branch.clone(),
+        })
+    }
+
+    /// Replace the HTTP transport (e.g. to replay a recorded cassette)
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = transport.with_secret(&self.token);
+        self
+    }
+
+    /// Build authenticated request
+    ///
+    /// Gitee takes the token as an `access_token` query parameter.
+    fn build_request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        let url = rest::build_url(&self.base_url, path);
+        let request = self.transport.request(method, &url);
+        rest::authorize(&ProviderType::Gitee, request, &self.token)
+            .header("Accept", "application/json")
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for GiteeProvider {
+    /// List repository tree items
+    /// https://gitee.com/api/v5/swagger#/getV5ReposOwnerRepoGitTreesSha
+    ///
+    /// The trees endpoint accepts a branch name, tag or commit SHA directly.
+    async fn list_repository_tree(
+        &self,
+        path: Option<&str>,
+        recursive: bool,
+        ref_branch: Option<&str>,
+    ) -> Result<Vec<TreeItem>> {
+        let tree_ref = ref_branch.unwrap_or(&self.default_branch);
+        let api_path = format!(
+            "repos/{}/{}/git/trees/{}?recursive=1",
+            self.owner,
+            self.repo,
+            urlencoding::encode(tree_ref)
+        );
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &api_path))
+            .await?;
+        let tree: GitTreeResponse = rest::handle_response(response, ERROR_FIELDS).await?;
+
+        if tree.truncated {
+            tracing::warn!("Gitee truncated the tree listing for {}", tree_ref);
+        }
+
+        let items = tree.tree.into_iter().map(TreeItem::from).collect();
+        Ok(filter_tree(items, path, recursive))
+    }
+
+    async fn get_file_content(&self, file_path: &str, ref_branch: Option<&str>) -> Result<Vec<u8>> {
+        let info = self.get_file_info(file_path, ref_branch).await?;
+
+        // Decode base64 content
+        use base64::Engine;
+        let encoded: String = info
+            .content
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| VktError::Api(format!("Failed to decode file content: {}", e)))
+    }
+
+    /// Get file metadata and base64 content
+    /// https://gitee.com/api/v5/swagger#/getV5ReposOwnerRepoContents(Path)
+    async fn get_file_info(&self, file_path: &str, ref_branch: Option<&str>) -> Result<FileInfo> {
+        let mut api_path = format!(
+            "repos/{}/{}/contents/{}",
+            self.owner,
+            self.repo,
+            rest::encode_path(file_path)
+        );
+
+        if let Some(branch) = ref_branch {
+            api_path = format!("{}?ref={}", api_path, urlencoding::encode(branch));
+        }
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &api_path))
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(VktError::ApiNotFound(format!(
+                "File not found: {}",
+                file_path
+            )));
+        }
+
+        match rest::handle_response(response, ERROR_FIELDS).await? {
+            GitCodeContents::File(content) => Ok(content.into()),
+            // Gitee answers with an empty array for missing paths as well as directories
+            GitCodeContents::Dir(entries) if entries.is_empty() => Err(VktError::ApiNotFound(
+                format!("File not found: {}", file_path),
+            )),
+            GitCodeContents::Dir(entries) => Err(VktError::ApiNotFound(format!(
+                "Not a file: {} is a directory with {} entries",
+                file_path,
+                entries.len()
+            ))),
+        }
+    }
+
+    /// Create a branch
+    /// https://gitee.com/api/v5/swagger#/postV5ReposOwnerRepoBranches
+    async fn create_branch(&self, branch_name: &str, source_branch: &str) -> Result<Branch> {
+        let path = format!("repos/{}/{}/branches", self.owner, self.repo);
+        let body = CreateBranchRequest {
+            branch_name: branch_name.to_string(),
+            refs: source_branch.to_string(),
+        };
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::POST, &path).json(&body))
+            .await?;
+
+        let branch_info: GitCodeBranchResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
+        branch_info.try_into()
+    }
+
+    /// Create or update a file (POST creates, PUT updates with the blob SHA)
+    /// https://gitee.com/api/v5/swagger#/postV5ReposOwnerRepoContentsPath
+    ///
+    /// The body is form-encoded so the `author[name]`/`author[email]` keys are
+    /// parsed as a nested author object.
+    async fn create_or_update_file(
+        &self,
+        file_path: &str,
+        content: &str,
+        branch: &str,
+        message: &str,
+        author_name: &str,
+        author_email: &str,
+    ) -> Result<FileCommitResponse> {
+        let path = format!(
+            "repos/{}/{}/contents/{}",
+            self.owner,
+            self.repo,
+            rest::encode_path(file_path)
+        );
+
+        let existing_sha = match self.get_file_info(file_path, Some(branch)).await {
+            Ok(file_info) => file_info.sha,
+            Err(e) if e.is_not_found() => None,
+            Err(e) => return Err(e),
+        };
+        let method = if existing_sha.is_some() {
+            Method::PUT
+        } else {
+            Method::POST
+        };
+
+        let body = CreateFileRequest {
+            message: message.to_string(),
+            content: content.to_string(),
+            branch: branch.to_string(),
+            sha: existing_sha,
+            author_name: Some(author_name.to_string()),
+            author_email: Some(author_email.to_string()),
+        };
+
+        let response = self
+            .transport
+            .send(self.build_request(method, &path).form(&body))
+            .await?;
+
+        let commit_response: GitCodeCommitResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(commit_response.into())
+    }
+
+    /// Create a pull request
+    /// https://gitee.com/api/v5/swagger#/postV5ReposOwnerRepoPulls
+    async fn create_pull_request(
+        &self,
+        title: &str,
+        head_branch: &str,
+        base_branch: &str,
+        body: Option<&str>,
+    ) -> Result<PullRequest> {
+        let path = format!("repos/{}/{}/pulls", self.owner, self.repo);
+        let body = CreatePullRequest {
+            title: title.to_string(),
+            head: head_branch.to_string(),
+            base: base_branch.to_string(),
+            body: body.map(|s| s.to_string()),
+        };
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::POST, &path).json(&body))
+            .await?;
+
+        let pull_response: GitCodePullResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(pull_response.into())
+    }
+
+    /// Assign reviewers to a pull request
+    /// https://gitee.com/api/v5/swagger#/postV5ReposOwnerRepoPullsNumberAssignees
+    async fn assign_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        if reviewers.is_empty() {
+            return Ok(());
+        }
+
+        let path = format!(
+            "repos/{}/{}/pulls/{}/assignees",
+            self.owner, self.repo, pr_number
+        );
+        let body = AssigneesRequest {
+            assignees: reviewers.join(","),
+        };
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::POST, &path).json(&body))
+            .await?;
+
+        rest::check_status(response, ERROR_FIELDS).await?;
+        Ok(())
+    }
+
+    async fn capabilities(&self) -> Result<Capabilities> {
+        // Contents are base64 JSON; reviewers are set through the assignees endpoint
+        Ok(Capabilities::new([Capability::ReviewerAssignment]))
+    }
+
+    async fn get_repository_info(&self) -> Result<RepositoryInfo> {
+        let path = format!("repos/{}/{}", self.owner, self.repo);
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        let repo_info: GitCodeRepoInfo = rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(repo_info.into())
+    }
+
+    async fn check_permissions(
+        &self,
+        _head_branch: &str,
+        _base_branch: &str,
+    ) -> Result<PermissionReport> {
+        let path = format!("repos/{}/{}", self.owner, self.repo);
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        let repo_info: GitCodeRepoInfo = rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(repo_info.permission_report())
+    }
+
+    fn stale_since(&self) -> Option<DateTime<Utc>> {
+        self.transport.stale_since()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, body_string_contains, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn create_test_config(base_url: String) -> Config {
+        let mut config = Config::for_tests("Gitee", &base_url);
+        config.repo.project_id = "lab/scripts".to_string();
+        config.repo.default_branch = "master".to_string();
+        config
+    }
+
+    async fn create_test_provider() -> (MockServer, GiteeProvider) {
+        let server = MockServer::start().await;
+        let provider =
+            GiteeProvider::new(&create_test_config(format!("{}/api/v5", server.uri()))).unwrap();
+        (server, provider)
+    }
+
+    fn content_json(sha: &str) -> serde_json::Value {
+        serde_json::json!({
+            "type": "file",
+            "encoding": "base64",
+            "size": 12,
+            "name": "run.sh",
+            "path": "qemu/run.sh",
+            "content": "cWVtdS1zeXN0\nZW0K",
+            "sha": sha,
+            "url": "https://gitee.com/api/v5/repos/lab/scripts/contents/qemu/run.sh",
+            "download_url": "https://gitee.com/lab/scripts/raw/master/qemu/run.sh"
+        })
+    }
+
+    #[tokio::test]
+    async fn test_list_repository_tree_uses_access_token() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v5/repos/lab/scripts/git/trees/master"))
+            .and(query_param("recursive", "1"))
+            .and(query_param("access_token", "test-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "sha": "c0ffee",
+                "truncated": false,
+                "tree": [
+                    {"path": "qemu", "mode": "040000", "type": "tree", "sha": "t1"},
+                    {"path": "qemu/run.sh", "mode": "100755", "type": "blob", "sha": "b1", "size": 12}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let items = provider
+            .list_repository_tree(Some("qemu"), false, None)
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "run.sh");
+    }
+
+    #[tokio::test]
+    async fn test_get_file_content_decodes_base64() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v5/repos/lab/scripts/contents/qemu/run.sh"))
+            .and(query_param("ref", "master"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(content_json("b1")))
+            .mount(&server)
+            .await;
+
+        let content = provider
+            .get_file_content("qemu/run.sh", Some("master"))
+            .await
+            .unwrap();
+        assert_eq!(content, b"qemu-system\n");
+    }
+
+    #[tokio::test]
+    async fn test_missing_file_is_not_found() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v5/repos/lab/scripts/contents/nope.sh"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&server)
+            .await;
+
+        let err = provider.get_file_info("nope.sh", None).await.unwrap_err();
+        assert!(err.is_not_found());
+        assert!(!provider.file_exists("nope.sh", None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_create_file_posts_form_with_author() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v5/repos/lab/scripts/contents/new.sh"))
+            .and(body_string_contains("author%5Bname%5D=Test"))
+            .and(body_string_contains("branch=feat%2Fx"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "content": {
+                    "name": "new.sh", "path": "new.sh", "sha": "newblob",
+                    "size": 3, "html_url": "https://gitee.com/lab/scripts/blob/feat/x/new.sh"
+                },
+                "commit": {
+                    "sha": "c1",
+                    "message": "add",
+                    "author": {"name": "Test", "email": "test@example.com", "date": "2024-01-01T00:00:00+08:00"},
+                    "committer": {"name": "Gitee", "email": "noreply@gitee.com", "date": "2024-01-01T00:00:00+08:00"}
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let response = provider
+            .create_or_update_file(
+                "new.sh",
+                "aGkh",
+                "feat/x",
+                "add",
+                "Test",
+                "test@example.com",
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.commit.id, "c1");
+    }
+
+    #[tokio::test]
+    async fn test_create_branch_and_assign_reviewers() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v5/repos/lab/scripts/branches"))
+            .and(body_partial_json(serde_json::json!({
+                "branch_name": "feat/x",
+                "refs": "master"
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "name": "feat/x",
+                "commit": {
+                    "sha": "c0ffee",
+                    "commit": {"message": "init"}
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v5/repos/lab/scripts/pulls/3/assignees"))
+            .and(body_partial_json(
+                serde_json::json!({"assignees": "alice,bob"}),
+            ))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let branch = provider.create_branch("feat/x", "master").await.unwrap();
+        assert_eq!(branch.commit.id, "c0ffee");
+        assert_eq!(branch.commit.message, "init");
+
+        provider
+            .assign_reviewers(3, &["alice".to_string(), "bob".to_string()])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_error_mapping() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v5/repos/lab/scripts/branches"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "message": "分支名已存在"
+            })))
+            .mount(&server)
+            .await;
+        let err = provider
+            .create_branch("feat/x", "master")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VktError::Conflict(_)));
+
+        Mock::given(method("GET"))
+            .and(path("/api/v5/repos/lab/scripts"))
+            .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+                "message": "API rate limit exceeded"
+            })))
+            .mount(&server)
+            .await;
+        let err = provider.get_repository_info().await.unwrap_err();
+        assert!(matches!(err, VktError::RateLimited(_)));
+    }
+}
@@ -0,0 +1,17 @@
+//! Gitee API Types
+//!
+//! Gitee's v5 API shares its response shapes with GitCode, so the provider
+//! reuses the GitCode types. Only the Gitee-specific request bodies live here.
+
+use serde::Serialize;
+
+/// Request body for assigning pull request reviewers
+/// (POST /repos/{owner}/{repo}/pulls/{number}/assignees)
+#[derive(Debug, Clone, Serialize)]
+pub struct AssigneesRequest {
+    /// Comma-separated usernames
+    pub assignees: String,
+}
+
+#[cfg(test)]
+mod tests {}
@@ -0,0 +1,974 @@
+//! GitHub Provider Implementation
+//!
+//! Implements the ForgeProvider trait for the GitHub REST API (v3).
+//! Works with both github.com and GitHub Enterprise Server (`https://host/api/v3`).
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{Method, StatusCode};
+
+use crate::api::rest;
+use crate::api::traits::ForgeProvider;
+use crate::api::transport::Transport;
+use crate::api::types::{
+    Branch, Capabilities, Capability, FileCommitResponse, FileInfo, Permission, PermissionReport,
+    PullRequest, RepositoryInfo, TreeItem, filter_tree,
+};
+use crate::config::{Config, ProviderType};
+use crate::error::{Result, VktError};
+
+pub(crate) mod types;
+
+use types::{
+    CreateFileRequest, CreatePullRequest, CreateRefRequest, GitHubAuthor, GitHubBranchRule,
+    GitHubCommitResponse, GitHubContents, GitHubPullResponse, GitHubRepoResponse, GitRefResponse,
+    GitTreeResponse, RequestReviewers,
+};
+
+/// Media type for JSON responses
+const ACCEPT_JSON: &str = "application/vnd.github+json";
+
+/// Media type for raw file contents
+const ACCEPT_RAW: &str = "application/vnd.github.raw+json";
+
+/// REST API version pinned by the provider
+const API_VERSION: &str = "2022-11-28";
+
+/// Fields of a GitHub error body that may hold the message (details come from `errors`)
+const ERROR_FIELDS: &[&str] = &["message"];
+
+/// GitHub API Provider
+#[derive(Debug, Clone)]
+pub struct GitHubProvider {
+    /// HTTP transport (live, recording or replaying)
+    transport: Transport,
+    /// Base API URL
+    base_url: String,
+    /// API token
+    token: String,
+    /// Repository owner
+    owner: String,
+    /// Repository name
+    repo: String,
+    /// Default branch name
+    default_branch: String,
+}
+
+impl GitHubProvider {
+    /// Create a new GitHub provider from configuration
+    pub fn new(config: &Config) -> Result<Self> {
+        let (owner, repo) = rest::parse_project_id(&config.repo.project_id)?;
+
+        let token = config.access_token()?;
+
+        Ok(Self {
+            transport: Transport::from_config(config)?.with_secret(&token),
+            base_url: config.remote.api_url.trim_end_matches('/').to_string(),
+            token,
+            owner,
+            repo,
+            default_branch: config.repo.default_branch.clone(),
+        })
+    }
+
+    /// Replace the HTTP transport (e.g. to replay a recorded cassette)
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = transport.with_secret(&self.token);
+        self
+    }
+
+    /// Build authenticated request
+    fn build_request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        self.build_request_with_accept(method, path, ACCEPT_JSON)
+    }
+
+    /// Build authenticated request with a specific media type
+    fn build_request_with_accept(
+        &self,
+        method: Method,
+        path: &str,
+        accept: &str,
+    ) -> reqwest::RequestBuilder {
+        let url = rest::build_url(&self.base_url, path);
+        let request = self.transport.request(method, &url);
+        rest::authorize(&ProviderType::GitHub, request, &self.token)
+            .header("Accept", accept)
+            .header("X-GitHub-Api-Version", API_VERSION)
+    }
+
+    /// Resolve a branch name to its head commit SHA
+    async fn resolve_branch_sha(&self, branch: &str) -> Result<String> {
+        let path = format!(
+            "repos/{}/{}/git/ref/heads/{}",
+            self.owner,
+            self.repo,
+            rest::encode_path(branch)
+        );
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        let git_ref: GitRefResponse = rest::handle_response(response, ERROR_FIELDS)
+            .await
+            .map_err(|e| {
+                if e.is_not_found() {
+                    VktError::ApiNotFound(format!("Branch not found: {}", branch))
+                } else {
+                    e
+                }
+            })?;
+        Ok(git_ref.object.sha)
+    }
+
+    /// Fetch one tree by ref or SHA, with all its descendants if `recursive`
+    async fn get_tree(&self, tree_sha: &str, recursive: bool) -> Result<GitTreeResponse> {
+        let mut api_path = format!(
+            "repos/{}/{}/git/trees/{}",
+            self.owner,
+            self.repo,
+            urlencoding::encode(tree_sha)
+        );
+        if recursive {
+            api_path.push_str("?recursive=1");
+        }
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &api_path))
+            .await?;
+        rest::handle_response(response, ERROR_FIELDS).await
+    }
+
+    /// List a tree one directory at a time
+    ///
+    /// Used when GitHub truncates the recursive listing of a large
+    /// repository. Fails if even a single directory is too large to list.
+    async fn walk_tree(
+        &self,
+        tree_ref: &str,
+        path: Option<&str>,
+        recursive: bool,
+    ) -> Result<Vec<TreeItem>> {
+        // Descend to the requested directory first
+        let mut sha = tree_ref.to_string();
+        let mut prefix = String::new();
+        for segment in path
+            .unwrap_or_default()
+            .split('/')
+            .filter(|s| !s.is_empty())
+        {
+            let tree = self.complete_tree(&sha).await?;
+            let Some(entry) = tree
+                .tree
+                .into_iter()
+                .find(|item| item.path == segment && item.item_type == "tree")
+            else {
+                return Ok(Vec::new());
+            };
+            sha = entry.sha;
+            prefix = join_path(&prefix, segment);
+        }
+
+        let mut items = Vec::new();
+        let mut pending = vec![(sha, prefix)];
+        while let Some((sha, prefix)) = pending.pop() {
+            for mut item in self.complete_tree(&sha).await?.tree {
+                item.path = join_path(&prefix, &item.path);
+                if recursive && item.item_type == "tree" {
+                    pending.push((item.sha.clone(), item.path.clone()));
+                }
+                items.push(TreeItem::from(item));
+            }
+        }
+        Ok(items)
+    }
+
+    /// Fetch the direct entries of a tree, failing if GitHub cuts them off
+    async fn complete_tree(&self, tree_sha: &str) -> Result<GitTreeResponse> {
+        let tree = self.get_tree(tree_sha, false).await?;
+        if tree.truncated {
+            return Err(VktError::Api(format!(
+                "Tree {} of {}/{} is too large for GitHub to list",
+                tree_sha, self.owner, self.repo
+            )));
+        }
+        Ok(tree)
+    }
+}
+
+/// Join a directory and a path below it
+fn join_path(dir: &str, path: &str) -> String {
+    if dir.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", dir, path)
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for GitHubProvider {
+    /// List repository tree items
+    /// https://docs.github.com/rest/git/trees#get-a-tree
+    ///
+    /// Fetches the whole tree of the ref with `recursive=1` and filters it locally.
+    /// If GitHub truncates that listing, the tree is walked one directory at a time.
+    async fn list_repository_tree(
+        &self,
+        path: Option<&str>,
+        recursive: bool,
+        ref_branch: Option<&str>,
+    ) -> Result<Vec<TreeItem>> {
+        let tree_ref = ref_branch.unwrap_or(&self.default_branch);
+        let tree = self.get_tree(tree_ref, true).await?;
+
+        if tree.truncated {
+            tracing::debug!(
+                "Tree listing for {}/{}@{} was truncated by GitHub, walking it by directory",
+                self.owner,
+                self.repo,
+                tree_ref
+            );
+            return self.walk_tree(tree_ref, path, recursive).await;
+        }
+
+        let items = tree.tree.into_iter().map(TreeItem::from).collect();
+        Ok(filter_tree(items, path, recursive))
+    }
+
+    /// Download raw file contents
+    /// https://docs.github.com/rest/repos/contents#get-repository-content
+    async fn get_file_content(&self, file_path: &str, ref_branch: Option<&str>) -> Result<Vec<u8>> {
+        let mut api_path = format!(
+            "repos/{}/{}/contents/{}",
+            self.owner,
+            self.repo,
+            rest::encode_path(file_path)
+        );
+
+        if let Some(branch) = ref_branch {
+            api_path = format!("{}?ref={}", api_path, urlencoding::encode(branch));
+        }
+
+        // The raw media type works for files up to 100 MB, unlike base64 JSON (1 MB)
+        let response = self
+            .transport
+            .send(self.build_request_with_accept(Method::GET, &api_path, ACCEPT_RAW))
+            .await?;
+
+        let response = rest::check_status(response, ERROR_FIELDS)
+            .await
+            .map_err(|e| {
+                if e.is_not_found() {
+                    VktError::ApiNotFound(format!("File not found: {}", file_path))
+                } else {
+                    e
+                }
+            })?;
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| VktError::Api(format!("Failed to read response body: {}", e)))?;
+        Ok(bytes.to_vec())
+    }
+
+    async fn get_file_info(&self, file_path: &str, ref_branch: Option<&str>) -> Result<FileInfo> {
+        let mut api_path = format!(
+            "repos/{}/{}/contents/{}",
+            self.owner,
+            self.repo,
+            rest::encode_path(file_path)
+        );
+
+        if let Some(branch) = ref_branch {
+            api_path = format!("{}?ref={}", api_path, urlencoding::encode(branch));
+        }
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &api_path))
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(VktError::ApiNotFound(format!(
+                "File not found: {}",
+                file_path
+            )));
+        }
+
+        match rest::handle_response(response, ERROR_FIELDS).await? {
+            GitHubContents::File(content) => Ok(content.into()),
+            GitHubContents::Dir(entries) => Err(VktError::ApiNotFound(format!(
+                "Not a file: {} is a directory with {} entries",
+                file_path,
+                entries.len()
+            ))),
+        }
+    }
+
+    /// Create a branch by adding a new ref
+    /// https://docs.github.com/rest/git/refs#create-a-reference
+    async fn create_branch(&self, branch_name: &str, source_branch: &str) -> Result<Branch> {
+        let sha = self.resolve_branch_sha(source_branch).await?;
+
+        let path = format!("repos/{}/{}/git/refs", self.owner, self.repo);
+        let body = CreateRefRequest {
+            ref_name: format!("refs/heads/{}", branch_name),
+            sha,
+        };
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::POST, &path).json(&body))
+            .await?;
+
+        let git_ref: GitRefResponse = rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(git_ref.into())
+    }
+
+    /// Create or update a file
+    /// https://docs.github.com/rest/repos/contents#create-or-update-file-contents
+    async fn create_or_update_file(
+        &self,
+        file_path: &str,
+        content: &str,
+        branch: &str,
+        message: &str,
+        author_name: &str,
+        author_email: &str,
+    ) -> Result<FileCommitResponse> {
+        let path = format!(
+            "repos/{}/{}/contents/{}",
+            self.owner,
+            self.repo,
+            rest::encode_path(file_path)
+        );
+
+        // Updates must reference the blob SHA currently on the target branch
+        let existing_sha = match self.get_file_info(file_path, Some(branch)).await {
+            Ok(file_info) => file_info.sha,
+            Err(e) if e.is_not_found() => None,
+            Err(e) => return Err(e),
+        };
+
+        let identity = GitHubAuthor {
+            name: author_name.to_string(),
+            email: author_email.to_string(),
+            date: None,
+        };
+        let body = CreateFileRequest {
+            message: message.to_string(),
+            content: content.to_string(),
+            branch: branch.to_string(),
+            sha: existing_sha,
+            author: identity.clone(),
+            committer: identity,
+        };
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::PUT, &path).json(&body))
+            .await?;
+
+        let commit_response: GitHubCommitResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(commit_response.into())
+    }
+
+    /// Create a pull request
+    /// https://docs.github.com/rest/pulls/pulls#create-a-pull-request
+    async fn create_pull_request(
+        &self,
+        title: &str,
+        head_branch: &str,
+        base_branch: &str,
+        body: Option<&str>,
+    ) -> Result<PullRequest> {
+        let path = format!("repos/{}/{}/pulls", self.owner, self.repo);
+        let body = CreatePullRequest {
+            title: title.to_string(),
+            head: head_branch.to_string(),
+            base: base_branch.to_string(),
+            body: body.map(|s| s.to_string()),
+        };
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::POST, &path).json(&body))
+            .await?;
+
+        let pull_response: GitHubPullResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(pull_response.into())
+    }
+
+    /// Request reviews from the given users
+    /// https://docs.github.com/rest/pulls/review-requests#request-reviewers-for-a-pull-request
+    async fn assign_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        if reviewers.is_empty() {
+            return Ok(());
+        }
+
+        let path = format!(
+            "repos/{}/{}/pulls/{}/requested_reviewers",
+            self.owner, self.repo, pr_number
+        );
+        let body = RequestReviewers {
+            reviewers: reviewers.to_vec(),
+        };
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::POST, &path).json(&body))
+            .await?;
+
+        rest::check_status(response, ERROR_FIELDS).await?;
+        Ok(())
+    }
+
+    async fn capabilities(&self) -> Result<Capabilities> {
+        // Contents are fetched with the raw media type
+        Ok(Capabilities::new([
+            Capability::ReviewerAssignment,
+            Capability::RawDownloads,
+        ]))
+    }
+
+    async fn get_repository_info(&self) -> Result<RepositoryInfo> {
+        let path = format!("repos/{}/{}", self.owner, self.repo);
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        let repo_response: GitHubRepoResponse =
+            rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(repo_response.into())
+    }
+
+    async fn check_permissions(
+        &self,
+        head_branch: &str,
+        base_branch: &str,
+    ) -> Result<PermissionReport> {
+        let full_name = format!("{}/{}", self.owner, self.repo);
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &format!("repos/{}", full_name)))
+            .await?;
+        let repo: GitHubRepoResponse = rest::handle_response(response, ERROR_FIELDS).await?;
+        let mut report = PermissionReport::from_push_access(
+            repo.permissions.map(|permissions| permissions.push),
+            &full_name,
+        );
+
+        // Rulesets can restrict branch creation even for users with push access
+        let path = format!(
+            "repos/{}/rules/branches/{}",
+            full_name,
+            rest::encode_path(head_branch)
+        );
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        match rest::handle_response::<Vec<GitHubBranchRule>>(response, ERROR_FIELDS).await {
+            Ok(rules) if rules.iter().any(|rule| rule.rule_type == "creation") => report.deny(
+                Permission::CreateBranch,
+                format!("a ruleset restricts creating {}", head_branch),
+            ),
+            Ok(_) => {}
+            // Older GitHub Enterprise Server releases have no rulesets
+            Err(e) => tracing::debug!("Branch rules unavailable: {}", e),
+        }
+
+        let path = format!(
+            "repos/{}/branches/{}",
+            full_name,
+            rest::encode_path(base_branch)
+        );
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        rest::require_base_branch(response.status(), base_branch, &mut report);
+        Ok(report)
+    }
+
+    fn stale_since(&self) -> Option<DateTime<Utc>> {
+        self.transport.stale_since()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{
+        body_partial_json, header, method, path, query_param, query_param_is_missing,
+    };
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn create_test_config(base_url: String) -> Config {
+        Config::for_tests("GitHub", &base_url)
+    }
+
+    async fn create_test_provider() -> (MockServer, GitHubProvider) {
+        let server = MockServer::start().await;
+        let provider = GitHubProvider::new(&create_test_config(server.uri())).unwrap();
+        (server, provider)
+    }
+
+    fn tree_fixture() -> serde_json::Value {
+        serde_json::json!({
+            "sha": "root",
+            "truncated": false,
+            "tree": [
+                {"path": "Cargo.toml", "mode": "100644", "type": "blob", "sha": "a1", "size": 10},
+                {"path": "src", "mode": "040000", "type": "tree", "sha": "t1"},
+                {"path": "src/main.rs", "mode": "100644", "type": "blob", "sha": "a2", "size": 20},
+                {"path": "src/commands", "mode": "040000", "type": "tree", "sha": "t2"},
+                {"path": "src/commands/mod.rs", "mode": "100644", "type": "blob", "sha": "a3"},
+                {"path": "srcs.txt", "mode": "100644", "type": "blob", "sha": "a4"}
+            ]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_list_repository_tree_immediate_children() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/git/trees/main"))
+            .and(query_param("recursive", "1"))
+            .and(header("Authorization", "Bearer test-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(tree_fixture()))
+            .mount(&server)
+            .await;
+
+        let items = provider
+            .list_repository_tree(Some("/src/"), false, None)
+            .await
+            .unwrap();
+        let mut names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["commands", "main.rs"]);
+        assert!(
+            items
+                .iter()
+                .find(|i| i.name == "commands")
+                .unwrap()
+                .is_dir()
+        );
+
+        let root = provider
+            .list_repository_tree(None, false, None)
+            .await
+            .unwrap();
+        assert_eq!(root.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_list_repository_tree_walks_truncated_trees() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/git/trees/main"))
+            .and(query_param("recursive", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "sha": "root", "truncated": true, "tree": []
+            })))
+            .mount(&server)
+            .await;
+        let subtrees = [
+            (
+                "main",
+                serde_json::json!([
+                    {"path": "Cargo.toml", "mode": "100644", "type": "blob", "sha": "a1"},
+                    {"path": "src", "mode": "040000", "type": "tree", "sha": "t1"}
+                ]),
+            ),
+            (
+                "t1",
+                serde_json::json!([
+                    {"path": "main.rs", "mode": "100644", "type": "blob", "sha": "a2"},
+                    {"path": "commands", "mode": "040000", "type": "tree", "sha": "t2"}
+                ]),
+            ),
+            (
+                "t2",
+                serde_json::json!([
+                    {"path": "mod.rs", "mode": "100644", "type": "blob", "sha": "a3"}
+                ]),
+            ),
+        ];
+        for (sha, tree) in subtrees {
+            Mock::given(method("GET"))
+                .and(path(format!("/repos/owner/repo/git/trees/{}", sha)))
+                .and(query_param_is_missing("recursive"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "sha": sha, "truncated": false, "tree": tree
+                })))
+                .mount(&server)
+                .await;
+        }
+
+        let items = provider
+            .list_repository_tree(Some("src"), true, None)
+            .await
+            .unwrap();
+        let mut paths: Vec<&str> = items.iter().map(|i| i.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec!["src/commands", "src/commands/mod.rs", "src/main.rs"]
+        );
+
+        let root = provider
+            .list_repository_tree(None, false, None)
+            .await
+            .unwrap();
+        assert_eq!(root.len(), 2);
+        assert!(
+            provider
+                .list_repository_tree(Some("missing"), true, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_repository_tree_recursive() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/git/trees/dev"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(tree_fixture()))
+            .mount(&server)
+            .await;
+
+        let items = provider
+            .list_repository_tree(Some("src"), true, Some("dev"))
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 3);
+        assert!(items.iter().all(|i| i.path.starts_with("src/")));
+    }
+
+    #[tokio::test]
+    async fn test_get_file_content_raw() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/contents/scripts/run.sh"))
+            .and(query_param("ref", "main"))
+            .and(header("Accept", ACCEPT_RAW))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"#!/bin/sh\n".to_vec()))
+            .mount(&server)
+            .await;
+
+        let content = provider
+            .get_file_content("scripts/run.sh", Some("main"))
+            .await
+            .unwrap();
+        assert_eq!(content, b"#!/bin/sh\n");
+    }
+
+    #[tokio::test]
+    async fn test_get_file_info_strips_wrapped_base64() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/contents/README.md"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "type": "file",
+                "encoding": "base64",
+                "size": 5,
+                "name": "README.md",
+                "path": "README.md",
+                "content": "SGVs\nbG8=\n",
+                "sha": "abc",
+                "download_url": null
+            })))
+            .mount(&server)
+            .await;
+
+        let info = provider.get_file_info("README.md", None).await.unwrap();
+        assert_eq!(info.sha.as_deref(), Some("abc"));
+        assert_eq!(info.content.as_deref(), Some("SGVsbG8="));
+    }
+
+    #[tokio::test]
+    async fn test_file_exists_for_directory_is_false() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/contents/src"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"type": "file", "name": "main.rs", "path": "src/main.rs"}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/contents/missing.txt"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "message": "Not Found"
+            })))
+            .mount(&server)
+            .await;
+
+        assert!(!provider.file_exists("src", None).await.unwrap());
+        assert!(!provider.file_exists("missing.txt", None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_create_branch_from_source_ref() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/git/ref/heads/main"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ref": "refs/heads/main",
+                "object": {"type": "commit", "sha": "deadbeef"}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/git/refs"))
+            .and(body_partial_json(serde_json::json!({
+                "ref": "refs/heads/feat/x",
+                "sha": "deadbeef"
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "ref": "refs/heads/feat/x",
+                "object": {"type": "commit", "sha": "deadbeef"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let branch = provider.create_branch("feat/x", "main").await.unwrap();
+        assert_eq!(branch.name, "feat/x");
+        assert_eq!(branch.commit.id, "deadbeef");
+    }
+
+    #[tokio::test]
+    async fn test_create_branch_already_exists_is_conflict() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/git/ref/heads/main"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ref": "refs/heads/main",
+                "object": {"type": "commit", "sha": "deadbeef"}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/git/refs"))
+            .respond_with(ResponseTemplate::new(422).set_body_json(serde_json::json!({
+                "message": "Reference already exists"
+            })))
+            .mount(&server)
+            .await;
+
+        let err = provider.create_branch("feat/x", "main").await.unwrap_err();
+        assert!(matches!(err, VktError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn test_create_file_without_existing_sha() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/contents/scripts/new.sh"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/repos/owner/repo/contents/scripts/new.sh"))
+            .and(body_partial_json(serde_json::json!({
+                "branch": "feat/x",
+                "content": "aGk=",
+                "author": {"name": "Test", "email": "test@example.com"}
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "content": {"name": "new.sh", "path": "scripts/new.sh", "sha": "blob1", "size": 2},
+                "commit": {
+                    "sha": "c0ffee",
+                    "message": "add",
+                    "author": {"name": "Test", "email": "test@example.com", "date": "2024-01-01T00:00:00Z"},
+                    "committer": {"name": "Test", "email": "test@example.com", "date": "2024-01-01T00:00:00Z"}
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let response = provider
+            .create_or_update_file(
+                "scripts/new.sh",
+                "aGk=",
+                "feat/x",
+                "add",
+                "Test",
+                "test@example.com",
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.commit.id, "c0ffee");
+        assert_eq!(response.content.path, "scripts/new.sh");
+    }
+
+    #[tokio::test]
+    async fn test_create_pull_request() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/pulls"))
+            .and(body_partial_json(serde_json::json!({
+                "title": "[T] add", "head": "feat/x", "base": "main"
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "number": 7,
+                "title": "[T] add",
+                "state": "open",
+                "html_url": "https://github.com/owner/repo/pull/7",
+                "body": "desc",
+                "head": {"ref": "feat/x", "sha": "c0ffee", "repo": {"full_name": "owner/repo"}},
+                "base": {"ref": "main", "sha": "deadbeef", "repo": {"full_name": "owner/repo"}}
+            })))
+            .mount(&server)
+            .await;
+
+        let pr = provider
+            .create_pull_request("[T] add", "feat/x", "main", Some("desc"))
+            .await
+            .unwrap();
+        assert_eq!(pr.number, 7);
+        assert_eq!(pr.head.unwrap().ref_branch, "feat/x");
+        assert_eq!(pr.base.unwrap().ref_branch, "main");
+    }
+
+    #[tokio::test]
+    async fn test_assign_reviewers() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/pulls/7/requested_reviewers"))
+            .and(body_partial_json(
+                serde_json::json!({"reviewers": ["alice"]}),
+            ))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        provider
+            .assign_reviewers(7, &["alice".to_string()])
+            .await
+            .unwrap();
+        // No request is made for an empty reviewer list
+        provider.assign_reviewers(7, &[]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_repository_info() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 1,
+                "full_name": "owner/repo",
+                "default_branch": "main",
+                "private": false
+            })))
+            .mount(&server)
+            .await;
+
+        let info = provider.get_repository_info().await.unwrap();
+        assert_eq!(info.full_name, "owner/repo");
+        assert_eq!(info.default_branch, "main");
+    }
+
+    #[tokio::test]
+    async fn test_check_permissions() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 1,
+                "full_name": "owner/repo",
+                "default_branch": "main",
+                "permissions": {"admin": false, "push": true, "pull": true}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/rules/branches/release/1.0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"type": "creation", "ruleset_id": 7}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/rules/branches/feature"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/branches/main"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "name": "main", "protected": true
+            })))
+            .mount(&server)
+            .await;
+
+        let report = provider.check_permissions("feature", "main").await.unwrap();
+        assert!(report.is_allowed());
+        assert!(report.is_granted(Permission::Push));
+
+        let report = provider
+            .check_permissions("release/1.0", "main")
+            .await
+            .unwrap();
+        assert!(report.is_granted(Permission::Push));
+        assert!(report.denied().any(|(p, _)| p == Permission::CreateBranch));
+
+        let report = provider
+            .check_permissions("feature", "stable")
+            .await
+            .unwrap();
+        let denied: Vec<_> = report.denied().collect();
+        assert_eq!(denied.len(), 1);
+        assert_eq!(denied[0].0, Permission::PullRequest);
+        assert!(denied[0].1.contains("stable"));
+    }
+
+    #[tokio::test]
+    async fn test_error_mapping() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+                "message": "Bad credentials"
+            })))
+            .mount(&server)
+            .await;
+        let err = provider.get_repository_info().await.unwrap_err();
+        assert!(matches!(err, VktError::AuthInvalid(ref m) if m == "Bad credentials"));
+
+        server.reset().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo"))
+            .respond_with(
+                ResponseTemplate::new(403)
+                    .insert_header("x-ratelimit-remaining", "0")
+                    .set_body_json(serde_json::json!({"message": "API rate limit exceeded"})),
+            )
+            .mount(&server)
+            .await;
+        let err = provider.get_repository_info().await.unwrap_err();
+        assert!(matches!(err, VktError::RateLimited(_)));
+        assert!(err.is_retryable());
+
+        server.reset().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo"))
+            .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+                "message": "Resource not accessible by integration"
+            })))
+            .mount(&server)
+            .await;
+        let err = provider.get_repository_info().await.unwrap_err();
+        assert!(matches!(err, VktError::PermissionDenied(_)));
+        assert!(err.to_string().contains("token's scopes"), "{}", err);
+    }
+}
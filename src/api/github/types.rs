@@ -0,0 +1,412 @@
+//! GitHub API Response Types
+//!
+//! These types map to the GitHub REST API (v3) responses used by the provider.
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::types::{
+    Author, Branch, Commit, FileCommitResponse, FileContent, FileInfo, PullRequest, PullRequestRef,
+    RepositoryInfo, RepositoryRef, TreeItem,
+};
+
+/// Git tree response (GET /repos/{owner}/{repo}/git/trees/{tree_sha})
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitTreeResponse {
+    /// Tree entries
+    pub tree: Vec<GitTreeItem>,
+    /// Whether the listing was cut off by the API limit
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// Individual tree entry from the git trees API
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitTreeItem {
+    /// Item path
+    pub path: String,
+    /// Item mode (e.g., "100644", "040000")
+    pub mode: String,
+    /// Item type ("blob" for file, "tree" for directory, "commit" for submodule)
+    #[serde(rename = "type")]
+    pub item_type: String,
+    /// Object SHA
+    pub sha: String,
+    /// Size in bytes (only for blobs)
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// API URL
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl From<GitTreeItem> for TreeItem {
+    fn from(item: GitTreeItem) -> Self {
+        let name = item
+            .path
+            .split('/')
+            .next_back()
+            .unwrap_or(&item.path)
+            .to_string();
+
+        TreeItem {
+            id: item.sha,
+            name,
+            item_type: item.item_type,
+            path: item.path,
+            mode: item.mode,
+        }
+    }
+}
+
+/// Git reference response (GET/POST /repos/{owner}/{repo}/git/refs)
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitRefResponse {
+    /// Reference name (e.g., "refs/heads/main")
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// Node ID
+    #[serde(default)]
+    pub node_id: Option<String>,
+    /// Reference object (commit SHA)
+    pub object: GitRefObject,
+}
+
+/// Git reference object
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitRefObject {
+    /// Object type (usually "commit")
+    #[serde(rename = "type")]
+    pub object_type: String,
+    /// Object SHA
+    pub sha: String,
+    /// API URL
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl From<GitRefResponse> for Branch {
+    fn from(git_ref: GitRefResponse) -> Self {
+        // Extract branch name from "refs/heads/branch-name"
+        let name = git_ref
+            .ref_name
+            .strip_prefix("refs/heads/")
+            .unwrap_or(&git_ref.ref_name)
+            .to_string();
+
+        Branch {
+            name,
+            commit: Commit {
+                id: git_ref.object.sha,
+                message: String::new(), // Not provided in ref response
+                author: None,
+                timestamp: None,
+            },
+        }
+    }
+}
+
+/// Contents API response
+///
+/// The contents endpoint returns an object for files and an array for directories.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum GitHubContents {
+    /// A single file (or symlink/submodule)
+    File(GitHubContentResponse),
+    /// A directory listing
+    Dir(Vec<serde_json::Value>),
+}
+
+/// File content response from GitHub
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubContentResponse {
+    /// Content type ("file", "symlink" or "submodule")
+    #[serde(rename = "type")]
+    pub content_type: String,
+    /// File encoding ("base64", or "none" for files over 1 MB)
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// Size in bytes
+    pub size: u64,
+    /// File name
+    pub name: String,
+    /// File path
+    pub path: String,
+    /// Content (Base64 encoded, wrapped at 60 columns)
+    #[serde(default)]
+    pub content: Option<String>,
+    /// File SHA
+    pub sha: String,
+    /// Download URL
+    #[serde(default)]
+    pub download_url: Option<String>,
+}
+
+impl From<GitHubContentResponse> for FileInfo {
+    fn from(content: GitHubContentResponse) -> Self {
+        FileInfo {
+            name: Some(content.name),
+            path: Some(content.path),
+            size: Some(content.size),
+            // GitHub wraps base64 content with newlines
+            content: content
+                .content
+                .map(|c| c.chars().filter(|c| !c.is_whitespace()).collect()),
+            sha: Some(content.sha),
+        }
+    }
+}
+
+/// Create/update file response (PUT /repos/{owner}/{repo}/contents/{path})
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubCommitResponse {
+    /// Content information
+    pub content: GitHubContentInfo,
+    /// Commit details
+    pub commit: GitHubCommitDetail,
+}
+
+/// Content info in commit response
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubContentInfo {
+    /// File name
+    pub name: String,
+    /// File path
+    pub path: String,
+    /// File SHA
+    pub sha: String,
+    /// Size in bytes
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// Download URL
+    #[serde(default)]
+    pub download_url: Option<String>,
+}
+
+/// Detailed commit information
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubCommitDetail {
+    /// Commit SHA
+    pub sha: String,
+    /// Commit message
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Author information
+    pub author: GitHubAuthor,
+    /// Committer information
+    pub committer: GitHubAuthor,
+}
+
+/// Author/Committer information
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GitHubAuthor {
+    /// Name
+    pub name: String,
+    /// Email
+    pub email: String,
+    /// Date
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+}
+
+impl From<GitHubCommitResponse> for FileCommitResponse {
+    fn from(response: GitHubCommitResponse) -> Self {
+        FileCommitResponse {
+            content: FileContent {
+                name: response.content.name,
+                path: response.content.path,
+                sha: response.content.sha,
+                size: response.content.size,
+                download_url: response.content.download_url,
+            },
+            commit: Commit {
+                id: response.commit.sha,
+                message: response.commit.message.unwrap_or_default(),
+                author: Some(Author {
+                    name: response.commit.author.name,
+                    email: response.commit.author.email,
+                    date: response.commit.author.date,
+                }),
+                timestamp: response.commit.committer.date,
+            },
+        }
+    }
+}
+
+/// Pull request response
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubPullResponse {
+    /// PR number
+    pub number: u64,
+    /// PR title
+    pub title: String,
+    /// PR body
+    #[serde(default)]
+    pub body: Option<String>,
+    /// PR state
+    pub state: String,
+    /// HTML URL
+    #[serde(default)]
+    pub html_url: Option<String>,
+    /// Head reference (source branch)
+    pub head: GitHubPullRef,
+    /// Base reference (target branch)
+    pub base: GitHubPullRef,
+}
+
+/// PR head/base reference
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubPullRef {
+    /// Branch name
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// Commit SHA
+    pub sha: String,
+    /// Repository (null when the fork was deleted)
+    #[serde(default)]
+    pub repo: Option<GitHubRepoRef>,
+}
+
+/// Minimal repository reference
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubRepoRef {
+    /// Full name (owner/repo)
+    pub full_name: String,
+}
+
+impl From<GitHubPullRef> for PullRequestRef {
+    fn from(pr_ref: GitHubPullRef) -> Self {
+        PullRequestRef {
+            ref_branch: pr_ref.ref_name,
+            repo: pr_ref.repo.map(|r| RepositoryRef {
+                full_name: r.full_name,
+            }),
+        }
+    }
+}
+
+impl From<GitHubPullResponse> for PullRequest {
+    fn from(pr: GitHubPullResponse) -> Self {
+        PullRequest {
+            number: pr.number,
+            title: pr.title,
+            html_url: pr.html_url,
+            state: pr.state,
+            head: Some(pr.head.into()),
+            base: Some(pr.base.into()),
+            body: pr.body,
+        }
+    }
+}
+
+/// Repository response
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubRepoResponse {
+    /// Repository ID
+    pub id: u64,
+    /// Full name (owner/repo)
+    pub full_name: String,
+    /// Description
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Default branch
+    pub default_branch: String,
+    /// Private flag
+    #[serde(default)]
+    pub private: Option<bool>,
+    /// HTML URL
+    #[serde(default)]
+    pub html_url: Option<String>,
+    /// Clone URL (HTTPS)
+    #[serde(default)]
+    pub clone_url: Option<String>,
+    /// SSH URL
+    #[serde(default)]
+    pub ssh_url: Option<String>,
+    /// Access of the authenticated user (absent for anonymous requests)
+    #[serde(default)]
+    pub permissions: Option<GitHubPermissions>,
+}
+
+/// Repository permissions of the authenticated user
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubPermissions {
+    /// Can push (write access)
+    #[serde(default)]
+    pub push: bool,
+}
+
+/// Ruleset rule that applies to a branch (GET /repos/{owner}/{repo}/rules/branches/{branch})
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubBranchRule {
+    /// Rule type (e.g. "creation", "update", "pull_request")
+    #[serde(rename = "type")]
+    pub rule_type: String,
+}
+
+impl From<GitHubRepoResponse> for RepositoryInfo {
+    fn from(repo: GitHubRepoResponse) -> Self {
+        RepositoryInfo {
+            id: repo.id,
+            full_name: repo.full_name,
+            description: repo.description,
+            default_branch: repo.default_branch,
+            private: repo.private,
+            html_url: repo.html_url,
+            clone_url: repo.clone_url,
+            ssh_url: repo.ssh_url,
+        }
+    }
+}
+
+/// Request body for creating a git reference
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateRefRequest {
+    /// Full reference name (refs/heads/...)
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// Commit SHA the ref points to
+    pub sha: String,
+}
+
+/// Request body for creating/updating a file
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateFileRequest {
+    /// Commit message
+    pub message: String,
+    /// Base64-encoded content
+    pub content: String,
+    /// Target branch
+    pub branch: String,
+    /// Blob SHA of the file being replaced (required for updates)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha: Option<String>,
+    /// Commit author
+    pub author: GitHubAuthor,
+    /// Committer
+    pub committer: GitHubAuthor,
+}
+
+/// Request body for creating a pull request
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatePullRequest {
+    /// PR title
+    pub title: String,
+    /// Source branch
+    pub head: String,
+    /// Target branch
+    pub base: String,
+    /// Optional: PR body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+/// Request body for requesting reviewers
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestReviewers {
+    /// List of reviewer usernames
+    pub reviewers: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {}
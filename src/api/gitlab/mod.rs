@@ -0,0 +1,996 @@
+//! GitLab Provider Implementation
+//!
+//! Implements the ForgeProvider trait for the GitLab REST API (v4).
+//! Works with gitlab.com and self-hosted instances under any API root
+//! (e.g. `https://git.example.com/gitlab/api/v4`).
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use reqwest::{Method, StatusCode};
+
+use crate::api::pagination::{Cursor, Page, next_from_headers, paginate};
+use crate::api::rest;
+use crate::api::traits::ForgeProvider;
+use crate::api::transport::Transport;
+use crate::api::types::{
+    Author, Branch, Capabilities, Capability, Commit, FileCommitResponse, FileContent, FileInfo,
+    Permission, PermissionReport, PullRequest, RepositoryInfo, TreeItem, branch_matches,
+};
+use crate::config::{Config, ProviderType};
+use crate::error::{Result, VktError};
+
+mod types;
+
+use types::{
+    CreateMergeRequest, FileRequest, GitLabBranchResponse, GitLabFileResponse,
+    GitLabMergeRequestResponse, GitLabProjectResponse, GitLabProtectedBranch, GitLabUser,
+    UpdateReviewers,
+};
+
+/// Page size used for paginated list endpoints (GitLab maximum)
+const PER_PAGE: u32 = 100;
+
+/// Fields of a GitLab error body that may hold the message, depending on the endpoint
+const ERROR_FIELDS: &[&str] = &["message", "error"];
+
+/// GitLab API Provider
+#[derive(Debug, Clone)]
+pub struct GitLabProvider {
+    /// HTTP transport (live, recording or replaying)
+    transport: Transport,
+    /// Base API URL
+    base_url: String,
+    /// API token
+    token: String,
+    /// Project ID as used in URLs (numeric ID or URL-encoded path)
+    project: String,
+    /// Default branch name
+    default_branch: String,
+}
+
+impl GitLabProvider {
+    /// Create a new GitLab provider from configuration
+    pub fn new(config: &Config) -> Result<Self> {
+        let token = config.access_token()?;
+
+        Ok(Self {
+            transport: Transport::from_config(config)?.with_secret(&token),
+            base_url: config.remote.api_url.trim_end_matches('/').to_string(),
+            token,
+            project: Self::encode_project_id(&config.repo.project_id)?,
+            default_branch: config.repo.default_branch.clone(),
+        })
+    }
+
+    /// Replace the HTTP transport (e.g. to replay a recorded cassette)
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = transport.with_secret(&self.token);
+        self
+    }
+
+    /// Normalize a project ID for use in URLs
+    ///
+    /// Accepts a numeric ID (`1234`), a namespaced path (`group/sub/repo`)
+    /// or an already URL-encoded path (`group%2Fsub%2Frepo`).
+    fn encode_project_id(project_id: &str) -> Result<String> {
+        let project_id = project_id.trim().trim_matches('/');
+        if project_id.is_empty() {
+            return Err(VktError::Config("Project ID cannot be empty".to_string()));
+        }
+
+        if project_id.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(project_id.to_string());
+        }
+
+        // Decode first so encoded and plain paths end up identical
+        let decoded = urlencoding::decode(project_id)
+            .map_err(|e| VktError::Config(format!("Invalid project ID encoding: {}", e)))?;
+        if !decoded.contains('/') {
+            return Err(VktError::Config(
+                "Project ID should be a numeric ID or 'namespace/project'".to_string(),
+            ));
+        }
+        Ok(urlencoding::encode(&decoded).into_owned())
+    }
+
+    /// Build authenticated request
+    fn build_request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        let url = rest::build_url(&self.base_url, path);
+        let request = self.transport.request(method, &url);
+        rest::authorize(&ProviderType::GitLab, request, &self.token)
+            .header("Accept", "application/json")
+    }
+
+    /// Version reported by the instance, None if it does not tell
+    async fn server_version(&self) -> Option<String> {
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, "version"))
+            .await
+            .ok()?;
+        rest::handle_response::<rest::VersionResponse>(response, ERROR_FIELDS)
+            .await
+            .ok()
+            .map(|v| v.version)
+    }
+
+    /// Path of a file in the repository files API (the whole path is one encoded segment)
+    fn file_api_path(&self, file_path: &str) -> String {
+        format!(
+            "projects/{}/repository/files/{}",
+            self.project,
+            urlencoding::encode(file_path.trim_matches('/'))
+        )
+    }
+
+    /// Resolve a username to its GitLab user ID
+    async fn resolve_user_id(&self, username: &str) -> Result<u64> {
+        let username = username.trim_start_matches('@');
+        let path = format!("users?username={}", urlencoding::encode(username));
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        let users: Vec<GitLabUser> = rest::handle_response(response, ERROR_FIELDS).await?;
+
+        users
+            .into_iter()
+            .find(|u| u.username.eq_ignore_ascii_case(username))
+            .map(|u| u.id)
+            .ok_or_else(|| VktError::ApiNotFound(format!("User not found: {}", username)))
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for GitLabProvider {
+    /// List repository tree items
+    /// https://docs.gitlab.com/ee/api/repositories.html#list-repository-tree
+    ///
+    /// Follows `Link`/`X-Next-Page` until all pages have been fetched.
+    async fn list_repository_tree(
+        &self,
+        path: Option<&str>,
+        recursive: bool,
+        ref_branch: Option<&str>,
+    ) -> Result<Vec<TreeItem>> {
+        let mut params = vec![
+            format!(
+                "ref={}",
+                urlencoding::encode(ref_branch.unwrap_or(&self.default_branch))
+            ),
+            format!("per_page={}", PER_PAGE),
+        ];
+
+        if let Some(path) = path.map(|p| p.trim_matches('/')).filter(|p| !p.is_empty()) {
+            params.push(format!("path={}", urlencoding::encode(path)));
+        }
+        if recursive {
+            params.push("recursive=true".to_string());
+        }
+
+        let base_path = format!(
+            "projects/{}/repository/tree?{}",
+            self.project,
+            params.join("&")
+        );
+
+        let base_path = &base_path;
+        paginate(Cursor::first(), |cursor| async move {
+            let response = self
+                .transport
+                .send(self.build_request(Method::GET, &cursor.apply(base_path)))
+                .await?;
+            let next = next_from_headers(response.headers());
+            let items: Vec<TreeItem> = rest::handle_response(response, ERROR_FIELDS).await?;
+            Ok(Page::new(items, next))
+        })
+        .try_collect()
+        .await
+    }
+
+    /// Download raw file contents
+    /// https://docs.gitlab.com/ee/api/repository_files.html#get-raw-file-from-repository
+    async fn get_file_content(&self, file_path: &str, ref_branch: Option<&str>) -> Result<Vec<u8>> {
+        let api_path = format!(
+            "{}/raw?ref={}",
+            self.file_api_path(file_path),
+            urlencoding::encode(ref_branch.unwrap_or(&self.default_branch))
+        );
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &api_path))
+            .await?;
+        let response = rest::check_status(response, ERROR_FIELDS)
+            .await
+            .map_err(|e| {
+                if e.is_not_found() {
+                    VktError::ApiNotFound(format!("File not found: {}", file_path))
+                } else {
+                    e
+                }
+            })?;
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| VktError::Api(format!("Failed to read response body: {}", e)))?;
+        Ok(bytes.to_vec())
+    }
+
+    async fn get_file_info(&self, file_path: &str, ref_branch: Option<&str>) -> Result<FileInfo> {
+        let api_path = format!(
+            "{}?ref={}",
+            self.file_api_path(file_path),
+            urlencoding::encode(ref_branch.unwrap_or(&self.default_branch))
+        );
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &api_path))
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(VktError::ApiNotFound(format!(
+                "File not found: {}",
+                file_path
+            )));
+        }
+
+        let file: GitLabFileResponse = rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(file.into())
+    }
+
+    /// Create a branch
+    /// https://docs.gitlab.com/ee/api/branches.html#create-repository-branch
+    async fn create_branch(&self, branch_name: &str, source_branch: &str) -> Result<Branch> {
+        let path = format!(
+            "projects/{}/repository/branches?branch={}&ref={}",
+            self.project,
+            urlencoding::encode(branch_name),
+            urlencoding::encode(source_branch)
+        );
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::POST, &path))
+            .await?;
+        let branch: GitLabBranchResponse = rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(branch.into())
+    }
+
+    /// Create or update a file
+    /// https://docs.gitlab.com/ee/api/repository_files.html#create-new-file-in-repository
+    ///
+    /// The files API only echoes the path and branch, so the resulting blob and
+    /// commit are read back from the file metadata afterwards.
+    async fn create_or_update_file(
+        &self,
+        file_path: &str,
+        content: &str,
+        branch: &str,
+        message: &str,
+        author_name: &str,
+        author_email: &str,
+    ) -> Result<FileCommitResponse> {
+        let exists = self.file_exists(file_path, Some(branch)).await?;
+        let method = if exists { Method::PUT } else { Method::POST };
+
+        let body = FileRequest {
+            branch: branch.to_string(),
+            content: content.to_string(),
+            encoding: "base64".to_string(),
+            commit_message: message.to_string(),
+            author_name: author_name.to_string(),
+            author_email: author_email.to_string(),
+        };
+
+        let response = self
+            .transport
+            .send(
+                self.build_request(method, &self.file_api_path(file_path))
+                    .json(&body),
+            )
+            .await?;
+        rest::check_status(response, ERROR_FIELDS).await?;
+
+        let api_path = format!(
+            "{}?ref={}",
+            self.file_api_path(file_path),
+            urlencoding::encode(branch)
+        );
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &api_path))
+            .await?;
+        let file: GitLabFileResponse = rest::handle_response(response, ERROR_FIELDS).await?;
+
+        // The blob ID names the content, not the commit, so it cannot stand in
+        let commit_id = file.last_commit_id.ok_or_else(|| {
+            VktError::Api(format!(
+                "GitLab did not report the commit that wrote {}",
+                file.file_path
+            ))
+        })?;
+        Ok(FileCommitResponse {
+            content: FileContent {
+                name: file.file_name,
+                path: file.file_path,
+                sha: file.blob_id,
+                size: Some(file.size),
+                download_url: None,
+            },
+            commit: Commit {
+                id: commit_id,
+                message: message.to_string(),
+                author: Some(Author {
+                    name: author_name.to_string(),
+                    email: author_email.to_string(),
+                    date: None,
+                }),
+                timestamp: None,
+            },
+        })
+    }
+
+    /// Create a merge request
+    /// https://docs.gitlab.com/ee/api/merge_requests.html#create-mr
+    async fn create_pull_request(
+        &self,
+        title: &str,
+        head_branch: &str,
+        base_branch: &str,
+        body: Option<&str>,
+    ) -> Result<PullRequest> {
+        let path = format!("projects/{}/merge_requests", self.project);
+        let body = CreateMergeRequest {
+            source_branch: head_branch.to_string(),
+            target_branch: base_branch.to_string(),
+            title: title.to_string(),
+            description: body.map(|s| s.to_string()),
+        };
+
+        let response = self
+            .transport
+            .send(self.build_request(Method::POST, &path).json(&body))
+            .await?;
+
+        let mr: GitLabMergeRequestResponse = rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(mr.into())
+    }
+
+    /// Set merge request reviewers, resolving usernames to user IDs
+    /// https://docs.gitlab.com/ee/api/merge_requests.html#update-mr
+    async fn assign_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        if reviewers.is_empty() {
+            return Ok(());
+        }
+
+        let mut reviewer_ids = Vec::with_capacity(reviewers.len());
+        for reviewer in reviewers {
+            reviewer_ids.push(self.resolve_user_id(reviewer).await?);
+        }
+
+        let path = format!("projects/{}/merge_requests/{}", self.project, pr_number);
+        let response = self
+            .transport
+            .send(
+                self.build_request(Method::PUT, &path)
+                    .json(&UpdateReviewers { reviewer_ids }),
+            )
+            .await?;
+
+        rest::check_status(response, ERROR_FIELDS).await?;
+        Ok(())
+    }
+
+    async fn capabilities(&self) -> Result<Capabilities> {
+        // Files come from the /raw endpoint; merge request reviewers arrived in GitLab 13.7
+        let capabilities =
+            Capabilities::new([Capability::ReviewerAssignment, Capability::RawDownloads]);
+        let version = self.server_version().await;
+        if version
+            .as_deref()
+            .and_then(rest::parse_version)
+            .is_some_and(|v| v < (13, 7))
+        {
+            Ok(capabilities.without(Capability::ReviewerAssignment))
+        } else {
+            Ok(capabilities)
+        }
+    }
+
+    async fn get_repository_info(&self) -> Result<RepositoryInfo> {
+        let path = format!("projects/{}", self.project);
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        let project: GitLabProjectResponse = rest::handle_response(response, ERROR_FIELDS).await?;
+        Ok(project.into())
+    }
+
+    async fn check_permissions(
+        &self,
+        head_branch: &str,
+        base_branch: &str,
+    ) -> Result<PermissionReport> {
+        let path = format!("projects/{}", self.project);
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        let project: GitLabProjectResponse = rest::handle_response(response, ERROR_FIELDS).await?;
+        let access_level = project.permissions.as_ref().map(|p| p.access_level());
+        // Developers (30) and above may push branches
+        let mut report = PermissionReport::from_push_access(
+            access_level.map(|level| level >= 30),
+            &project.path_with_namespace,
+        );
+        if project.merge_requests_access_level.as_deref() == Some("disabled") {
+            report.deny(
+                Permission::PullRequest,
+                format!(
+                    "merge requests are disabled for {}",
+                    project.path_with_namespace
+                ),
+            );
+        }
+
+        let path = format!(
+            "projects/{}/repository/branches/{}",
+            self.project,
+            urlencoding::encode(base_branch)
+        );
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        rest::require_base_branch(response.status(), base_branch, &mut report);
+
+        let Some(access_level) = access_level else {
+            return Ok(report);
+        };
+        let path = format!("projects/{}/protected_branches", self.project);
+        let response = self
+            .transport
+            .send(self.build_request(Method::GET, &path))
+            .await?;
+        match rest::handle_response::<Vec<GitLabProtectedBranch>>(response, ERROR_FIELDS).await {
+            Ok(protected) => {
+                // When several rules match, the most permissive one applies
+                let matching: Vec<_> = protected
+                    .iter()
+                    .filter(|rule| branch_matches(&rule.name, head_branch))
+                    .collect();
+                let allowed = matching.iter().any(|rule| rule.allows_push(access_level));
+                if let Some(rule) = matching.first().filter(|_| !allowed) {
+                    report.deny(
+                        Permission::CreateBranch,
+                        format!(
+                            "{} is protected by '{}' and your role may not push to it",
+                            head_branch, rule.name
+                        ),
+                    );
+                }
+            }
+            // Listing protected branches needs maintainer access on older releases
+            Err(e) => tracing::debug!("Protected branches unavailable: {}", e),
+        }
+        Ok(report)
+    }
+
+    fn stale_since(&self) -> Option<DateTime<Utc>> {
+        self.transport.stale_since()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn create_test_config(base_url: String, project_id: &str) -> Config {
+        let mut config = Config::for_tests("GitLab", &base_url);
+        config.repo.project_id = project_id.to_string();
+        config
+    }
+
+    /// Start a fake server mounted under a custom API root
+    async fn create_test_provider() -> (MockServer, GitLabProvider) {
+        let server = MockServer::start().await;
+        let config = create_test_config(
+            format!("{}/gitlab/api/v4/", server.uri()),
+            "kernel/virt/scripts",
+        );
+        let provider = GitLabProvider::new(&config).unwrap();
+        (server, provider)
+    }
+
+    /// Project path as it appears on the wire
+    const PROJECT: &str = "/gitlab/api/v4/projects/kernel%2Fvirt%2Fscripts";
+
+    #[test]
+    fn test_encode_project_id() {
+        assert_eq!(GitLabProvider::encode_project_id("1234").unwrap(), "1234");
+        assert_eq!(
+            GitLabProvider::encode_project_id("group/sub/repo").unwrap(),
+            "group%2Fsub%2Frepo"
+        );
+        assert_eq!(
+            GitLabProvider::encode_project_id("group%2Fsub%2Frepo").unwrap(),
+            "group%2Fsub%2Frepo"
+        );
+        assert!(GitLabProvider::encode_project_id("repo").is_err());
+        assert!(GitLabProvider::encode_project_id("").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_repository_tree_follows_pages() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/repository/tree", PROJECT)))
+            .and(query_param("ref", "main"))
+            .and(query_param("path", "scripts"))
+            .and(query_param("page", "1"))
+            .and(header("PRIVATE-TOKEN", "test-token"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-next-page", "2")
+                    .set_body_json(serde_json::json!([
+                        {"id": "a1", "name": "run.sh", "type": "blob", "path": "scripts/run.sh", "mode": "100755"}
+                    ])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/repository/tree", PROJECT)))
+            .and(query_param("page", "2"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-next-page", "")
+                    .set_body_json(serde_json::json!([
+                        {"id": "t1", "name": "qemu", "type": "tree", "path": "scripts/qemu", "mode": "040000"}
+                    ])),
+            )
+            .mount(&server)
+            .await;
+
+        let items = provider
+            .list_repository_tree(Some("/scripts/"), false, None)
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().any(|i| i.name == "qemu" && i.is_dir()));
+        assert!(items.iter().any(|i| i.name == "run.sh" && i.is_file()));
+    }
+
+    #[tokio::test]
+    async fn test_list_repository_tree_recursive_flag() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/repository/tree", PROJECT)))
+            .and(query_param("recursive", "true"))
+            .and(query_param("ref", "dev"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let items = provider
+            .list_repository_tree(None, true, Some("dev"))
+            .await
+            .unwrap();
+        assert!(items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_file_content_raw() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "{}/repository/files/scripts%2Frun.sh/raw",
+                PROJECT
+            )))
+            .and(query_param("ref", "main"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"echo hi\n".to_vec()))
+            .mount(&server)
+            .await;
+
+        let content = provider
+            .get_file_content("scripts/run.sh", None)
+            .await
+            .unwrap();
+        assert_eq!(content, b"echo hi\n");
+
+        let err = provider
+            .get_file_content("scripts/missing.sh", None)
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_get_file_info() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/repository/files/README.md", PROJECT)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "file_name": "README.md",
+                "file_path": "README.md",
+                "size": 5,
+                "encoding": "base64",
+                "content": "SGVsbG8=",
+                "blob_id": "blob1",
+                "last_commit_id": "c1"
+            })))
+            .mount(&server)
+            .await;
+
+        let info = provider
+            .get_file_info("README.md", Some("main"))
+            .await
+            .unwrap();
+        assert_eq!(info.sha.as_deref(), Some("blob1"));
+        assert_eq!(info.size, Some(5));
+        assert!(provider.file_exists("README.md", None).await.unwrap());
+        assert!(!provider.file_exists("NOPE.md", None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_create_branch() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("POST"))
+            .and(path(format!("{}/repository/branches", PROJECT)))
+            .and(query_param("branch", "feat/x"))
+            .and(query_param("ref", "main"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "name": "feat/x",
+                "commit": {
+                    "id": "deadbeef",
+                    "title": "init",
+                    "message": "init\n",
+                    "author_name": "A",
+                    "author_email": "a@example.com",
+                    "committed_date": "2024-01-01T00:00:00Z"
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let branch = provider.create_branch("feat/x", "main").await.unwrap();
+        assert_eq!(branch.name, "feat/x");
+        assert_eq!(branch.commit.id, "deadbeef");
+        assert_eq!(branch.commit.author.unwrap().name, "A");
+    }
+
+    #[tokio::test]
+    async fn test_create_branch_existing_is_conflict() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("POST"))
+            .and(path(format!("{}/repository/branches", PROJECT)))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "message": "Branch already exists"
+            })))
+            .mount(&server)
+            .await;
+
+        let err = provider.create_branch("feat/x", "main").await.unwrap_err();
+        assert!(matches!(err, VktError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn test_create_new_file_uses_post() {
+        let (server, provider) = create_test_provider().await;
+        let file_path = format!("{}/repository/files/scripts%2Fnew.sh", PROJECT);
+
+        // Not on the branch yet -> create with POST
+        Mock::given(method("GET"))
+            .and(path(file_path.clone()))
+            .respond_with(ResponseTemplate::new(404))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(file_path.clone()))
+            .and(body_partial_json(serde_json::json!({
+                "branch": "feat/x",
+                "content": "aGk=",
+                "encoding": "base64",
+                "commit_message": "add",
+                "author_email": "test@example.com"
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "file_path": "scripts/new.sh", "branch": "feat/x"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(file_path))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "file_name": "new.sh",
+                "file_path": "scripts/new.sh",
+                "size": 2,
+                "blob_id": "blob2",
+                "last_commit_id": "c0ffee"
+            })))
+            .mount(&server)
+            .await;
+
+        let response = provider
+            .create_or_update_file(
+                "scripts/new.sh",
+                "aGk=",
+                "feat/x",
+                "add",
+                "Test",
+                "test@example.com",
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.commit.id, "c0ffee");
+        assert_eq!(response.content.sha, "blob2");
+    }
+
+    #[tokio::test]
+    async fn test_write_without_commit_id_is_an_error() {
+        let (server, provider) = create_test_provider().await;
+        let file_path = format!("{}/repository/files/new.sh", PROJECT);
+        Mock::given(method("GET"))
+            .and(path(file_path.clone()))
+            .respond_with(ResponseTemplate::new(404))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(file_path.clone()))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "file_path": "new.sh", "branch": "feat/x"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(file_path))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "file_name": "new.sh",
+                "file_path": "new.sh",
+                "size": 2,
+                "blob_id": "blob2"
+            })))
+            .mount(&server)
+            .await;
+
+        // A blob ID must never be reported as the commit
+        let err = provider
+            .create_or_update_file(
+                "new.sh",
+                "aGk=",
+                "feat/x",
+                "add",
+                "Test",
+                "test@example.com",
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("did not report the commit"));
+    }
+
+    #[tokio::test]
+    async fn test_create_merge_request() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("POST"))
+            .and(path(format!("{}/merge_requests", PROJECT)))
+            .and(body_partial_json(serde_json::json!({
+                "source_branch": "feat/x",
+                "target_branch": "main",
+                "title": "[T] add",
+                "description": "desc"
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "id": 99,
+                "iid": 3,
+                "title": "[T] add",
+                "description": "desc",
+                "state": "opened",
+                "web_url": "https://gitlab.example.com/kernel/virt/scripts/-/merge_requests/3",
+                "source_branch": "feat/x",
+                "target_branch": "main"
+            })))
+            .mount(&server)
+            .await;
+
+        let pr = provider
+            .create_pull_request("[T] add", "feat/x", "main", Some("desc"))
+            .await
+            .unwrap();
+        assert_eq!(pr.number, 3);
+        assert_eq!(pr.state, "open");
+        assert_eq!(pr.head.unwrap().ref_branch, "feat/x");
+    }
+
+    #[tokio::test]
+    async fn test_assign_reviewers_resolves_user_ids() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/gitlab/api/v4/users"))
+            .and(query_param("username", "alice"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"id": 11, "username": "alice"}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/gitlab/api/v4/users"))
+            .and(query_param("username", "bob"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"id": 22, "username": "Bob"}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path(format!("{}/merge_requests/3", PROJECT)))
+            .and(body_partial_json(
+                serde_json::json!({"reviewer_ids": [11, 22]}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        provider
+            .assign_reviewers(3, &["alice".to_string(), "@bob".to_string()])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_assign_reviewers_unknown_user() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/gitlab/api/v4/users"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&server)
+            .await;
+
+        let err = provider
+            .assign_reviewers(3, &["ghost".to_string()])
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_numeric_project_id_and_repository_info() {
+        let server = MockServer::start().await;
+        let provider = GitLabProvider::new(&create_test_config(server.uri(), "4242")).unwrap();
+        Mock::given(method("GET"))
+            .and(path("/projects/4242"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 4242,
+                "path_with_namespace": "kernel/virt/scripts",
+                "default_branch": "master",
+                "visibility": "internal"
+            })))
+            .mount(&server)
+            .await;
+
+        let info = provider.get_repository_info().await.unwrap();
+        assert_eq!(info.full_name, "kernel/virt/scripts");
+        assert_eq!(info.default_branch, "master");
+        assert_eq!(info.private, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_follow_server_version() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path("/gitlab/api/v4/version"))
+            .and(header("PRIVATE-TOKEN", "test-token"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"version": "13.6.2-ee", "revision": "a"})),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/gitlab/api/v4/version"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"version": "16.0.0", "revision": "b"})),
+            )
+            .mount(&server)
+            .await;
+
+        let old = provider.capabilities().await.unwrap();
+        assert!(!old.supports(Capability::ReviewerAssignment));
+        assert!(old.supports(Capability::RawDownloads));
+
+        let current = provider.capabilities().await.unwrap();
+        assert!(current.supports(Capability::ReviewerAssignment));
+        assert!(!current.supports(Capability::DraftPullRequests));
+        assert!(!current.supports(Capability::AutoMerge));
+    }
+
+    #[tokio::test]
+    async fn test_error_mapping() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path(PROJECT))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+                "message": "401 Unauthorized"
+            })))
+            .mount(&server)
+            .await;
+        let err = provider.get_repository_info().await.unwrap_err();
+        assert!(matches!(err, VktError::AuthInvalid(ref m) if m == "401 Unauthorized"));
+
+        server.reset().await;
+        Mock::given(method("GET"))
+            .and(path(PROJECT))
+            .respond_with(ResponseTemplate::new(429).set_body_string("Retry later"))
+            .mount(&server)
+            .await;
+        let err = provider.get_repository_info().await.unwrap_err();
+        assert!(matches!(err, VktError::RateLimited(_)));
+    }
+
+    #[tokio::test]
+    async fn test_check_permissions() {
+        let (server, provider) = create_test_provider().await;
+        Mock::given(method("GET"))
+            .and(path(PROJECT))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": 42,
+                "path_with_namespace": "kernel/virt/scripts",
+                "default_branch": "main",
+                "merge_requests_access_level": "enabled",
+                "permissions": {
+                    "project_access": null,
+                    "group_access": {"access_level": 30}
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/protected_branches", PROJECT)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"name": "main", "push_access_levels": [{"access_level": 40}]},
+                {"name": "release/*", "push_access_levels": [{"access_level": 40}]},
+                {"name": "dev-*", "push_access_levels": [{"access_level": 30}]}
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/repository/branches/main", PROJECT)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "name": "main", "protected": true
+            })))
+            .mount(&server)
+            .await;
+
+        let report = provider.check_permissions("dev-fix", "main").await.unwrap();
+        assert!(report.is_allowed());
+
+        let report = provider
+            .check_permissions("release/6.9", "main")
+            .await
+            .unwrap();
+        assert!(report.is_granted(Permission::Push));
+        let denied: Vec<_> = report.denied().collect();
+        assert_eq!(denied.len(), 1);
+        assert_eq!(denied[0].0, Permission::CreateBranch);
+        assert!(denied[0].1.contains("release/*"));
+
+        let report = provider
+            .check_permissions("dev-fix", "stable")
+            .await
+            .unwrap();
+        let denied: Vec<_> = report.denied().collect();
+        assert_eq!(denied.len(), 1);
+        assert_eq!(denied[0].0, Permission::PullRequest);
+    }
+}
@@ -0,0 +1,328 @@
+//! GitLab API Response Types
+//!
+//! These types map to the GitLab REST API (v4) responses used by the provider.
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::types::{Branch, Commit, FileInfo, PullRequest, PullRequestRef, RepositoryInfo};
+
+/// Repository file metadata (GET /projects/:id/repository/files/:file_path)
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabFileResponse {
+    /// File name
+    pub file_name: String,
+    /// Full file path
+    pub file_path: String,
+    /// Size in bytes
+    pub size: u64,
+    /// Content encoding (usually "base64")
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// File content
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Blob SHA
+    pub blob_id: String,
+    /// Last commit that touched the file
+    #[serde(default)]
+    pub last_commit_id: Option<String>,
+}
+
+impl From<GitLabFileResponse> for FileInfo {
+    fn from(file: GitLabFileResponse) -> Self {
+        let content = match file.encoding.as_deref() {
+            Some("base64") | None => file.content,
+            // Text-encoded content is re-encoded so FileInfo.content is always base64
+            Some(_) => file.content.map(|c| {
+                use base64::Engine;
+                base64::engine::general_purpose::STANDARD.encode(c)
+            }),
+        };
+
+        FileInfo {
+            name: Some(file.file_name),
+            path: Some(file.file_path),
+            size: Some(file.size),
+            content,
+            sha: Some(file.blob_id),
+        }
+    }
+}
+
+/// Branch response (POST /projects/:id/repository/branches)
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabBranchResponse {
+    /// Branch name
+    pub name: String,
+    /// Head commit
+    pub commit: GitLabCommit,
+}
+
+/// Commit object as embedded in branch responses
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabCommit {
+    /// Commit SHA
+    pub id: String,
+    /// Full commit message
+    #[serde(default)]
+    pub message: Option<String>,
+    /// First line of the message
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Author name
+    #[serde(default)]
+    pub author_name: Option<String>,
+    /// Author email
+    #[serde(default)]
+    pub author_email: Option<String>,
+    /// Author date
+    #[serde(default)]
+    pub authored_date: Option<String>,
+    /// Commit date
+    #[serde(default)]
+    pub committed_date: Option<String>,
+}
+
+impl From<GitLabCommit> for Commit {
+    fn from(commit: GitLabCommit) -> Self {
+        let author = match (commit.author_name, commit.author_email) {
+            (Some(name), Some(email)) => Some(crate::api::types::Author {
+                name,
+                email,
+                date: commit.authored_date,
+            }),
+            _ => None,
+        };
+
+        Commit {
+            id: commit.id,
+            message: commit.message.or(commit.title).unwrap_or_default(),
+            author,
+            timestamp: commit.committed_date,
+        }
+    }
+}
+
+impl From<GitLabBranchResponse> for Branch {
+    fn from(branch: GitLabBranchResponse) -> Self {
+        Branch {
+            name: branch.name,
+            commit: branch.commit.into(),
+        }
+    }
+}
+
+/// Merge request response
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabMergeRequestResponse {
+    /// Project-scoped MR number
+    pub iid: u64,
+    /// MR title
+    pub title: String,
+    /// MR description
+    #[serde(default)]
+    pub description: Option<String>,
+    /// MR state ("opened", "closed", "merged", "locked")
+    pub state: String,
+    /// Web URL
+    #[serde(default)]
+    pub web_url: Option<String>,
+    /// Source branch
+    pub source_branch: String,
+    /// Target branch
+    pub target_branch: String,
+}
+
+impl From<GitLabMergeRequestResponse> for PullRequest {
+    fn from(mr: GitLabMergeRequestResponse) -> Self {
+        // Normalize to the GitHub-style state names used across providers
+        let state = match mr.state.as_str() {
+            "opened" => "open".to_string(),
+            _ => mr.state,
+        };
+
+        PullRequest {
+            number: mr.iid,
+            title: mr.title,
+            html_url: mr.web_url,
+            state,
+            head: Some(PullRequestRef {
+                ref_branch: mr.source_branch,
+                repo: None,
+            }),
+            base: Some(PullRequestRef {
+                ref_branch: mr.target_branch,
+                repo: None,
+            }),
+            body: mr.description,
+        }
+    }
+}
+
+/// Project response (GET /projects/:id)
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabProjectResponse {
+    /// Project ID
+    pub id: u64,
+    /// Full path (group/subgroup/project)
+    pub path_with_namespace: String,
+    /// Description
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Default branch (null for empty repositories)
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    /// Visibility ("private", "internal", "public")
+    #[serde(default)]
+    pub visibility: Option<String>,
+    /// Web URL
+    #[serde(default)]
+    pub web_url: Option<String>,
+    /// Clone URL (HTTPS)
+    #[serde(default)]
+    pub http_url_to_repo: Option<String>,
+    /// Clone URL (SSH)
+    #[serde(default)]
+    pub ssh_url_to_repo: Option<String>,
+    /// Access of the authenticated user (absent for anonymous requests)
+    #[serde(default)]
+    pub permissions: Option<GitLabProjectPermissions>,
+    /// Merge request feature level ("enabled", "private", "disabled")
+    #[serde(default)]
+    pub merge_requests_access_level: Option<String>,
+}
+
+/// Project and group membership of the authenticated user
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabProjectPermissions {
+    /// Direct project membership
+    #[serde(default)]
+    pub project_access: Option<GitLabAccess>,
+    /// Membership inherited from the group
+    #[serde(default)]
+    pub group_access: Option<GitLabAccess>,
+}
+
+impl GitLabProjectPermissions {
+    /// Highest access level of the user (0 if not a member)
+    pub fn access_level(&self) -> u32 {
+        [&self.project_access, &self.group_access]
+            .into_iter()
+            .flatten()
+            .map(|access| access.access_level)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Membership access level (10 guest, 20 reporter, 30 developer, 40 maintainer, 50 owner)
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabAccess {
+    /// Access level
+    pub access_level: u32,
+}
+
+/// Protected branch (GET /projects/:id/protected_branches)
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabProtectedBranch {
+    /// Branch name or wildcard pattern (e.g. "release/*")
+    pub name: String,
+    /// Who may push to (and create) matching branches
+    #[serde(default)]
+    pub push_access_levels: Vec<GitLabPushAccess>,
+}
+
+/// Entry of a protected branch's push rule
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabPushAccess {
+    /// Minimum access level (0 means no one), unset for user or group entries
+    #[serde(default)]
+    pub access_level: Option<u32>,
+    /// User allowed to push
+    #[serde(default)]
+    pub user_id: Option<u64>,
+    /// Group allowed to push
+    #[serde(default)]
+    pub group_id: Option<u64>,
+}
+
+impl GitLabProtectedBranch {
+    /// Whether a user with `access_level` may push to matching branches
+    ///
+    /// Entries naming a user or group cannot be checked against the current
+    /// user and are assumed to allow the push.
+    pub fn allows_push(&self, access_level: u32) -> bool {
+        self.push_access_levels.iter().any(|entry| {
+            entry.user_id.is_some()
+                || entry.group_id.is_some()
+                || entry
+                    .access_level
+                    .is_some_and(|level| level > 0 && level <= access_level)
+        })
+    }
+}
+
+impl From<GitLabProjectResponse> for RepositoryInfo {
+    fn from(project: GitLabProjectResponse) -> Self {
+        RepositoryInfo {
+            id: project.id,
+            full_name: project.path_with_namespace,
+            description: project.description,
+            default_branch: project.default_branch.unwrap_or_default(),
+            private: project.visibility.map(|v| v != "public"),
+            html_url: project.web_url,
+            clone_url: project.http_url_to_repo,
+            ssh_url: project.ssh_url_to_repo,
+        }
+    }
+}
+
+/// User entry (GET /users?username=...)
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabUser {
+    /// User ID
+    pub id: u64,
+    /// Username
+    pub username: String,
+}
+
+/// Request body for creating/updating a file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileRequest {
+    /// Target branch
+    pub branch: String,
+    /// File content
+    pub content: String,
+    /// Content encoding ("base64")
+    pub encoding: String,
+    /// Commit message
+    pub commit_message: String,
+    /// Author name
+    pub author_name: String,
+    /// Author email
+    pub author_email: String,
+}
+
+/// Request body for creating a merge request
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateMergeRequest {
+    /// Source branch
+    pub source_branch: String,
+    /// Target branch
+    pub target_branch: String,
+    /// MR title
+    pub title: String,
+    /// Optional: MR description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Request body for setting merge request reviewers
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateReviewers {
+    /// Reviewer user IDs
+    pub reviewer_ids: Vec<u64>,
+}
+
+#[cfg(test)]
+mod tests {}
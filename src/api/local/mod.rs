@@ -0,0 +1,854 @@
+//! Local Repository Provider Implementation
+//!
+//! Implements the ForgeProvider trait directly on a bare or non-bare git
+//! repository (e.g. a shared repository on NFS) by running git plumbing
+//! commands. Pull requests are modelled as `refs/pull/<n>/head` refs plus a
+//! JSON metadata file under `<git-dir>/vkt/pulls/`.
+//!
+//! Commits are written to refs without touching any working tree, so a
+//! branch that is checked out in a non-bare repository will show the new
+//! commit as uncommitted changes until it is reset.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use async_trait::async_trait;
+use base64::Engine;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::api::traits::ForgeProvider;
+use crate::api::types::{
+    Author, Branch, Capabilities, Capability, Commit, FileCommitResponse, FileContent, FileInfo,
+    PullRequest, RepositoryInfo, TreeItem, filter_tree,
+};
+use crate::config::Config;
+use crate::error::{Result, VktError};
+
+mod types;
+
+use types::{LsTreeEntry, PullRequestRecord};
+
+/// Directory (inside the git dir) holding pull request metadata
+const PULLS_DIR: &str = "vkt/pulls";
+
+/// Local git repository provider
+#[derive(Debug, Clone)]
+pub struct LocalProvider {
+    /// Repository path (bare repository or working tree)
+    repo_path: PathBuf,
+    /// Configured repository URL (file://...)
+    repo_url: String,
+    /// Project identifier, reported as the repository full name
+    project_id: String,
+    /// Default branch name
+    default_branch: String,
+}
+
+impl LocalProvider {
+    /// Create a new local provider from configuration
+    pub fn new(config: &Config) -> Result<Self> {
+        let repo_path = Self::parse_repo_url(&config.remote.api_url)?;
+        if !repo_path.is_dir() {
+            return Err(VktError::Config(format!(
+                "Local repository not found: {}",
+                repo_path.display()
+            )));
+        }
+
+        Ok(Self {
+            repo_path,
+            repo_url: config.remote.api_url.clone(),
+            project_id: config.repo.project_id.clone(),
+            default_branch: config.repo.default_branch.clone(),
+        })
+    }
+
+    /// Parse a `file://` URL (or plain path) into a filesystem path
+    fn parse_repo_url(api_url: &str) -> Result<PathBuf> {
+        let raw = api_url
+            .strip_prefix("file://localhost")
+            .or_else(|| api_url.strip_prefix("file://"))
+            .unwrap_or(api_url);
+        let decoded = urlencoding::decode(raw)
+            .map_err(|e| VktError::Config(format!("Invalid repository URL {}: {}", api_url, e)))?;
+
+        if decoded.is_empty() {
+            return Err(VktError::Config(format!(
+                "Invalid repository URL: {}",
+                api_url
+            )));
+        }
+        Ok(PathBuf::from(decoded.as_ref()))
+    }
+
+    /// Run a git command in the repository and return its stdout
+    async fn git(&self, args: &[&str]) -> Result<Vec<u8>> {
+        self.git_with(args, &[], None).await
+    }
+
+    /// Run a git command with extra environment variables and optional stdin
+    async fn git_with(
+        &self,
+        args: &[&str],
+        envs: &[(&str, &str)],
+        stdin: Option<&[u8]>,
+    ) -> Result<Vec<u8>> {
+        let mut command = Command::new("git");
+        command
+            .arg("--literal-pathspecs")
+            .arg("-C")
+            .arg(&self.repo_path)
+            .args(args)
+            // Variables inherited from an enclosing git hook would redirect us elsewhere
+            .env_remove("GIT_DIR")
+            .env_remove("GIT_WORK_TREE")
+            .env_remove("GIT_INDEX_FILE")
+            .envs(envs.iter().copied())
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| VktError::Config(format!("Failed to run git: {}", e)))?;
+
+        if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            pipe.write_all(input).await?;
+        }
+
+        let output = child.wait_with_output().await?;
+        if output.status.success() {
+            Ok(output.stdout)
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(self.map_git_error(args, stderr.trim()))
+        }
+    }
+
+    /// Map git's stderr to a VktError
+    fn map_git_error(&self, args: &[&str], stderr: &str) -> VktError {
+        let lower = stderr.to_lowercase();
+        let command = args.first().copied().unwrap_or("git");
+
+        if lower.contains("not a git repository") {
+            VktError::Config(format!(
+                "Not a git repository: {}",
+                self.repo_path.display()
+            ))
+        } else if lower.contains("already exists") || lower.contains("but expected") {
+            VktError::Conflict(stderr.to_string())
+        } else if lower.contains("not a valid")
+            || lower.contains("invalid object name")
+            || lower.contains("unknown revision")
+            || lower.contains("bad revision")
+            || lower.contains("needed a single revision")
+            || lower.contains("does not exist")
+        {
+            VktError::ApiNotFound(stderr.to_string())
+        } else {
+            VktError::Api(format!("git {} failed: {}", command, stderr))
+        }
+    }
+
+    /// Absolute path of the git directory
+    async fn git_dir(&self) -> Result<PathBuf> {
+        let output = self.git(&["rev-parse", "--absolute-git-dir"]).await?;
+        Ok(PathBuf::from(String::from_utf8_lossy(&output).trim()))
+    }
+
+    /// Resolve a branch, tag or SHA to a commit SHA
+    async fn resolve_commit(&self, rev: &str) -> Result<String> {
+        let spec = format!("{}^{{commit}}", rev);
+        match self.git(&["rev-parse", "--verify", "--quiet", &spec]).await {
+            Ok(output) => Ok(String::from_utf8_lossy(&output).trim().to_string()),
+            // --quiet suppresses the message, leaving a bare failure
+            Err(VktError::Api(_)) | Err(VktError::ApiNotFound(_)) => {
+                Err(VktError::ApiNotFound(format!("Ref not found: {}", rev)))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Resolve a branch name to its head commit
+    async fn resolve_branch(&self, branch: &str) -> Result<String> {
+        self.resolve_commit(&format!("refs/heads/{}", branch))
+            .await
+            .map_err(|e| match e {
+                VktError::ApiNotFound(_) => {
+                    VktError::ApiNotFound(format!("Branch not found: {}", branch))
+                }
+                other => other,
+            })
+    }
+
+    /// List tree entries, optionally recursively or limited to a single path
+    async fn ls_tree(
+        &self,
+        commit: &str,
+        recursive: bool,
+        path: Option<&str>,
+    ) -> Result<Vec<LsTreeEntry>> {
+        let mut args = vec!["ls-tree", "-z", "-l"];
+        if recursive {
+            args.extend(["-r", "-t"]);
+        }
+        args.push(commit);
+        if let Some(path) = path {
+            args.extend(["--", path]);
+        }
+
+        let output = self.git(&args).await?;
+        Ok(String::from_utf8_lossy(&output)
+            .split('\0')
+            .filter(|record| !record.is_empty())
+            .filter_map(LsTreeEntry::parse)
+            .collect())
+    }
+
+    /// Look up the blob entry for a file path
+    async fn find_blob(&self, file_path: &str, ref_branch: Option<&str>) -> Result<LsTreeEntry> {
+        let commit = self
+            .resolve_commit(ref_branch.unwrap_or(&self.default_branch))
+            .await?;
+        let file_path = file_path.trim_matches('/');
+
+        self.ls_tree(&commit, false, Some(file_path))
+            .await?
+            .into_iter()
+            .find(|entry| entry.path == file_path && entry.object_type == "blob")
+            .ok_or_else(|| VktError::ApiNotFound(format!("File not found: {}", file_path)))
+    }
+
+    /// Read commit metadata
+    async fn commit_info(&self, sha: &str) -> Result<Commit> {
+        let output = self
+            .git(&[
+                "show",
+                "-s",
+                "--format=%H%x00%an%x00%ae%x00%aI%x00%cI%x00%B",
+                sha,
+            ])
+            .await?;
+        let text = String::from_utf8_lossy(&output);
+        let fields: Vec<&str> = text.splitn(6, '\0').collect();
+        if fields.len() != 6 {
+            return Err(VktError::Api(format!(
+                "Unexpected git show output for {}",
+                sha
+            )));
+        }
+
+        Ok(Commit {
+            id: fields[0].to_string(),
+            message: fields[5].trim_end().to_string(),
+            author: Some(Author {
+                name: fields[1].to_string(),
+                email: fields[2].to_string(),
+                date: Some(fields[3].to_string()),
+            }),
+            timestamp: Some(fields[4].to_string()),
+        })
+    }
+
+    /// Build a tree for `parent` with `file_path` set to `blob`, using a throwaway index
+    async fn write_tree_with(
+        &self,
+        git_dir: &Path,
+        parent: &str,
+        mode: &str,
+        blob: &str,
+        file_path: &str,
+    ) -> Result<String> {
+        let index_file = git_dir.join(format!(
+            "vkt-index-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let index = index_file.to_string_lossy().into_owned();
+        let env = [("GIT_INDEX_FILE", index.as_str())];
+        let cacheinfo = format!("{},{},{}", mode, blob, file_path);
+
+        let result = async {
+            self.git_with(&["read-tree", parent], &env, None).await?;
+            self.git_with(
+                &["update-index", "--add", "--cacheinfo", &cacheinfo],
+                &env,
+                None,
+            )
+            .await?;
+            self.git_with(&["write-tree"], &env, None).await
+        }
+        .await;
+
+        let _ = tokio::fs::remove_file(&index_file).await;
+        Ok(String::from_utf8_lossy(&result?).trim().to_string())
+    }
+
+    /// Path of a pull request metadata file
+    fn pull_path(git_dir: &Path, number: u64) -> PathBuf {
+        git_dir.join(PULLS_DIR).join(format!("{}.json", number))
+    }
+
+    /// Link reported for a pull request
+    fn pull_url(path: &Path) -> String {
+        format!("file://{}", path.display())
+    }
+
+    /// Persist a pull request record atomically
+    async fn write_pull(&self, path: &Path, record: &PullRequestRecord) -> Result<()> {
+        let json = serde_json::to_vec_pretty(record)
+            .map_err(|e| VktError::Api(format!("Failed to serialize pull request: {}", e)))?;
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, json).await?;
+        tokio::fs::rename(&tmp, path).await?;
+        Ok(())
+    }
+
+    /// Reserve the next free pull request number by creating its metadata file
+    async fn allocate_pull(&self, git_dir: &Path) -> Result<(u64, PathBuf)> {
+        let dir = git_dir.join(PULLS_DIR);
+        tokio::fs::create_dir_all(&dir).await?;
+
+        loop {
+            let mut highest = 0;
+            let mut entries = tokio::fs::read_dir(&dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let name = entry.file_name();
+                if let Some(number) = name
+                    .to_str()
+                    .and_then(|n| n.strip_suffix(".json"))
+                    .and_then(|n| n.parse::<u64>().ok())
+                {
+                    highest = highest.max(number);
+                }
+            }
+
+            let number = highest + 1;
+            let path = Self::pull_path(git_dir, number);
+            match tokio::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+                .await
+            {
+                Ok(_) => return Ok((number, path)),
+                // Another writer took this number; rescan
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for LocalProvider {
+    async fn list_repository_tree(
+        &self,
+        path: Option<&str>,
+        recursive: bool,
+        ref_branch: Option<&str>,
+    ) -> Result<Vec<TreeItem>> {
+        let commit = self
+            .resolve_commit(ref_branch.unwrap_or(&self.default_branch))
+            .await?;
+
+        let items = self
+            .ls_tree(&commit, true, None)
+            .await?
+            .into_iter()
+            .map(|entry| TreeItem {
+                id: entry.sha,
+                name: entry
+                    .path
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(&entry.path)
+                    .to_string(),
+                item_type: entry.object_type,
+                path: entry.path,
+                mode: entry.mode,
+            })
+            .collect();
+
+        Ok(filter_tree(items, path, recursive))
+    }
+
+    async fn get_file_content(&self, file_path: &str, ref_branch: Option<&str>) -> Result<Vec<u8>> {
+        let entry = self.find_blob(file_path, ref_branch).await?;
+        self.git(&["cat-file", "blob", &entry.sha]).await
+    }
+
+    async fn get_file_info(&self, file_path: &str, ref_branch: Option<&str>) -> Result<FileInfo> {
+        let entry = self.find_blob(file_path, ref_branch).await?;
+        let content = self.git(&["cat-file", "blob", &entry.sha]).await?;
+
+        Ok(FileInfo {
+            name: entry.path.rsplit('/').next().map(|n| n.to_string()),
+            path: Some(entry.path.clone()),
+            size: entry.size,
+            content: Some(base64::engine::general_purpose::STANDARD.encode(content)),
+            sha: Some(entry.sha),
+        })
+    }
+
+    async fn create_branch(&self, branch_name: &str, source_branch: &str) -> Result<Branch> {
+        let ref_name = format!("refs/heads/{}", branch_name);
+        if self.git(&["check-ref-format", &ref_name]).await.is_err() {
+            return Err(VktError::Validation(format!(
+                "Invalid branch name: {}",
+                branch_name
+            )));
+        }
+
+        let source = self.resolve_commit(source_branch).await?;
+
+        // An empty old value makes update-ref fail if the branch already exists
+        self.git(&["update-ref", &ref_name, &source, ""])
+            .await
+            .map_err(|e| match e {
+                VktError::Conflict(_) => {
+                    VktError::Conflict(format!("Branch already exists: {}", branch_name))
+                }
+                other => other,
+            })?;
+
+        Ok(Branch {
+            name: branch_name.to_string(),
+            commit: self.commit_info(&source).await?,
+        })
+    }
+
+    async fn create_or_update_file(
+        &self,
+        file_path: &str,
+        content: &str,
+        branch: &str,
+        message: &str,
+        author_name: &str,
+        author_email: &str,
+    ) -> Result<FileCommitResponse> {
+        let file_path = file_path.trim_matches('/');
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(content)
+            .map_err(|e| VktError::Validation(format!("Invalid base64 content: {}", e)))?;
+
+        let git_dir = self.git_dir().await?;
+        let parent = self.resolve_branch(branch).await?;
+
+        // Keep the executable bit of an existing file
+        let mode = match self.find_blob(file_path, Some(&parent)).await {
+            Ok(entry) if entry.mode == "100755" => "100755",
+            Ok(_) => "100644",
+            Err(e) if e.is_not_found() => "100644",
+            Err(e) => return Err(e),
+        };
+
+        let blob = self
+            .git_with(&["hash-object", "-w", "--stdin"], &[], Some(&bytes))
+            .await?;
+        let blob = String::from_utf8_lossy(&blob).trim().to_string();
+
+        let tree = self
+            .write_tree_with(&git_dir, &parent, mode, &blob, file_path)
+            .await?;
+
+        let identity = [
+            ("GIT_AUTHOR_NAME", author_name),
+            ("GIT_AUTHOR_EMAIL", author_email),
+            ("GIT_COMMITTER_NAME", author_name),
+            ("GIT_COMMITTER_EMAIL", author_email),
+        ];
+        let commit = self
+            .git_with(
+                &["commit-tree", &tree, "-p", &parent, "-F", "-"],
+                &identity,
+                Some(message.as_bytes()),
+            )
+            .await?;
+        let commit = String::from_utf8_lossy(&commit).trim().to_string();
+
+        // Compare-and-swap so concurrent writers cannot silently drop commits
+        let ref_name = format!("refs/heads/{}", branch);
+        self.git(&["update-ref", &ref_name, &commit, &parent])
+            .await
+            .map_err(|e| match e {
+                VktError::Conflict(_) => VktError::Conflict(format!(
+                    "Branch {} was updated concurrently; retry the commit",
+                    branch
+                )),
+                other => other,
+            })?;
+
+        Ok(FileCommitResponse {
+            content: FileContent {
+                name: file_path
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(file_path)
+                    .to_string(),
+                path: file_path.to_string(),
+                sha: blob,
+                size: Some(bytes.len() as u64),
+                download_url: None,
+            },
+            commit: self.commit_info(&commit).await?,
+        })
+    }
+
+    async fn create_pull_request(
+        &self,
+        title: &str,
+        head_branch: &str,
+        base_branch: &str,
+        body: Option<&str>,
+    ) -> Result<PullRequest> {
+        let head_sha = self.resolve_branch(head_branch).await?;
+        self.resolve_branch(base_branch).await?;
+
+        let git_dir = self.git_dir().await?;
+        let (number, path) = self.allocate_pull(&git_dir).await?;
+
+        let record = PullRequestRecord {
+            number,
+            title: title.to_string(),
+            body: body.map(|b| b.to_string()),
+            state: "open".to_string(),
+            head: head_branch.to_string(),
+            head_sha: head_sha.clone(),
+            base: base_branch.to_string(),
+            reviewers: Vec::new(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        let pull_ref = format!("refs/pull/{}/head", number);
+        let created = async {
+            self.git(&["update-ref", &pull_ref, &head_sha]).await?;
+            self.write_pull(&path, &record).await
+        }
+        .await;
+        if let Err(e) = created {
+            let _ = tokio::fs::remove_file(&path).await;
+            return Err(e);
+        }
+
+        Ok(record.to_pull_request(Some(Self::pull_url(&path))))
+    }
+
+    async fn assign_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        if reviewers.is_empty() {
+            return Ok(());
+        }
+
+        let path = Self::pull_path(&self.git_dir().await?, pr_number);
+        let data = match tokio::fs::read(&path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(VktError::ApiNotFound(format!(
+                    "Pull request not found: #{}",
+                    pr_number
+                )));
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut record: PullRequestRecord = serde_json::from_slice(&data).map_err(|e| {
+            VktError::Api(format!(
+                "Invalid pull request metadata {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        for reviewer in reviewers {
+            if !record.reviewers.contains(reviewer) {
+                record.reviewers.push(reviewer.clone());
+            }
+        }
+
+        self.write_pull(&path, &record).await
+    }
+
+    async fn capabilities(&self) -> Result<Capabilities> {
+        // Reviewers are stored in the pull request metadata file
+        Ok(Capabilities::new([
+            Capability::ReviewerAssignment,
+            Capability::RawDownloads,
+        ]))
+    }
+
+    async fn get_repository_info(&self) -> Result<RepositoryInfo> {
+        let git_dir = self.git_dir().await?;
+
+        let default_branch = match self.git(&["symbolic-ref", "--short", "HEAD"]).await {
+            Ok(output) => String::from_utf8_lossy(&output).trim().to_string(),
+            Err(_) => self.default_branch.clone(),
+        };
+
+        // git init writes a placeholder description that carries no information
+        let description = tokio::fs::read_to_string(git_dir.join("description"))
+            .await
+            .ok()
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty() && !d.starts_with("Unnamed repository"));
+
+        Ok(RepositoryInfo {
+            id: 0,
+            full_name: self.project_id.clone(),
+            description,
+            default_branch,
+            private: None,
+            html_url: None,
+            clone_url: Some(self.repo_url.clone()),
+            ssh_url: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn run_git(dir: &Path, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=Seed", "-c", "user.email=seed@example.com"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    /// Create a working repository with a few files, plus a bare clone of it
+    fn create_test_repos() -> (TempDir, PathBuf, PathBuf) {
+        let tmp = TempDir::new().unwrap();
+        let work = tmp.path().join("work");
+        std::fs::create_dir_all(work.join("qemu")).unwrap();
+        std::fs::write(work.join("README.md"), "# scripts\n").unwrap();
+        std::fs::write(work.join("qemu/run.sh"), "qemu-system\n").unwrap();
+        run_git(tmp.path(), &["init", "-q", "-b", "main", "work"]);
+        run_git(&work, &["add", "."]);
+        run_git(&work, &["commit", "-q", "-m", "init"]);
+
+        let bare = tmp.path().join("scripts.git");
+        run_git(
+            tmp.path(),
+            &["clone", "-q", "--bare", "work", "scripts.git"],
+        );
+        (tmp, work, bare)
+    }
+
+    fn create_provider(repo: &Path) -> LocalProvider {
+        let mut config = Config::for_tests("local", &format!("file://{}", repo.display()));
+        config.repo.project_id = "lab/scripts".to_string();
+        LocalProvider::new(&config).unwrap()
+    }
+
+    #[test]
+    fn test_parse_repo_url() {
+        assert_eq!(
+            LocalProvider::parse_repo_url("file:///srv/git/scripts.git").unwrap(),
+            PathBuf::from("/srv/git/scripts.git")
+        );
+        assert_eq!(
+            LocalProvider::parse_repo_url("file://localhost/srv/my%20repo").unwrap(),
+            PathBuf::from("/srv/my repo")
+        );
+        assert!(LocalProvider::parse_repo_url("file://").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_and_read_files() {
+        let (_tmp, work, bare) = create_test_repos();
+
+        for repo in [&work, &bare] {
+            let provider = create_provider(repo);
+
+            let root = provider
+                .list_repository_tree(None, false, None)
+                .await
+                .unwrap();
+            let mut names: Vec<_> = root.iter().map(|i| i.name.as_str()).collect();
+            names.sort();
+            assert_eq!(names, ["README.md", "qemu"]);
+
+            let qemu = provider
+                .list_repository_tree(Some("qemu"), false, None)
+                .await
+                .unwrap();
+            assert_eq!(qemu.len(), 1);
+            assert!(qemu[0].is_file());
+
+            let content = provider
+                .get_file_content("qemu/run.sh", None)
+                .await
+                .unwrap();
+            assert_eq!(content, b"qemu-system\n");
+
+            let info = provider
+                .get_file_info("qemu/run.sh", Some("main"))
+                .await
+                .unwrap();
+            assert_eq!(info.size, Some(12));
+            assert_eq!(info.name.as_deref(), Some("run.sh"));
+
+            assert!(
+                provider
+                    .get_file_info("qemu", None)
+                    .await
+                    .unwrap_err()
+                    .is_not_found()
+            );
+            assert!(!provider.file_exists("missing.sh", None).await.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_branch() {
+        let (_tmp, _work, bare) = create_test_repos();
+        let provider = create_provider(&bare);
+
+        let branch = provider.create_branch("feat/x", "main").await.unwrap();
+        assert_eq!(branch.name, "feat/x");
+        assert_eq!(branch.commit.message, "init");
+
+        let err = provider.create_branch("feat/x", "main").await.unwrap_err();
+        assert!(matches!(err, VktError::Conflict(_)));
+
+        let err = provider.create_branch("feat/y", "nope").await.unwrap_err();
+        assert!(err.is_not_found());
+
+        let err = provider
+            .create_branch("bad..name", "main")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VktError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn test_commit_files_on_branch() {
+        let (_tmp, _work, bare) = create_test_repos();
+        let provider = create_provider(&bare);
+        provider.create_branch("feat/x", "main").await.unwrap();
+
+        let encoded = base64::engine::general_purpose::STANDARD.encode("new content\n");
+        let response = provider
+            .create_or_update_file(
+                "tools/new.sh",
+                &encoded,
+                "feat/x",
+                "add tool\n\nSigned-off-by: Test <test@example.com>",
+                "Test",
+                "test@example.com",
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.content.path, "tools/new.sh");
+        assert_eq!(response.commit.author.unwrap().name, "Test");
+        assert!(response.commit.message.starts_with("add tool"));
+
+        let content = provider
+            .get_file_content("tools/new.sh", Some("feat/x"))
+            .await
+            .unwrap();
+        assert_eq!(content, b"new content\n");
+        // Untouched files survive, and the base branch is unchanged
+        assert!(
+            provider
+                .file_exists("README.md", Some("feat/x"))
+                .await
+                .unwrap()
+        );
+        assert!(
+            !provider
+                .file_exists("tools/new.sh", Some("main"))
+                .await
+                .unwrap()
+        );
+
+        let encoded = base64::engine::general_purpose::STANDARD.encode("updated\n");
+        provider
+            .create_or_update_file(
+                "qemu/run.sh",
+                &encoded,
+                "feat/x",
+                "update",
+                "Test",
+                "test@example.com",
+            )
+            .await
+            .unwrap();
+        let log = run_git(&bare, &["log", "--format=%s", "feat/x"]);
+        assert_eq!(
+            log.lines().collect::<Vec<_>>(),
+            ["update", "add tool", "init"]
+        );
+
+        let err = provider
+            .create_or_update_file(
+                "a.txt",
+                &encoded,
+                "missing",
+                "m",
+                "Test",
+                "test@example.com",
+            )
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_pull_requests_as_refs_and_metadata() {
+        let (_tmp, _work, bare) = create_test_repos();
+        let provider = create_provider(&bare);
+        provider.create_branch("feat/x", "main").await.unwrap();
+
+        let first = provider
+            .create_pull_request("[T] first", "feat/x", "main", Some("body"))
+            .await
+            .unwrap();
+        let second = provider
+            .create_pull_request("[T] second", "feat/x", "main", None)
+            .await
+            .unwrap();
+        assert_eq!((first.number, second.number), (1, 2));
+        assert_eq!(first.state, "open");
+        assert!(first.html_url.unwrap().ends_with("vkt/pulls/1.json"));
+
+        assert_eq!(
+            run_git(&bare, &["rev-parse", "refs/pull/1/head"]),
+            run_git(&bare, &["rev-parse", "feat/x"])
+        );
+
+        provider
+            .assign_reviewers(1, &["alice".to_string(), "alice".to_string()])
+            .await
+            .unwrap();
+        let record: PullRequestRecord =
+            serde_json::from_slice(&std::fs::read(bare.join("vkt/pulls/1.json")).unwrap()).unwrap();
+        assert_eq!(record.reviewers, ["alice"]);
+        assert_eq!(record.body.as_deref(), Some("body"));
+
+        let err = provider
+            .assign_reviewers(9, &["bob".to_string()])
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_repository_info() {
+        let (_tmp, _work, bare) = create_test_repos();
+        let info = create_provider(&bare).get_repository_info().await.unwrap();
+        assert_eq!(info.full_name, "lab/scripts");
+        assert_eq!(info.default_branch, "main");
+        assert!(info.description.is_none());
+    }
+}
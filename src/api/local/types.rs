@@ -0,0 +1,114 @@
+//! Local Provider Types
+//!
+//! Pull requests on a local repository are stored as JSON metadata files
+//! next to a `refs/pull/<n>/head` ref pointing at the proposed commit.
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::types::{PullRequest, PullRequestRef};
+
+/// A single parsed `git ls-tree -l` entry
+#[derive(Debug, Clone)]
+pub struct LsTreeEntry {
+    /// File mode (e.g., "100644")
+    pub mode: String,
+    /// Object type ("blob", "tree" or "commit")
+    pub object_type: String,
+    /// Object SHA
+    pub sha: String,
+    /// Blob size in bytes (None for trees and submodules)
+    pub size: Option<u64>,
+    /// Path relative to the repository root
+    pub path: String,
+}
+
+impl LsTreeEntry {
+    /// Parse one NUL-terminated record: `<mode> SP <type> SP <sha> SP* <size> TAB <path>`
+    pub fn parse(record: &str) -> Option<Self> {
+        let (meta, path) = record.split_once('\t')?;
+        let mut fields = meta.split_whitespace();
+        let mode = fields.next()?.to_string();
+        let object_type = fields.next()?.to_string();
+        let sha = fields.next()?.to_string();
+        let size = fields.next().and_then(|s| s.parse().ok());
+
+        Some(Self {
+            mode,
+            object_type,
+            sha,
+            size,
+            path: path.to_string(),
+        })
+    }
+}
+
+/// Pull request metadata file (`<git-dir>/vkt/pulls/<n>.json`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PullRequestRecord {
+    /// PR number
+    pub number: u64,
+    /// PR title
+    pub title: String,
+    /// PR body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// PR state ("open", "closed", "merged")
+    pub state: String,
+    /// Source branch
+    pub head: String,
+    /// Head commit at creation time (also stored in refs/pull/<n>/head)
+    pub head_sha: String,
+    /// Target branch
+    pub base: String,
+    /// Requested reviewers
+    #[serde(default)]
+    pub reviewers: Vec<String>,
+    /// Creation time (RFC 3339)
+    pub created_at: String,
+}
+
+impl PullRequestRecord {
+    /// Convert to the shared PullRequest type, using `html_url` as the link
+    pub fn to_pull_request(&self, html_url: Option<String>) -> PullRequest {
+        PullRequest {
+            number: self.number,
+            title: self.title.clone(),
+            html_url,
+            state: self.state.clone(),
+            head: Some(PullRequestRef {
+                ref_branch: self.head.clone(),
+                repo: None,
+            }),
+            base: Some(PullRequestRef {
+                ref_branch: self.base.clone(),
+                repo: None,
+            }),
+            body: self.body.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ls_tree_entry() {
+        let entry = LsTreeEntry::parse(
+            "100755 blob 3b18e512dba79e4c8300dd08aeb37f8e728b8dad      12\tqemu/run me.sh",
+        )
+        .unwrap();
+        assert_eq!(entry.mode, "100755");
+        assert_eq!(entry.object_type, "blob");
+        assert_eq!(entry.size, Some(12));
+        assert_eq!(entry.path, "qemu/run me.sh");
+
+        let tree = LsTreeEntry::parse(
+            "040000 tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904       -\tqemu",
+        )
+        .unwrap();
+        assert_eq!(tree.size, None);
+
+        assert!(LsTreeEntry::parse("garbage").is_none());
+    }
+}
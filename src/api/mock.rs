@@ -0,0 +1,641 @@
+//! In-Memory Mock Provider
+//!
+//! A ForgeProvider backed by an in-memory repository, for testing code that
+//! drives vkt as a library. Every mutating call is recorded so tests can
+//! assert on the branches, commits and pull requests a workflow produced.
+//!
+//! Available with the `testing` cargo feature.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::error::{Result, VktError};
+
+use super::traits::ForgeProvider;
+use super::types::{
+    Author, Branch, Capabilities, Commit, FileCommitResponse, FileContent, FileInfo,
+    PermissionReport, PullRequest, PullRequestRef, RepositoryInfo, TreeItem, filter_tree,
+};
+
+/// A mutating call made against a MockProvider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockCall {
+    /// `create_branch`
+    CreateBranch {
+        /// New branch name
+        name: String,
+        /// Source branch
+        source: String,
+    },
+    /// `create_or_update_file`
+    CreateOrUpdateFile {
+        /// File path
+        path: String,
+        /// Target branch
+        branch: String,
+        /// Decoded file content
+        content: Vec<u8>,
+        /// Commit message
+        message: String,
+        /// Author name
+        author_name: String,
+        /// Author email
+        author_email: String,
+    },
+    /// `create_pull_request`
+    CreatePullRequest {
+        /// PR title
+        title: String,
+        /// Source branch
+        head: String,
+        /// Target branch
+        base: String,
+        /// PR body
+        body: Option<String>,
+    },
+    /// `assign_reviewers`
+    AssignReviewers {
+        /// Pull request number
+        pr_number: u64,
+        /// Reviewer usernames
+        reviewers: Vec<String>,
+    },
+}
+
+/// A commit created through the mock
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockCommit {
+    /// Commit SHA
+    pub id: String,
+    /// Branch the commit was made on
+    pub branch: String,
+    /// File changed by the commit
+    pub path: String,
+    /// Commit message
+    pub message: String,
+    /// Author name
+    pub author_name: String,
+    /// Author email
+    pub author_email: String,
+}
+
+/// A pull request held by the mock
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockPullRequest {
+    /// PR number
+    pub number: u64,
+    /// PR title
+    pub title: String,
+    /// Source branch
+    pub head: String,
+    /// Target branch
+    pub base: String,
+    /// PR body
+    pub body: Option<String>,
+    /// Assigned reviewers
+    pub reviewers: Vec<String>,
+}
+
+impl From<&MockPullRequest> for PullRequest {
+    fn from(pr: &MockPullRequest) -> Self {
+        PullRequest {
+            number: pr.number,
+            title: pr.title.clone(),
+            html_url: Some(format!("mock://pulls/{}", pr.number)),
+            state: "open".to_string(),
+            head: Some(PullRequestRef {
+                ref_branch: pr.head.clone(),
+                repo: None,
+            }),
+            base: Some(PullRequestRef {
+                ref_branch: pr.base.clone(),
+                repo: None,
+            }),
+            body: pr.body.clone(),
+        }
+    }
+}
+
+/// Branch state: head commit plus a full snapshot of its files
+#[derive(Debug, Clone, Default)]
+struct MockBranch {
+    head: String,
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    default_branch: String,
+    branches: BTreeMap<String, MockBranch>,
+    commits: Vec<MockCommit>,
+    pulls: Vec<MockPullRequest>,
+    calls: Vec<MockCall>,
+    next_commit: u64,
+    capabilities: Capabilities,
+    permissions: PermissionReport,
+    stale_since: Option<DateTime<Utc>>,
+    handles: usize,
+    latency: Option<Duration>,
+    downloads_in_flight: usize,
+    peak_downloads: usize,
+}
+
+impl MockState {
+    fn next_commit_id(&mut self) -> String {
+        self.next_commit += 1;
+        format!("{:040x}", self.next_commit)
+    }
+
+    fn branch(&self, name: Option<&str>) -> Result<&MockBranch> {
+        let name = name.unwrap_or(&self.default_branch);
+        self.branches
+            .get(name)
+            .ok_or_else(|| VktError::ApiNotFound(format!("Branch not found: {}", name)))
+    }
+}
+
+/// Hex SHA-256 of some content, truncated to git's SHA length
+fn content_sha(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))[..40].to_string()
+}
+
+/// In-memory ForgeProvider
+///
+/// Clones share the same repository, so a test can keep one handle for
+/// assertions while handing another to the code under test.
+#[derive(Debug)]
+pub struct MockProvider {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl Clone for MockProvider {
+    fn clone(&self) -> Self {
+        self.lock().handles += 1;
+        Self {
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+impl MockProvider {
+    /// Create a mock repository with an empty default branch
+    pub fn new(default_branch: &str) -> Self {
+        let mut state = MockState {
+            default_branch: default_branch.to_string(),
+            capabilities: Capabilities::all(),
+            handles: 1,
+            ..Default::default()
+        };
+        let head = state.next_commit_id();
+        state.branches.insert(
+            default_branch.to_string(),
+            MockBranch {
+                head,
+                files: BTreeMap::new(),
+            },
+        );
+
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Seed a file on the default branch (not recorded as a call)
+    pub fn with_file(self, path: &str, content: impl Into<Vec<u8>>) -> Self {
+        let branch = self.lock().default_branch.clone();
+        self.with_branch_file(&branch, path, content)
+    }
+
+    /// Seed a file on a branch, creating the branch if needed (not recorded as a call)
+    pub fn with_branch_file(self, branch: &str, path: &str, content: impl Into<Vec<u8>>) -> Self {
+        {
+            let mut state = self.lock();
+            let head = state.next_commit_id();
+            let entry = state.branches.entry(branch.to_string()).or_default();
+            entry.head = head;
+            entry
+                .files
+                .insert(path.trim_matches('/').to_string(), content.into());
+        }
+        self
+    }
+
+    /// Override the reported capabilities (all are supported by default)
+    pub fn with_capabilities(self, capabilities: Capabilities) -> Self {
+        self.lock().capabilities = capabilities;
+        self
+    }
+
+    /// Override the pre-flight permission report (nothing is denied by default)
+    pub fn with_permissions(self, permissions: PermissionReport) -> Self {
+        self.lock().permissions = permissions;
+        self
+    }
+
+    /// Pretend answers come from the offline cache, fetched at `fetched_at`
+    pub fn with_stale_since(self, fetched_at: DateTime<Utc>) -> Self {
+        self.lock().stale_since = Some(fetched_at);
+        self
+    }
+
+    /// Delay every file download by `latency`, so that concurrent downloads overlap
+    pub fn with_latency(self, latency: Duration) -> Self {
+        self.lock().latency = Some(latency);
+        self
+    }
+
+    /// Number of handles to this repository: the original and all its clones
+    pub fn handles(&self) -> usize {
+        self.lock().handles
+    }
+
+    /// Most file downloads that were in progress at the same time
+    pub fn peak_downloads(&self) -> usize {
+        self.lock().peak_downloads
+    }
+
+    /// All mutating calls, in order
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.lock().calls.clone()
+    }
+
+    /// Names of all branches
+    pub fn branches(&self) -> Vec<String> {
+        self.lock().branches.keys().cloned().collect()
+    }
+
+    /// Content of a file on a branch
+    pub fn file(&self, branch: &str, path: &str) -> Option<Vec<u8>> {
+        self.lock()
+            .branches
+            .get(branch)?
+            .files
+            .get(path.trim_matches('/'))
+            .cloned()
+    }
+
+    /// Commits created through `create_or_update_file`
+    pub fn commits(&self) -> Vec<MockCommit> {
+        self.lock().commits.clone()
+    }
+
+    /// Pull requests created through `create_pull_request`
+    pub fn pull_requests(&self) -> Vec<MockPullRequest> {
+        self.lock().pulls.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        // A panicking test must not cascade into unrelated assertions
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for MockProvider {
+    async fn list_repository_tree(
+        &self,
+        path: Option<&str>,
+        recursive: bool,
+        ref_branch: Option<&str>,
+    ) -> Result<Vec<TreeItem>> {
+        let state = self.lock();
+        let branch = state.branch(ref_branch)?;
+
+        let mut items: BTreeMap<String, TreeItem> = BTreeMap::new();
+        for (file_path, content) in &branch.files {
+            // Synthesize the directories leading to each file
+            let mut dir = String::new();
+            let segments: Vec<&str> = file_path.split('/').collect();
+            for segment in &segments[..segments.len() - 1] {
+                if !dir.is_empty() {
+                    dir.push('/');
+                }
+                dir.push_str(segment);
+                items.entry(dir.clone()).or_insert_with(|| TreeItem {
+                    id: content_sha(dir.as_bytes()),
+                    name: segment.to_string(),
+                    item_type: "tree".to_string(),
+                    path: dir.clone(),
+                    mode: "040000".to_string(),
+                });
+            }
+
+            items.insert(
+                file_path.clone(),
+                TreeItem {
+                    id: content_sha(content),
+                    name: segments[segments.len() - 1].to_string(),
+                    item_type: "blob".to_string(),
+                    path: file_path.clone(),
+                    mode: "100644".to_string(),
+                },
+            );
+        }
+
+        Ok(filter_tree(items.into_values().collect(), path, recursive))
+    }
+
+    async fn get_file_content(&self, file_path: &str, ref_branch: Option<&str>) -> Result<Vec<u8>> {
+        let (content, latency) = {
+            let mut state = self.lock();
+            state.downloads_in_flight += 1;
+            state.peak_downloads = state.peak_downloads.max(state.downloads_in_flight);
+            let content = state.branch(ref_branch).and_then(|branch| {
+                branch
+                    .files
+                    .get(file_path.trim_matches('/'))
+                    .cloned()
+                    .ok_or_else(|| VktError::ApiNotFound(format!("File not found: {}", file_path)))
+            });
+            (content, state.latency)
+        };
+        if let Some(latency) = latency {
+            tokio::time::sleep(latency).await;
+        }
+        self.lock().downloads_in_flight -= 1;
+        content
+    }
+
+    async fn get_file_info(&self, file_path: &str, ref_branch: Option<&str>) -> Result<FileInfo> {
+        let path = file_path.trim_matches('/');
+        let content = self.get_file_content(path, ref_branch).await?;
+
+        Ok(FileInfo {
+            name: path.rsplit('/').next().map(|n| n.to_string()),
+            path: Some(path.to_string()),
+            size: Some(content.len() as u64),
+            sha: Some(content_sha(&content)),
+            content: Some(base64::engine::general_purpose::STANDARD.encode(content)),
+        })
+    }
+
+    async fn create_branch(&self, branch_name: &str, source_branch: &str) -> Result<Branch> {
+        let mut state = self.lock();
+        state.calls.push(MockCall::CreateBranch {
+            name: branch_name.to_string(),
+            source: source_branch.to_string(),
+        });
+
+        let source = state.branch(Some(source_branch))?.clone();
+        if state.branches.contains_key(branch_name) {
+            return Err(VktError::Conflict(format!(
+                "Branch already exists: {}",
+                branch_name
+            )));
+        }
+
+        let commit = Commit {
+            id: source.head.clone(),
+            message: String::new(),
+            author: None,
+            timestamp: None,
+        };
+        state.branches.insert(branch_name.to_string(), source);
+
+        Ok(Branch {
+            name: branch_name.to_string(),
+            commit,
+        })
+    }
+
+    async fn create_or_update_file(
+        &self,
+        file_path: &str,
+        content: &str,
+        branch: &str,
+        message: &str,
+        author_name: &str,
+        author_email: &str,
+    ) -> Result<FileCommitResponse> {
+        let path = file_path.trim_matches('/').to_string();
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(content)
+            .map_err(|e| VktError::Validation(format!("Invalid base64 content: {}", e)))?;
+
+        let mut state = self.lock();
+        state.calls.push(MockCall::CreateOrUpdateFile {
+            path: path.clone(),
+            branch: branch.to_string(),
+            content: decoded.clone(),
+            message: message.to_string(),
+            author_name: author_name.to_string(),
+            author_email: author_email.to_string(),
+        });
+
+        state.branch(Some(branch))?;
+        let id = state.next_commit_id();
+        let target = state
+            .branches
+            .get_mut(branch)
+            .expect("branch checked above");
+        target.head = id.clone();
+        target.files.insert(path.clone(), decoded.clone());
+
+        state.commits.push(MockCommit {
+            id: id.clone(),
+            branch: branch.to_string(),
+            path: path.clone(),
+            message: message.to_string(),
+            author_name: author_name.to_string(),
+            author_email: author_email.to_string(),
+        });
+
+        Ok(FileCommitResponse {
+            content: FileContent {
+                name: path.rsplit('/').next().unwrap_or(&path).to_string(),
+                path: path.clone(),
+                sha: content_sha(&decoded),
+                size: Some(decoded.len() as u64),
+                download_url: None,
+            },
+            commit: Commit {
+                id,
+                message: message.to_string(),
+                author: Some(Author {
+                    name: author_name.to_string(),
+                    email: author_email.to_string(),
+                    date: None,
+                }),
+                timestamp: None,
+            },
+        })
+    }
+
+    async fn create_pull_request(
+        &self,
+        title: &str,
+        head_branch: &str,
+        base_branch: &str,
+        body: Option<&str>,
+    ) -> Result<PullRequest> {
+        let mut state = self.lock();
+        state.calls.push(MockCall::CreatePullRequest {
+            title: title.to_string(),
+            head: head_branch.to_string(),
+            base: base_branch.to_string(),
+            body: body.map(|b| b.to_string()),
+        });
+
+        state.branch(Some(head_branch))?;
+        state.branch(Some(base_branch))?;
+
+        let pull = MockPullRequest {
+            number: state.pulls.len() as u64 + 1,
+            title: title.to_string(),
+            head: head_branch.to_string(),
+            base: base_branch.to_string(),
+            body: body.map(|b| b.to_string()),
+            reviewers: Vec::new(),
+        };
+        let response = PullRequest::from(&pull);
+        state.pulls.push(pull);
+        Ok(response)
+    }
+
+    async fn assign_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        let mut state = self.lock();
+        state.calls.push(MockCall::AssignReviewers {
+            pr_number,
+            reviewers: reviewers.to_vec(),
+        });
+
+        let pull = state
+            .pulls
+            .iter_mut()
+            .find(|pr| pr.number == pr_number)
+            .ok_or_else(|| {
+                VktError::ApiNotFound(format!("Pull request not found: #{}", pr_number))
+            })?;
+        for reviewer in reviewers {
+            if !pull.reviewers.contains(reviewer) {
+                pull.reviewers.push(reviewer.clone());
+            }
+        }
+        Ok(())
+    }
+
+    async fn capabilities(&self) -> Result<Capabilities> {
+        Ok(self.lock().capabilities.clone())
+    }
+
+    async fn check_permissions(
+        &self,
+        _head_branch: &str,
+        _base_branch: &str,
+    ) -> Result<PermissionReport> {
+        Ok(self.lock().permissions.clone())
+    }
+
+    fn stale_since(&self) -> Option<DateTime<Utc>> {
+        self.lock().stale_since
+    }
+
+    async fn get_repository_info(&self) -> Result<RepositoryInfo> {
+        let state = self.lock();
+        Ok(RepositoryInfo {
+            id: 1,
+            full_name: "mock/repo".to_string(),
+            description: None,
+            default_branch: state.default_branch.clone(),
+            private: Some(false),
+            html_url: None,
+            clone_url: None,
+            ssh_url: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(content: &str) -> String {
+        base64::engine::general_purpose::STANDARD.encode(content)
+    }
+
+    #[tokio::test]
+    async fn test_tree_synthesizes_directories() {
+        let mock = MockProvider::new("main")
+            .with_file("README.md", "# repo\n")
+            .with_file("scripts/qemu/run.sh", "qemu\n");
+
+        let root = mock.list_repository_tree(None, false, None).await.unwrap();
+        let names: Vec<_> = root.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["README.md", "scripts"]);
+        assert!(root[1].is_dir());
+
+        let all = mock.list_repository_tree(None, true, None).await.unwrap();
+        assert_eq!(all.len(), 4);
+
+        assert!(
+            mock.list_repository_tree(None, false, Some("nope"))
+                .await
+                .unwrap_err()
+                .is_not_found()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_records_mutating_calls() {
+        let mock = MockProvider::new("main").with_file("README.md", "# repo\n");
+        let provider: Box<dyn ForgeProvider> = Box::new(mock.clone());
+
+        provider.create_branch("feat/x", "main").await.unwrap();
+        provider
+            .create_or_update_file("a/b.txt", &encode("hi"), "feat/x", "add b", "T", "t@e.com")
+            .await
+            .unwrap();
+        let pr = provider
+            .create_pull_request("[T] add", "feat/x", "main", Some("body"))
+            .await
+            .unwrap();
+        provider
+            .assign_reviewers(pr.number, &["alice".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(mock.calls().len(), 4);
+        assert_eq!(
+            mock.calls()[0],
+            MockCall::CreateBranch {
+                name: "feat/x".to_string(),
+                source: "main".to_string()
+            }
+        );
+        assert_eq!(mock.file("feat/x", "a/b.txt"), Some(b"hi".to_vec()));
+        assert_eq!(mock.file("main", "a/b.txt"), None);
+        assert_eq!(mock.commits()[0].message, "add b");
+        assert_eq!(mock.pull_requests()[0].body.as_deref(), Some("body"));
+        assert_eq!(mock.pull_requests()[0].reviewers, ["alice"]);
+    }
+
+    #[tokio::test]
+    async fn test_errors_match_real_providers() {
+        let mock = MockProvider::new("main");
+        mock.create_branch("feat/x", "main").await.unwrap();
+
+        let err = mock.create_branch("feat/x", "main").await.unwrap_err();
+        assert!(matches!(err, VktError::Conflict(_)));
+        assert!(
+            mock.get_file_info("missing", None)
+                .await
+                .unwrap_err()
+                .is_not_found()
+        );
+        assert!(!mock.file_exists("missing", None).await.unwrap());
+        assert!(
+            mock.assign_reviewers(7, &["bob".to_string()])
+                .await
+                .unwrap_err()
+                .is_not_found()
+        );
+        // Failed calls are still recorded
+        assert_eq!(mock.calls().len(), 3);
+    }
+}
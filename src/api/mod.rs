@@ -1,49 +1,60 @@
 //! Git/Forge API Client Module
 //!
 //! Provides a provider-based abstraction for Git hosting APIs.
-//! Supports GitCode, GitLab, GitHub, and other Git hosting providers.
+//! Supports GitCode, GitLab, GitHub, Gitea/Forgejo, Gitee, local git repositories,
+//! and other Git hosting providers.
 
 // Module declarations
+pub mod auth;
+pub mod auto;
+pub mod cache;
 pub mod compat;
 pub mod factory;
 pub mod gitcode;
+pub mod gitea;
+pub mod gitee;
+pub mod github;
+pub mod gitlab;
+pub mod local;
+#[cfg(any(test, feature = "testing"))]
+pub mod mock;
+pub mod pagination;
+pub mod rate_limit;
+pub mod rest;
 pub mod traits;
+pub mod transport;
 pub mod types;
 
 // Re-export main types for convenience
+pub use cache::ResponseCache;
 pub use compat::ApiClient;
 pub use factory::create_provider;
+pub use pagination::{Cursor, Page, paginate};
+pub use rate_limit::{RateLimit, RateLimiter};
 pub use traits::ForgeProvider;
+pub use transport::{Cassette, Transport};
 pub use types::*;
 
-// Re-export gitcode types
+// Re-export provider implementations
+pub use auto::AutoProvider;
 pub use gitcode::GitCodeProvider;
+pub use gitea::GiteaProvider;
+pub use gitee::GiteeProvider;
+pub use github::GitHubProvider;
+pub use gitlab::GitLabProvider;
+pub use local::LocalProvider;
+#[cfg(any(test, feature = "testing"))]
+pub use mock::{MockCall, MockCommit, MockProvider, MockPullRequest};
 
 // Re-export factory function
 pub use factory::detect_provider;
 
 #[cfg(test)]
 mod tests {
-    use crate::config::{Config, RemoteConfig, RepoConfig, TemplateConfig, UserConfig};
+    use crate::config::Config;
 
     fn create_test_config(base_url: String) -> Config {
-        Config {
-            user: UserConfig {
-                name: "Test".to_string(),
-                email: "test@example.com".to_string(),
-                auto_signoff: true,
-            },
-            remote: RemoteConfig {
-                provider: "Gitcode".to_string(),
-                api_url: base_url,
-                token: "test-token".to_string(),
-            },
-            repo: RepoConfig {
-                project_id: "owner/repo".to_string(),
-                default_branch: "main".to_string(),
-            },
-            template: TemplateConfig::default(),
-        }
+        Config::for_tests("Gitcode", &base_url)
     }
 
     #[test]
@@ -140,6 +151,42 @@ mod tests {
         assert!(provider.is_ok());
     }
 
+    #[test]
+    fn test_create_provider_github() {
+        let mut config = create_test_config("https://api.github.com".to_string());
+        config.remote.provider = "GitHub".to_string();
+        let provider = super::create_provider(&config);
+        assert!(provider.is_ok());
+    }
+
+    #[test]
+    fn test_create_provider_gitlab() {
+        let mut config = create_test_config("https://gitlab.example.com/api/v4".to_string());
+        config.remote.provider = "GitLab".to_string();
+        config.repo.project_id = "1234".to_string();
+        let provider = super::create_provider(&config);
+        assert!(provider.is_ok());
+    }
+
+    #[test]
+    fn test_create_provider_gitea_and_gitee() {
+        let mut config = create_test_config("https://codeberg.org/api/v1".to_string());
+        config.remote.provider = "Forgejo".to_string();
+        assert!(super::create_provider(&config).is_ok());
+
+        config.remote.api_url = "https://gitee.com/api/v5".to_string();
+        config.remote.provider = "Gitee".to_string();
+        assert!(super::create_provider(&config).is_ok());
+    }
+
+    #[test]
+    fn test_create_provider_auto() {
+        let mut config = create_test_config("https://git.corp.example/api/v4".to_string());
+        config.remote.provider = "auto".to_string();
+        // Detection is deferred until the first call
+        assert!(super::create_provider(&config).is_ok());
+    }
+
     #[test]
     fn test_provider_type_detection() {
         use super::factory::detect_provider;
@@ -0,0 +1,239 @@
+//! Pagination
+//!
+//! List endpoints return their results in pages. Forges signal the next page
+//! in different ways:
+//! - an RFC 8288 `Link: <url>; rel="next"` header (GitHub, Gitea, GitCode, Gitee,
+//!   GitLab keyset pagination)
+//! - `page`/`per_page` query parameters, with `X-Next-Page` (GitLab) or a
+//!   short/`truncated` page marking the end
+//! - an opaque cursor token returned with the page
+//!
+//! [`paginate`] turns a page-fetching closure into an async stream of items,
+//! requesting further pages only as the stream is consumed.
+
+use std::future::Future;
+
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use reqwest::header::HeaderMap;
+
+use crate::error::Result;
+
+/// Position of a page in a paginated listing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cursor {
+    /// Absolute URL of the page (from a `Link` header)
+    Url(String),
+    /// Page number for `page`/`per_page` pagination (1-based)
+    Page(u32),
+    /// Opaque cursor token (keyset or GraphQL-style pagination)
+    Token(String),
+}
+
+impl Cursor {
+    /// The first page of a page-number listing
+    pub fn first() -> Self {
+        Cursor::Page(1)
+    }
+
+    /// Apply the cursor to the path or URL of the first page
+    ///
+    /// Page numbers and tokens are appended as `page=` and `cursor=` query
+    /// parameters; a URL cursor replaces `base` entirely.
+    pub fn apply(&self, base: &str) -> String {
+        let separator = if base.contains('?') { '&' } else { '?' };
+        match self {
+            Cursor::Url(url) => url.clone(),
+            Cursor::Page(page) => format!("{}{}page={}", base, separator, page),
+            Cursor::Token(token) => {
+                format!("{}{}cursor={}", base, separator, urlencoding::encode(token))
+            }
+        }
+    }
+
+    /// The following page number, for listings that only mark the last page
+    /// (e.g. a `truncated` flag or a short page)
+    ///
+    /// # Returns
+    /// None for URL and token cursors, which cannot be advanced locally
+    pub fn next_page(&self) -> Option<Cursor> {
+        match self {
+            Cursor::Page(page) => Some(Cursor::Page(page + 1)),
+            _ => None,
+        }
+    }
+}
+
+/// One page of a listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    /// Items on this page
+    pub items: Vec<T>,
+    /// Where the next page is, or None on the last page
+    pub next: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    /// Create a page
+    pub fn new(items: Vec<T>, next: Option<Cursor>) -> Self {
+        Self { items, next }
+    }
+}
+
+/// Read the next-page cursor from response headers
+///
+/// Prefers `Link: <url>; rel="next"` and falls back to `X-Next-Page`.
+///
+/// # Returns
+/// None when the headers mark this as the last page (or carry no pagination)
+pub fn next_from_headers(headers: &HeaderMap) -> Option<Cursor> {
+    let link = headers
+        .get_all("link")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find_map(next_link);
+    if let Some(url) = link {
+        return Some(Cursor::Url(url));
+    }
+
+    headers
+        .get("x-next-page")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .map(Cursor::Page)
+}
+
+/// Extract the `rel="next"` target from a `Link` header value
+fn next_link(header: &str) -> Option<String> {
+    header.split(',').find_map(|link| {
+        let mut parts = link.split(';');
+        let url = parts.next()?.trim().strip_prefix('<')?.strip_suffix('>')?;
+        parts
+            .filter_map(|param| param.trim().strip_prefix("rel="))
+            .any(|rel| {
+                rel.trim_matches('"')
+                    .split_whitespace()
+                    .any(|r| r == "next")
+            })
+            .then(|| url.to_string())
+    })
+}
+
+/// Stream every item of a paginated listing
+///
+/// # Arguments
+/// * `first` - Cursor of the first page
+/// * `fetch` - Fetches the page at a cursor
+///
+/// # Returns
+/// A stream that fetches pages lazily and yields their items in order. The
+/// stream ends after a page without a next cursor, an empty page, or a next
+/// cursor equal to the current one; it yields the error and ends if a fetch fails.
+pub fn paginate<'a, T, F, Fut>(first: Cursor, fetch: F) -> BoxStream<'a, Result<T>>
+where
+    T: Send + 'a,
+    F: FnMut(Cursor) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<Page<T>>> + Send + 'a,
+{
+    stream::try_unfold((Some(first), fetch), |(cursor, mut fetch)| async move {
+        let Some(cursor) = cursor else {
+            return Result::Ok(None);
+        };
+        let page = fetch(cursor.clone()).await?;
+        let next = page
+            .next
+            .filter(|next| *next != cursor && !page.items.is_empty());
+        Ok(Some((page.items, (next, fetch))))
+    })
+    .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+    .try_flatten()
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::VktError;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_cursor_apply() {
+        assert_eq!(
+            Cursor::Page(2).apply("repos/o/r/pulls"),
+            "repos/o/r/pulls?page=2"
+        );
+        assert_eq!(
+            Cursor::Page(3).apply("tree?per_page=100"),
+            "tree?per_page=100&page=3"
+        );
+        assert_eq!(
+            Cursor::Token("a b".to_string()).apply("commits"),
+            "commits?cursor=a%20b"
+        );
+        assert_eq!(
+            Cursor::Url("https://forge.example/api?page=2".to_string()).apply("ignored"),
+            "https://forge.example/api?page=2"
+        );
+        assert_eq!(Cursor::first().next_page(), Some(Cursor::Page(2)));
+    }
+
+    #[test]
+    fn test_next_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "link",
+            HeaderValue::from_static(
+                r#"<https://api.example/items?page=1>; rel="prev", <https://api.example/items?page=3>; rel="next""#,
+            ),
+        );
+        headers.insert("x-next-page", HeaderValue::from_static("7"));
+        assert_eq!(
+            next_from_headers(&headers),
+            Some(Cursor::Url("https://api.example/items?page=3".to_string()))
+        );
+
+        headers.insert(
+            "link",
+            HeaderValue::from_static(r#"<https://api.example/items?page=1>; rel="first""#),
+        );
+        assert_eq!(next_from_headers(&headers), Some(Cursor::Page(7)));
+
+        headers.insert("x-next-page", HeaderValue::from_static(""));
+        assert_eq!(next_from_headers(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_streams_all_pages() {
+        let items: Vec<u32> = paginate(Cursor::first(), |cursor| async move {
+            let Cursor::Page(page) = cursor else {
+                panic!("unexpected cursor");
+            };
+            let next = (page < 3).then(|| Cursor::Page(page + 1));
+            Ok(Page::new(vec![page * 10, page * 10 + 1], next))
+        })
+        .try_collect()
+        .await
+        .unwrap();
+        assert_eq!(items, vec![10, 11, 20, 21, 30, 31]);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_stops_on_repeated_cursor_and_errors() {
+        let items: Vec<u32> = paginate(Cursor::first(), |cursor| async move {
+            Ok(Page::new(vec![1], Some(cursor)))
+        })
+        .try_collect()
+        .await
+        .unwrap();
+        assert_eq!(items, vec![1]);
+
+        let mut stream = paginate(Cursor::first(), |cursor| async move {
+            match cursor {
+                Cursor::Page(1) => Ok(Page::new(vec![1], Some(Cursor::Page(2)))),
+                _ => Err(VktError::Network("connection reset".to_string())),
+            }
+        });
+        assert_eq!(stream.next().await.unwrap().unwrap(), 1);
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.is_none());
+    }
+}
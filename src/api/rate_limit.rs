@@ -0,0 +1,278 @@
+//! Rate Limit Tracking
+//!
+//! Forges report the remaining request quota in response headers:
+//! `X-RateLimit-*` (GitHub, GitCode, Gitea, Gitee) or the IETF-style
+//! `RateLimit-*` (GitLab). A [`RateLimiter`] keeps the latest snapshot for
+//! all requests of a provider and slows them down before the quota runs out,
+//! instead of letting a large download hit a wall of 403/429 responses.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+
+use crate::error::{Result, VktError};
+
+/// Remaining quota at or below which requests are spread out until the reset
+/// (used when the server does not report the total limit)
+const THROTTLE_REMAINING: u64 = 10;
+
+/// Reset values above this are Unix timestamps, below it delays in seconds
+const EPOCH_THRESHOLD: i64 = 1_000_000_000;
+
+/// Quota snapshot parsed from rate-limit response headers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Requests allowed per window, if reported
+    pub limit: Option<u64>,
+    /// Requests left in the current window
+    pub remaining: u64,
+    /// When the window resets, if reported
+    pub reset: Option<DateTime<Utc>>,
+}
+
+impl RateLimit {
+    /// Parse the rate-limit headers of a response
+    ///
+    /// # Returns
+    /// None if the response carries no remaining-quota header
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let remaining = header_number(headers, "remaining")?;
+        let limit = header_number(headers, "limit");
+        let reset = header_number(headers, "reset").and_then(|value| {
+            let value = i64::try_from(value).ok()?;
+            if value >= EPOCH_THRESHOLD {
+                Utc.timestamp_opt(value, 0).single()
+            } else {
+                Some(Utc::now() + chrono::Duration::seconds(value))
+            }
+        });
+
+        Some(Self {
+            limit,
+            remaining,
+            reset,
+        })
+    }
+
+    /// Whether the quota is used up
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Time left until the window resets (zero if it already has)
+    pub fn reset_in(&self) -> Option<Duration> {
+        self.reset
+            .map(|reset| (reset - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+    }
+
+    /// Human-readable reset time, e.g. "resets at 12:00:00 UTC (in 42s)"
+    pub fn describe_reset(&self) -> Option<String> {
+        let reset = self.reset?;
+        let seconds = self.reset_in().unwrap_or_default().as_secs();
+        Some(format!(
+            "resets at {} (in {}s)",
+            reset.format("%H:%M:%S UTC"),
+            seconds
+        ))
+    }
+
+    /// Whether requests should be spread out to make the quota last
+    fn is_low(&self) -> bool {
+        let threshold = self
+            .limit
+            .map(|limit| (limit / 10).max(1))
+            .unwrap_or(THROTTLE_REMAINING);
+        self.remaining <= threshold
+    }
+}
+
+/// Build a rate-limit error, mentioning the reset time when known
+pub fn rate_limited_error(message: String, rate_limit: Option<&RateLimit>) -> VktError {
+    match rate_limit.and_then(RateLimit::describe_reset) {
+        Some(reset) => VktError::RateLimited(format!("{} (rate limit {})", message, reset)),
+        None => VktError::RateLimited(message),
+    }
+}
+
+/// Read `x-ratelimit-<name>` or `ratelimit-<name>` as a number
+fn header_number(headers: &HeaderMap, name: &str) -> Option<u64> {
+    [
+        format!("x-ratelimit-{}", name),
+        format!("ratelimit-{}", name),
+    ]
+    .iter()
+    .find_map(|header| {
+        headers
+            .get(header.as_str())?
+            .to_str()
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    })
+}
+
+/// Shared request budget of a provider
+///
+/// Clones share the same budget, so concurrent requests see each other's
+/// consumption before the next response arrives.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    state: Arc<Mutex<Option<RateLimit>>>,
+}
+
+impl RateLimiter {
+    /// Create a limiter with no known budget
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The most recent quota snapshot
+    pub fn current(&self) -> Option<RateLimit> {
+        self.lock().clone()
+    }
+
+    /// Record the quota reported by a response
+    pub fn update(&self, headers: &HeaderMap) {
+        if let Some(rate_limit) = RateLimit::from_headers(headers) {
+            *self.lock() = Some(rate_limit);
+        }
+    }
+
+    /// Take one request from the budget
+    ///
+    /// # Arguments
+    /// * `max_wait` - Longest acceptable delay
+    ///
+    /// # Returns
+    /// How long to wait before sending: zero while the quota is healthy, a
+    /// share of the time until the reset once it runs low. Fails with
+    /// `RateLimited` if the quota is exhausted for longer than `max_wait`.
+    pub fn acquire(&self, max_wait: Duration) -> Result<Duration> {
+        let mut state = self.lock();
+        let Some(rate_limit) = state.as_mut() else {
+            return Ok(Duration::ZERO);
+        };
+
+        let reset_in = rate_limit.reset_in();
+        if reset_in == Some(Duration::ZERO) {
+            // The window has rolled over; wait for fresh numbers
+            *state = None;
+            return Ok(Duration::ZERO);
+        }
+
+        if rate_limit.is_exhausted() {
+            return match reset_in {
+                // Without a reset time only the server can tell when to continue
+                None => Ok(Duration::ZERO),
+                Some(wait) if wait <= max_wait => Ok(wait),
+                Some(_) => Err(rate_limited_error(
+                    "Request quota exhausted".to_string(),
+                    Some(rate_limit),
+                )),
+            };
+        }
+
+        let delay = match reset_in {
+            Some(reset_in) if rate_limit.is_low() => {
+                let share = u32::try_from(rate_limit.remaining + 1).unwrap_or(u32::MAX);
+                (reset_in / share).min(max_wait)
+            }
+            _ => Duration::ZERO,
+        };
+        rate_limit.remaining -= 1;
+        Ok(delay)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<RateLimit>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, String)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_parses_github_style_headers() {
+        let reset = Utc::now().timestamp() + 600;
+        let rate_limit = RateLimit::from_headers(&headers(&[
+            ("x-ratelimit-limit", "5000".to_string()),
+            ("x-ratelimit-remaining", "4999".to_string()),
+            ("x-ratelimit-reset", reset.to_string()),
+        ]))
+        .unwrap();
+
+        assert_eq!(rate_limit.limit, Some(5000));
+        assert_eq!(rate_limit.remaining, 4999);
+        assert_eq!(rate_limit.reset.unwrap().timestamp(), reset);
+        assert!(!rate_limit.is_exhausted());
+    }
+
+    #[test]
+    fn test_parses_delta_seconds_reset() {
+        let rate_limit = RateLimit::from_headers(&headers(&[
+            ("ratelimit-remaining", "0".to_string()),
+            ("ratelimit-reset", "60".to_string()),
+        ]))
+        .unwrap();
+
+        assert!(rate_limit.is_exhausted());
+        let reset_in = rate_limit.reset_in().unwrap();
+        assert!(reset_in > Duration::from_secs(55) && reset_in <= Duration::from_secs(60));
+        assert!(RateLimit::from_headers(&HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_throttles_when_quota_runs_low() {
+        let limiter = RateLimiter::new();
+        assert_eq!(limiter.acquire(Duration::MAX).unwrap(), Duration::ZERO);
+
+        limiter.update(&headers(&[
+            ("x-ratelimit-limit", "100".to_string()),
+            ("x-ratelimit-remaining", "50".to_string()),
+            ("x-ratelimit-reset", "100".to_string()),
+        ]));
+        assert_eq!(limiter.acquire(Duration::MAX).unwrap(), Duration::ZERO);
+        assert_eq!(limiter.current().unwrap().remaining, 49);
+
+        limiter.update(&headers(&[
+            ("x-ratelimit-limit", "100".to_string()),
+            ("x-ratelimit-remaining", "4".to_string()),
+            ("x-ratelimit-reset", "100".to_string()),
+        ]));
+        let delay = limiter.acquire(Duration::MAX).unwrap();
+        assert!(delay > Duration::from_secs(15) && delay <= Duration::from_secs(20));
+        assert_eq!(
+            limiter.acquire(Duration::from_secs(1)).unwrap(),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn test_exhausted_quota_fails_with_reset_time() {
+        let limiter = RateLimiter::new();
+        limiter.update(&headers(&[
+            ("x-ratelimit-remaining", "0".to_string()),
+            ("x-ratelimit-reset", "3600".to_string()),
+        ]));
+
+        let err = limiter.acquire(Duration::from_secs(30)).unwrap_err();
+        assert!(matches!(err, VktError::RateLimited(_)));
+        assert!(err.to_string().contains("resets at"));
+
+        let wait = limiter.acquire(Duration::from_secs(7200)).unwrap();
+        assert!(wait > Duration::from_secs(3500));
+    }
+}
@@ -0,0 +1,301 @@
+//! Shared REST Plumbing
+//!
+//! The HTTP providers only differ in how they authenticate and where their
+//! error bodies keep the message. Parsing project IDs, encoding paths,
+//! building URLs and mapping error responses to [`VktError`] is done here,
+//! so that every forge reports the same failure the same way.
+
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::rate_limit::{RateLimit, rate_limited_error};
+use super::types::{Permission, PermissionReport};
+use crate::config::ProviderType;
+use crate::error::{Result, VktError};
+
+/// Longest excerpt of an unparsable body quoted in error messages
+const BODY_EXCERPT_CHARS: usize = 200;
+
+/// Split a project ID in "owner/repo" format
+pub fn parse_project_id(project_id: &str) -> Result<(String, String)> {
+    let parts: Vec<&str> = project_id.split('/').collect();
+    if parts.len() != 2 || parts.iter().any(|p| p.is_empty()) {
+        return Err(VktError::Config(
+            "Project ID format should be 'owner/repo'".to_string(),
+        ));
+    }
+    Ok((parts[0].to_string(), parts[1].to_string()))
+}
+
+/// Percent-encode each segment of a repository path, keeping the slashes
+pub fn encode_path(path: &str) -> String {
+    path.trim_matches('/')
+        .split('/')
+        .map(|segment| urlencoding::encode(segment).into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Full URL of an API path
+///
+/// Absolute URLs below the API root (e.g. next-page links from the server)
+/// are passed through unchanged.
+pub fn build_url(base_url: &str, path: &str) -> String {
+    if path.starts_with(base_url) {
+        return path.to_string();
+    }
+    format!("{}/{}", base_url, path.trim_start_matches('/'))
+}
+
+/// Body of the `/version` endpoint of Gitea and GitLab
+#[derive(Debug, Deserialize)]
+pub struct VersionResponse {
+    /// Version string, e.g. "1.21.0", "16.0.0-ee" or "7.0.0+gitea-1.21.11"
+    pub version: String,
+}
+
+/// Major and minor number of a version string
+///
+/// Suffixes after the patch number ("-ee", "+dev") are ignored.
+pub fn parse_version(version: &str) -> Option<(u64, u64)> {
+    let mut parts = version
+        .trim()
+        .trim_start_matches('v')
+        .split(['.', '-', '+']);
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// Add the forge's authentication to a request
+///
+/// GitLab reads `PRIVATE-TOKEN`, Gitea a `token` authorization, Gitee an
+/// `access_token` query parameter; the others take a bearer token.
+pub fn authorize(provider: &ProviderType, request: RequestBuilder, token: &str) -> RequestBuilder {
+    match provider {
+        ProviderType::GitLab => request.header("PRIVATE-TOKEN", token),
+        ProviderType::Gitea => request.header("Authorization", format!("token {}", token)),
+        ProviderType::Gitee => request.query(&[("access_token", token)]),
+        _ => request.header("Authorization", format!("Bearer {}", token)),
+    }
+}
+
+/// Extract a readable message from an error body
+///
+/// The first of `fields` present is used. Nested messages (GitLab's
+/// `{"message": {"field": ["..."]}}`) are flattened, and GitHub-style
+/// `errors` details are appended. Bodies without any of the fields are
+/// returned as they are.
+pub fn error_message(body: &str, fields: &[&str]) -> String {
+    fn flatten(value: &Value) -> String {
+        match value {
+            Value::String(s) => s.clone(),
+            Value::Array(items) => items.iter().map(flatten).collect::<Vec<_>>().join("; "),
+            Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| format!("{} {}", k, flatten(v)))
+                .collect::<Vec<_>>()
+                .join("; "),
+            other => other.to_string(),
+        }
+    }
+
+    let Ok(json) = serde_json::from_str::<Value>(body) else {
+        return body.to_string();
+    };
+    let Some(message) = fields
+        .iter()
+        .find_map(|field| json.get(*field))
+        .map(flatten)
+        .filter(|message| !message.is_empty())
+    else {
+        return body.to_string();
+    };
+
+    let details: Vec<&str> = json
+        .get("errors")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.get("message").or_else(|| e.get("code"))?.as_str())
+        .collect();
+    if details.is_empty() {
+        message
+    } else {
+        format!("{} ({})", message, details.join("; "))
+    }
+}
+
+/// Map a non-success response of an API call to a VktError, passing
+/// successful responses through
+///
+/// A 403 from the forge's API is mostly a token without the needed scope, so
+/// its message points at `vkt auth status`.
+///
+/// # Arguments
+/// * `response` - The forge's response
+/// * `fields` - Fields of the error body that may hold the message, in order
+pub async fn check_status(response: Response, fields: &[&str]) -> Result<Response> {
+    error_for_status(response, fields)
+        .await
+        .map_err(|e| match e {
+            VktError::PermissionDenied(message) => VktError::PermissionDenied(format!(
+                "{} (check the token's scopes with `vkt auth status`)",
+                message
+            )),
+            e => e,
+        })
+}
+
+/// Map a non-success response to a VktError, passing successful responses through
+///
+/// # Arguments
+/// * `response` - The forge's response
+/// * `fields` - Fields of the error body that may hold the message, in order
+pub async fn error_for_status(response: Response, fields: &[&str]) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    // Some forges signal rate limiting with 403 and an exhausted quota
+    let rate_limit = RateLimit::from_headers(response.headers());
+    let rate_limited = rate_limit.as_ref().is_some_and(RateLimit::is_exhausted)
+        || response.headers().contains_key("retry-after");
+
+    let error_text = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unknown error".to_string());
+    let mut message = error_message(&error_text, fields);
+    if message.trim().is_empty() {
+        message = status.to_string();
+    }
+    let lower = message.to_lowercase();
+
+    Err(match status {
+        StatusCode::UNAUTHORIZED => VktError::AuthInvalid(message),
+        StatusCode::TOO_MANY_REQUESTS => rate_limited_error(message, rate_limit.as_ref()),
+        StatusCode::FORBIDDEN if rate_limited || lower.contains("rate limit") => {
+            rate_limited_error(message, rate_limit.as_ref())
+        }
+        StatusCode::FORBIDDEN => VktError::PermissionDenied(message),
+        StatusCode::NOT_FOUND => VktError::ApiNotFound(message),
+        StatusCode::CONFLICT => VktError::Conflict(message),
+        // Existing branches and files: 422 on GitHub and Gitea, 400 on GitLab and Gitee
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY
+            if lower.contains("already exist") || message.contains("已存在") =>
+        {
+            VktError::Conflict(message)
+        }
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => VktError::Validation(message),
+        _ => VktError::Api(format!("API error (HTTP {}): {}", status, message)),
+    })
+}
+
+/// Deny pull requests in `report` if the lookup of their base branch found nothing
+///
+/// Other failures of the lookup leave the decision to the forge when the
+/// pull request is opened.
+pub fn require_base_branch(status: StatusCode, base_branch: &str, report: &mut PermissionReport) {
+    match status {
+        StatusCode::NOT_FOUND => report.deny(
+            Permission::PullRequest,
+            format!("the base branch {} does not exist", base_branch),
+        ),
+        status if status.is_success() => {}
+        status => tracing::debug!("Base branch {} unavailable: HTTP {}", base_branch, status),
+    }
+}
+
+/// Check the status of a response and parse its JSON body
+///
+/// # Arguments
+/// * `response` - The forge's response
+/// * `fields` - Fields of the error body that may hold the message, in order
+pub async fn handle_response<T: DeserializeOwned>(
+    response: Response,
+    fields: &[&str],
+) -> Result<T> {
+    let response = check_status(response, fields).await?;
+    let text = response
+        .text()
+        .await
+        .map_err(|e| VktError::Api(format!("Failed to read response body: {}", e)))?;
+
+    serde_json::from_str(&text).map_err(|e| {
+        VktError::Api(format!(
+            "Failed to parse response: {}. Body: {}",
+            e,
+            if text.chars().count() > BODY_EXCERPT_CHARS {
+                format!(
+                    "{}...",
+                    text.chars().take(BODY_EXCERPT_CHARS).collect::<String>()
+                )
+            } else {
+                text
+            }
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_message() {
+        let fields = &["message", "error"];
+        assert_eq!(
+            error_message(r#"{"message": "Not Found"}"#, fields),
+            "Not Found"
+        );
+        assert_eq!(
+            error_message(
+                r#"{"message": {"name": ["has already been taken"]}}"#,
+                fields
+            ),
+            "name has already been taken"
+        );
+        assert_eq!(
+            error_message(r#"{"error": "invalid_token"}"#, fields),
+            "invalid_token"
+        );
+        assert_eq!(
+            error_message(
+                r#"{"message": "Validation Failed", "errors": [{"code": "already_exists"}]}"#,
+                fields
+            ),
+            "Validation Failed (already_exists)"
+        );
+        assert_eq!(
+            error_message("<html>502</html>", fields),
+            "<html>502</html>"
+        );
+        assert_eq!(error_message(r#"{"other": 1}"#, fields), r#"{"other": 1}"#);
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(encode_path("/dir/a b#.sh"), "dir/a%20b%23.sh");
+        assert_eq!(build_url("https://x/api", "/repos"), "https://x/api/repos");
+        assert_eq!(
+            build_url("https://x/api", "https://x/api/repos?page=2"),
+            "https://x/api/repos?page=2"
+        );
+        assert!(parse_project_id("owner/repo").is_ok());
+        assert!(parse_project_id("owner").is_err());
+        assert!(parse_project_id("owner/").is_err());
+        assert!(parse_project_id("group/sub/repo").is_err());
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("1.21.0"), Some((1, 21)));
+        assert_eq!(parse_version("16.0.0-ee"), Some((16, 0)));
+        assert_eq!(parse_version("v1.14.0+dev-12-gabcdef"), Some((1, 14)));
+        assert_eq!(parse_version("unknown"), None);
+    }
+}
@@ -4,10 +4,14 @@
 //! This trait abstracts over different Git hosting providers (GitCode, GitLab, GitHub, etc.)
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 use crate::error::Result;
 
-use super::types::{Branch, FileCommitResponse, FileInfo, PullRequest, RepositoryInfo, TreeItem};
+use super::types::{
+    Branch, Capabilities, FileCommitResponse, FileInfo, PermissionReport, PullRequest,
+    RepositoryInfo, TreeItem,
+};
 
 /// Forge Provider trait
 ///
@@ -114,6 +118,38 @@ pub trait ForgeProvider: Send + Sync {
     /// Repository metadata
     async fn get_repository_info(&self) -> Result<RepositoryInfo>;
 
+    /// Get the optional features this provider supports
+    ///
+    /// Commands consult this before making calls so that unsupported features
+    /// are skipped with a clear message instead of failing half-way.
+    ///
+    /// # Default Implementation
+    /// The default implementation reports no optional features.
+    async fn capabilities(&self) -> Result<Capabilities> {
+        Ok(Capabilities::default())
+    }
+
+    /// Check whether the token may push, create `head_branch` and open a pull
+    /// request from it into `base_branch`, without writing anything
+    ///
+    /// Commands call this before their first write, so that missing access is
+    /// reported up front instead of leaving e.g. an orphan branch behind.
+    ///
+    /// # Arguments
+    /// * `head_branch` - Branch that will be created
+    /// * `base_branch` - Branch the pull request will target
+    ///
+    /// # Default Implementation
+    /// The default implementation reports nothing and leaves the decision to
+    /// the forge when the writes happen.
+    async fn check_permissions(
+        &self,
+        _head_branch: &str,
+        _base_branch: &str,
+    ) -> Result<PermissionReport> {
+        Ok(PermissionReport::default())
+    }
+
     /// Check if a file exists
     ///
     /// # Arguments
@@ -132,4 +168,15 @@ pub trait ForgeProvider: Send + Sync {
             Err(e) => Err(e),
         }
     }
+
+    /// Fetch time of the oldest cached answer given in offline mode
+    ///
+    /// # Returns
+    /// None if every answer so far came from the forge itself
+    ///
+    /// # Default Implementation
+    /// The default implementation never serves cached answers.
+    fn stale_since(&self) -> Option<DateTime<Utc>> {
+        None
+    }
 }
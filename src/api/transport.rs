@@ -0,0 +1,1303 @@
+//! HTTP Transport
+//!
+//! Every HTTP provider sends its requests through a [`Transport`]. In live mode
+//! this is a thin wrapper around `reqwest::Client`; it can also record each
+//! request/response pair to a cassette file, or replay a cassette without
+//! touching the network.
+//!
+//! Recording is enabled with `VKT_CASSETTE=<file>` and `VKT_CASSETTE_MODE=record`;
+//! `VKT_CASSETTE_MODE=replay` (the default when `VKT_CASSETTE` is set) serves
+//! responses from the file. Tokens are redacted before anything is written.
+//!
+//! The underlying `reqwest::Client` is built from the `[network]` section of
+//! the configuration (proxy, extra CA certificates, client certificate,
+//! timeouts, user agent) by [`client_builder`].
+//!
+//! Live GET responses are stored in a [`ResponseCache`] and revalidated with
+//! `If-None-Match`/`If-Modified-Since` on the next request. In offline mode
+//! GET requests are answered from the cache regardless of age, the fetch time
+//! of the oldest answer is reported by [`Transport::stale_since`], and every
+//! other request fails with [`VktError::Offline`].
+//!
+//! Transient failures (gateway errors, rate limits, dropped connections) are
+//! retried with exponential backoff according to a [`RetryPolicy`], and the
+//! rate-limit headers of every response feed a shared [`RateLimiter`] that
+//! throttles requests before the quota runs out.
+
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, RandomState};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH};
+use reqwest::{
+    Certificate, Client, ClientBuilder, Identity, Method, NoProxy, Proxy, Request, RequestBuilder,
+    Response, StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::cache::{CacheEntry, ResponseCache};
+use super::rate_limit::{RateLimit, RateLimiter};
+use crate::config::{Config, NetworkConfig, RetryConfig};
+use crate::error::{Result, VktError};
+
+/// Environment variable naming the cassette file
+pub const CASSETTE_ENV: &str = "VKT_CASSETTE";
+
+/// Environment variable selecting `record` or `replay`
+pub const CASSETTE_MODE_ENV: &str = "VKT_CASSETTE_MODE";
+
+/// Placeholder written in place of secrets
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Headers whose values are never written to a cassette
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "private-token",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+];
+
+/// Query parameters whose values are never written to a cassette
+const SENSITIVE_PARAMS: &[&str] = &["access_token", "private_token", "token"];
+
+/// A recorded request
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct RecordedRequest {
+    /// HTTP method
+    pub method: String,
+    /// Full URL, with secrets redacted
+    pub url: String,
+    /// Request headers, with secrets redacted
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Request body (text, or base64 when `body_base64` is set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Whether `body` is base64-encoded binary data
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub body_base64: bool,
+}
+
+/// A recorded response
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct RecordedResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// Response headers, with secrets redacted
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Response body (text, or base64 when `body_base64` is set)
+    #[serde(default)]
+    pub body: String,
+    /// Whether `body` is base64-encoded binary data
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub body_base64: bool,
+}
+
+/// One request/response pair
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Interaction {
+    /// The request as sent
+    pub request: RecordedRequest,
+    /// The response as received
+    pub response: RecordedResponse,
+}
+
+/// A sequence of recorded interactions, stored as JSON
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct Cassette {
+    /// Interactions in the order they were recorded
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Load a cassette from a JSON file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            VktError::Config(format!("Failed to read cassette {}: {}", path.display(), e))
+        })?;
+        Self::from_json(&content)
+    }
+
+    /// Parse a cassette from a JSON string
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| VktError::Config(format!("Invalid cassette: {}", e)))
+    }
+
+    /// Write the cassette as pretty-printed JSON, creating parent directories
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| VktError::Api(format!("Failed to serialize cassette: {}", e)))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+/// Start an HTTP client configured from the `[network]` settings
+///
+/// # Returns
+/// A builder so callers can add request-specific settings (e.g. a shorter
+/// overall timeout); fails if a certificate file cannot be read or parsed
+pub fn client_builder(network: &NetworkConfig) -> Result<ClientBuilder> {
+    let mut builder = Client::builder()
+        .user_agent(network.user_agent())
+        .connect_timeout(Duration::from_secs(network.connect_timeout_secs))
+        .read_timeout(Duration::from_secs(network.read_timeout_secs));
+
+    if !network.proxy.is_empty() {
+        let proxy = Proxy::all(network.proxy.as_str())
+            .map_err(|e| VktError::Config(format!("Invalid proxy '{}': {}", network.proxy, e)))?
+            .no_proxy(NoProxy::from_string(&network.no_proxy));
+        builder = builder.proxy(proxy);
+    }
+
+    if !network.ca_bundle.is_empty() {
+        let pem = read_pem(&network.ca_bundle, "CA bundle")?;
+        let certificates = Certificate::from_pem_bundle(&pem).map_err(|e| {
+            VktError::Config(format!("Invalid CA bundle '{}': {}", network.ca_bundle, e))
+        })?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    if !network.client_cert.is_empty() {
+        let cert = read_pem(&network.client_cert, "client certificate")?;
+        let key = read_pem(&network.client_key, "client key")?;
+        let identity = Identity::from_pkcs8_pem(&cert, &key)
+            .map_err(|e| VktError::Config(format!("Invalid client certificate: {}", e)))?;
+        builder = builder.identity(identity);
+    }
+
+    Ok(builder)
+}
+
+/// Read a PEM file named in the network configuration
+fn read_pem(path: &str, what: &str) -> Result<Vec<u8>> {
+    std::fs::read(path)
+        .map_err(|e| VktError::Config(format!("Cannot read {} '{}': {}", what, path, e)))
+}
+
+/// Send a single GET to `url`, without retries, cache or cassette
+///
+/// Used to tell whether the forge can be reached at all: any response counts,
+/// whatever its status.
+///
+/// # Errors
+/// `Network` with the whole chain of causes (e.g. the certificate
+/// verification failure behind a TLS error) if no response arrives
+pub async fn probe(url: &str, network: &NetworkConfig) -> Result<StatusCode> {
+    let client = client_builder(network)?
+        .build()
+        .map_err(|e| VktError::Network(e.to_string()))?;
+    match client.get(url).send().await {
+        Ok(response) => Ok(response.status()),
+        Err(e) => {
+            let mut message = e.to_string();
+            let mut source = std::error::Error::source(&e);
+            while let Some(cause) = source {
+                message = format!("{}: {}", message, cause);
+                source = cause.source();
+            }
+            Err(VktError::Network(message))
+        }
+    }
+}
+
+/// When and how often to retry failed requests
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on every attempt
+    pub initial_delay: Duration,
+    /// Upper bound for a single delay; a longer Retry-After is not waited for
+    pub max_delay: Duration,
+    /// Retry writes after failures the server may have acted on (5xx, rate limits)
+    ///
+    /// Writes whose connection failed before sending are retried regardless.
+    pub retry_mutations: bool,
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            initial_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+            retry_mutations: false,
+        }
+    }
+
+    /// Backoff before retry number `attempt` (0-based): exponential with jitter
+    ///
+    /// The delay is drawn from the upper half of the exponential step so that
+    /// concurrent clients spread out without ever retrying immediately.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let step = self
+            .initial_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let half = step / 2;
+        let spread = half.as_millis() as u64;
+        let jitter = if spread == 0 {
+            0
+        } else {
+            RandomState::new().hash_one(attempt) % (spread + 1)
+        };
+        half + Duration::from_millis(jitter)
+    }
+}
+
+impl From<&RetryConfig> for RetryPolicy {
+    fn from(config: &RetryConfig) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            initial_delay: Duration::from_millis(config.initial_delay_ms),
+            max_delay: Duration::from_millis(config.max_delay_ms),
+            retry_mutations: config.retry_mutations,
+        }
+    }
+}
+
+/// Methods that only read, and so can always be repeated
+///
+/// PUT and DELETE are idempotent on paper, but a replayed contents-API PUT
+/// creates a second commit or fails with a stale SHA.
+fn is_read_only(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// The error a transient failure response stands for, if it is one
+fn transient_error(response: &Response) -> Option<VktError> {
+    let status = response.status();
+    let rate_limit_exhausted =
+        RateLimit::from_headers(response.headers()).is_some_and(|r| r.is_exhausted());
+
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => Some(VktError::RateLimited(status.to_string())),
+        StatusCode::FORBIDDEN if rate_limit_exhausted => {
+            Some(VktError::RateLimited(status.to_string()))
+        }
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT => {
+            Some(VktError::Network(status.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// Fingerprint of the credentials a request carries, empty if it has none
+///
+/// Cached responses are stored per fingerprint, so that a response fetched
+/// with one token is never served to another.
+fn credential(request: &Request) -> String {
+    let mut hasher = Sha256::new();
+    let mut found = false;
+    for name in SENSITIVE_HEADERS {
+        for value in request.headers().get_all(*name) {
+            hasher.update(format!("{}:", name));
+            hasher.update(value.as_bytes());
+            hasher.update(b"\n");
+            found = true;
+        }
+    }
+    for (key, value) in request.url().query_pairs() {
+        if SENSITIVE_PARAMS.contains(&key.as_ref()) {
+            hasher.update(format!("{}={}\n", key, value));
+            found = true;
+        }
+    }
+    if !found {
+        return String::new();
+    }
+    // 64 bits tell tokens apart without making the stored value worth stealing
+    format!("{:x}", hasher.finalize())[..16].to_string()
+}
+
+/// Parse a Retry-After header (delay in seconds or an HTTP date), falling
+/// back to the rate-limit reset time of an exhausted quota
+fn retry_after(response: &Response) -> Option<Duration> {
+    let Some(value) = response.headers().get("retry-after") else {
+        return RateLimit::from_headers(response.headers())
+            .filter(RateLimit::is_exhausted)
+            .and_then(|r| r.reset_in());
+    };
+    let value = value.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.signed_duration_since(chrono::Utc::now());
+    Some(delay.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// A failed attempt, remembering whether the request never reached the server
+struct Failure {
+    error: VktError,
+    unsent: bool,
+}
+
+impl From<reqwest::Error> for Failure {
+    fn from(err: reqwest::Error) -> Self {
+        Self {
+            unsent: err.is_connect(),
+            error: err.into(),
+        }
+    }
+}
+
+impl From<VktError> for Failure {
+    fn from(error: VktError) -> Self {
+        Self {
+            error,
+            unsent: false,
+        }
+    }
+}
+
+/// Replay state: the cassette plus which interactions were already served
+#[derive(Debug)]
+struct Player {
+    cassette: Cassette,
+    used: Vec<bool>,
+}
+
+#[derive(Debug, Clone)]
+enum Mode {
+    Live,
+    Record {
+        path: PathBuf,
+        cassette: Arc<Mutex<Cassette>>,
+    },
+    Replay(Arc<Mutex<Player>>),
+}
+
+/// HTTP transport shared by the HTTP providers
+#[derive(Debug, Clone)]
+pub struct Transport {
+    client: Client,
+    mode: Mode,
+    secrets: Vec<String>,
+    retry: RetryPolicy,
+    rate_limit: RateLimiter,
+    cache: Option<ResponseCache>,
+    offline: bool,
+    stale_since: Arc<Mutex<Option<DateTime<Utc>>>>,
+}
+
+impl Transport {
+    /// Create a live transport around a client
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            mode: Mode::Live,
+            secrets: Vec::new(),
+            retry: RetryPolicy::none(),
+            rate_limit: RateLimiter::new(),
+            cache: None,
+            offline: false,
+            stale_since: Arc::default(),
+        }
+    }
+
+    /// Create a transport configured from `VKT_CASSETTE` / `VKT_CASSETTE_MODE`
+    ///
+    /// `token` is redacted from everything recorded and from URLs matched on replay.
+    pub fn from_env(client: Client, token: &str) -> Result<Self> {
+        let transport = match std::env::var(CASSETTE_ENV) {
+            Ok(path) if !path.is_empty() => {
+                let mode = std::env::var(CASSETTE_MODE_ENV).unwrap_or_default();
+                match mode.to_lowercase().as_str() {
+                    "record" => Self::record(client, path),
+                    "" | "replay" => Self::replay(Cassette::load(Path::new(&path))?),
+                    other => {
+                        return Err(VktError::Config(format!(
+                            "Invalid {}: {} (expected 'record' or 'replay')",
+                            CASSETTE_MODE_ENV, other
+                        )));
+                    }
+                }
+            }
+            _ => Self::new(client),
+        };
+        Ok(transport.with_secret(token))
+    }
+
+    /// Create the transport for a provider: HTTP client and retry policy from
+    /// the configuration, cassette settings from the environment
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let client = client_builder(&config.network)?
+            .build()
+            .map_err(|e| VktError::Network(e.to_string()))?;
+        Ok(Self::from_env(client, &config.remote.token)?
+            .with_retry(RetryPolicy::from(&config.retry))
+            .with_cache(ResponseCache::from_config(&config.cache))
+            .with_offline(config.cache.offline))
+    }
+
+    /// Create a transport that sends live requests and appends them to `path`
+    ///
+    /// The file is rewritten after every interaction, starting from an empty cassette.
+    pub fn record(client: Client, path: impl Into<PathBuf>) -> Self {
+        Self {
+            client,
+            mode: Mode::Record {
+                path: path.into(),
+                cassette: Arc::new(Mutex::new(Cassette::default())),
+            },
+            secrets: Vec::new(),
+            retry: RetryPolicy::none(),
+            rate_limit: RateLimiter::new(),
+            cache: None,
+            offline: false,
+            stale_since: Arc::default(),
+        }
+    }
+
+    /// Create a transport that answers from a cassette and never hits the network
+    pub fn replay(cassette: Cassette) -> Self {
+        let used = vec![false; cassette.interactions.len()];
+        Self {
+            client: Client::new(),
+            mode: Mode::Replay(Arc::new(Mutex::new(Player { cassette, used }))),
+            secrets: Vec::new(),
+            retry: RetryPolicy::none(),
+            rate_limit: RateLimiter::new(),
+            cache: None,
+            offline: false,
+            stale_since: Arc::default(),
+        }
+    }
+
+    /// Register a secret to redact (empty strings are ignored)
+    pub fn with_secret(mut self, secret: &str) -> Self {
+        if !secret.is_empty() {
+            self.secrets.push(secret.to_string());
+        }
+        self
+    }
+
+    /// Set the retry policy
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Set the response cache (only used for live GET requests)
+    pub fn with_cache(mut self, cache: Option<ResponseCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Answer GET requests from the cache only and refuse everything else
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Fetch time of the oldest cached response served in offline mode
+    ///
+    /// # Returns
+    /// None if nothing has been served from the cache offline
+    pub fn stale_since(&self) -> Option<DateTime<Utc>> {
+        *lock(&self.stale_since)
+    }
+
+    /// The request budget shared by all requests of this transport
+    pub fn rate_limit(&self) -> &RateLimiter {
+        &self.rate_limit
+    }
+
+    /// Start building a request
+    pub fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        self.client.request(method, url)
+    }
+
+    /// Interactions recorded so far (record mode only)
+    pub fn recorded(&self) -> Option<Cassette> {
+        match &self.mode {
+            Mode::Record { cassette, .. } => Some(lock(cassette).clone()),
+            _ => None,
+        }
+    }
+
+    /// Send a request
+    ///
+    /// Cached GET responses are revalidated instead of downloaded again, and
+    /// transient failures are retried (see [`RetryPolicy`]).
+    pub async fn send(&self, builder: RequestBuilder) -> Result<Response> {
+        let mut request = builder.build()?;
+        if self.offline {
+            return self.send_offline(&request);
+        }
+
+        let cache = self
+            .cache
+            .as_ref()
+            .filter(|_| matches!(self.mode, Mode::Live) && request.method() == Method::GET);
+        let Some(cache) = cache else {
+            return self.send_with_retry(request).await;
+        };
+
+        let key = self.redact_url(request.url().as_str());
+        let credential = credential(&request);
+        let cached = cache.get(&key, &credential);
+        if let Some(entry) = &cached {
+            let headers = request.headers_mut();
+            if let Some(etag) = entry.etag().and_then(|v| HeaderValue::from_str(v).ok()) {
+                headers.insert(IF_NONE_MATCH, etag);
+            }
+            if let Some(date) = entry
+                .last_modified()
+                .and_then(|v| HeaderValue::from_str(v).ok())
+            {
+                headers.insert(IF_MODIFIED_SINCE, date);
+            }
+        }
+
+        let response = self.send_with_retry(request).await?;
+        self.revalidate(cache, &key, &credential, cached, response)
+            .await
+    }
+
+    /// Answer a request from the cache without touching the network
+    fn send_offline(&self, request: &Request) -> Result<Response> {
+        let url = self.redact_url(request.url().as_str());
+        if request.method() != Method::GET {
+            return Err(VktError::Offline(format!(
+                "{} {} needs network access",
+                request.method(),
+                url
+            )));
+        }
+        let cache = self.cache.as_ref().ok_or_else(|| {
+            VktError::Offline("the response cache is disabled (cache.enabled = false)".to_string())
+        })?;
+        let entry = cache
+            .get(&url, &credential(request))
+            .ok_or_else(|| VktError::Offline(format!("no cached response for {}", url)))?;
+
+        let mut stale_since = lock(&self.stale_since);
+        if stale_since.is_none_or(|oldest| entry.fetched_at < oldest) {
+            *stale_since = Some(entry.fetched_at);
+        }
+        build_response(entry.status, &to_header_map(&entry.headers), entry.body()?)
+    }
+
+    /// Answer a 304 from the cache and store fresh 200 responses
+    async fn revalidate(
+        &self,
+        cache: &ResponseCache,
+        key: &str,
+        credential: &str,
+        cached: Option<CacheEntry>,
+        response: Response,
+    ) -> Result<Response> {
+        match (response.status(), cached) {
+            (StatusCode::NOT_MODIFIED, Some(entry)) => {
+                tracing::debug!("Not modified, serving {} from cache", key);
+                if let Err(e) = cache.touch(&entry) {
+                    tracing::debug!("Failed to update cache entry for {}: {}", key, e);
+                }
+                build_response(entry.status, &to_header_map(&entry.headers), entry.body()?)
+            }
+            (StatusCode::OK, _) => {
+                let status = response.status().as_u16();
+                let headers = response.headers().clone();
+                let body = response.bytes().await?.to_vec();
+                if let Err(e) = cache.insert(key, credential, status, &headers, &body) {
+                    tracing::debug!("Failed to cache response for {}: {}", key, e);
+                }
+                build_response(status, &headers, body)
+            }
+            _ => Ok(response),
+        }
+    }
+
+    /// Send a request, retrying transient failures
+    ///
+    /// Reads are retried on any retryable error. Writes are only retried when
+    /// the connection failed before the request was sent, unless the policy
+    /// opts into retrying them (`retry_mutations`).
+    async fn send_with_retry(&self, request: Request) -> Result<Response> {
+        let may_retry = is_read_only(request.method()) || self.retry.retry_mutations;
+
+        let mut attempt = 0;
+        loop {
+            // Streaming bodies cannot be cloned, so they are sent exactly once
+            let Some(current) = request
+                .try_clone()
+                .filter(|_| attempt < self.retry.max_retries)
+            else {
+                return self.attempt(request).await.map_err(|failure| failure.error);
+            };
+
+            let (error, delay) = match self.attempt(current).await {
+                Ok(response) => {
+                    let Some(error) = transient_error(&response) else {
+                        return Ok(response);
+                    };
+                    if !(error.is_retryable() && may_retry) {
+                        return Ok(response);
+                    }
+                    match retry_after(&response) {
+                        Some(delay) if delay > self.retry.max_delay => return Ok(response),
+                        Some(delay) => (error, delay),
+                        None => (error, self.retry.backoff(attempt)),
+                    }
+                }
+                Err(Failure { error, unsent }) => {
+                    if !(error.is_retryable() && (may_retry || unsent)) {
+                        return Err(error);
+                    }
+                    (error, self.retry.backoff(attempt))
+                }
+            };
+
+            attempt += 1;
+            tracing::warn!(
+                "{} {} failed ({}), retrying in {:.1}s ({}/{})",
+                request.method(),
+                self.redact_url(request.url().as_str()),
+                error,
+                delay.as_secs_f64(),
+                attempt,
+                self.retry.max_retries
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Send a single request once the rate limit allows it, and record the
+    /// quota reported by the response
+    async fn attempt(&self, request: Request) -> std::result::Result<Response, Failure> {
+        let wait = self.rate_limit.acquire(self.retry.max_delay)?;
+        if !wait.is_zero() {
+            tracing::info!(
+                "Rate limit running low, delaying {} {} by {:.1}s",
+                request.method(),
+                self.redact_url(request.url().as_str()),
+                wait.as_secs_f64()
+            );
+            tokio::time::sleep(wait).await;
+        }
+
+        let response = self.execute(request).await?;
+        self.rate_limit.update(response.headers());
+        Ok(response)
+    }
+
+    /// Send a single request through the configured mode
+    async fn execute(&self, request: Request) -> std::result::Result<Response, Failure> {
+        match &self.mode {
+            Mode::Live => Ok(self.client.execute(request).await?),
+            Mode::Record { path, cassette } => {
+                let recorded_request = self.record_request(&request);
+                let response = self.client.execute(request).await?;
+
+                let status = response.status();
+                let headers = response.headers().clone();
+                let body = response.bytes().await?.to_vec();
+
+                let interaction = Interaction {
+                    request: recorded_request,
+                    response: self.record_response(status.as_u16(), &headers, &body),
+                };
+                let snapshot = {
+                    let mut cassette = lock(cassette);
+                    cassette.interactions.push(interaction);
+                    cassette.clone()
+                };
+                snapshot.save(path)?;
+
+                Ok(build_response(status.as_u16(), &headers, body)?)
+            }
+            Mode::Replay(player) => {
+                let method = request.method().as_str().to_string();
+                let url = self.redact_url(request.url().as_str());
+
+                let mut player = lock(player);
+                let Player { cassette, used } = &mut *player;
+                let index = cassette
+                    .interactions
+                    .iter()
+                    .enumerate()
+                    .position(|(i, interaction)| {
+                        !used[i]
+                            && interaction.request.method.eq_ignore_ascii_case(&method)
+                            && interaction.request.url == url
+                    })
+                    .ok_or_else(|| {
+                        VktError::Api(format!(
+                            "No recorded interaction for {} {} in cassette",
+                            method, url
+                        ))
+                    })?;
+                used[index] = true;
+
+                let recorded = &cassette.interactions[index].response;
+                let body = decode_body(&recorded.body, recorded.body_base64)?;
+                let headers = to_header_map(&recorded.headers);
+                Ok(build_response(recorded.status, &headers, body)?)
+            }
+        }
+    }
+
+    /// Replace every registered secret in `text`
+    fn redact(&self, text: &str) -> String {
+        self.secrets.iter().fold(text.to_string(), |acc, secret| {
+            acc.replace(secret.as_str(), REDACTED)
+        })
+    }
+
+    /// Redact secrets and sensitive query parameters from a URL
+    fn redact_url(&self, url: &str) -> String {
+        let Ok(mut parsed) = reqwest::Url::parse(url) else {
+            return self.redact(url);
+        };
+        if parsed.query().is_some() {
+            let pairs: Vec<(String, String)> = parsed
+                .query_pairs()
+                .map(|(key, value)| {
+                    let value = if SENSITIVE_PARAMS.contains(&key.as_ref()) {
+                        REDACTED.to_string()
+                    } else {
+                        value.into_owned()
+                    };
+                    (key.into_owned(), value)
+                })
+                .collect();
+            parsed.query_pairs_mut().clear().extend_pairs(pairs);
+        }
+        self.redact(parsed.as_str())
+    }
+
+    /// Redact a header map into a sorted string map
+    fn redact_headers(&self, headers: &HeaderMap) -> BTreeMap<String, String> {
+        let mut map = BTreeMap::new();
+        for (name, value) in headers {
+            let value = if SENSITIVE_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                self.redact(&String::from_utf8_lossy(value.as_bytes()))
+            };
+            map.entry(name.as_str().to_string())
+                .and_modify(|existing: &mut String| {
+                    existing.push_str(", ");
+                    existing.push_str(&value);
+                })
+                .or_insert(value);
+        }
+        map
+    }
+
+    /// Encode a body as text if possible, otherwise as base64
+    fn encode_body(&self, body: &[u8]) -> (String, bool) {
+        match std::str::from_utf8(body) {
+            Ok(text) => (self.redact(text), false),
+            Err(_) => (base64::engine::general_purpose::STANDARD.encode(body), true),
+        }
+    }
+
+    fn record_request(&self, request: &Request) -> RecordedRequest {
+        let (body, body_base64) = match request.body().and_then(|b| b.as_bytes()) {
+            Some(bytes) => {
+                let (body, base64) = self.encode_body(bytes);
+                (Some(body), base64)
+            }
+            None => (None, false),
+        };
+        RecordedRequest {
+            method: request.method().as_str().to_string(),
+            url: self.redact_url(request.url().as_str()),
+            headers: self.redact_headers(request.headers()),
+            body,
+            body_base64,
+        }
+    }
+
+    fn record_response(&self, status: u16, headers: &HeaderMap, body: &[u8]) -> RecordedResponse {
+        let (body, body_base64) = self.encode_body(body);
+        RecordedResponse {
+            status,
+            headers: self.redact_headers(headers),
+            body,
+            body_base64,
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn decode_body(body: &str, is_base64: bool) -> Result<Vec<u8>> {
+    if is_base64 {
+        base64::engine::general_purpose::STANDARD
+            .decode(body)
+            .map_err(|e| VktError::Api(format!("Invalid base64 body in cassette: {}", e)))
+    } else {
+        Ok(body.as_bytes().to_vec())
+    }
+}
+
+/// Convert stored headers back into a header map, skipping invalid ones
+fn to_header_map(headers: &BTreeMap<String, String>) -> HeaderMap {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            map.append(name, value);
+        }
+    }
+    map
+}
+
+/// Rebuild a `reqwest::Response` from its parts
+fn build_response(status: u16, headers: &HeaderMap, body: Vec<u8>) -> Result<Response> {
+    let mut builder = http::Response::builder().status(status);
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+    let response = builder
+        .body(body)
+        .map_err(|e| VktError::Api(format!("Invalid recorded response: {}", e)))?;
+    Ok(Response::from(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_record_redacts_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("set-cookie", "session=abc")
+                    .set_body_string(r#"{"name":"repo","echo":"secret-token"}"#),
+            )
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let cassette_path = dir.path().join("cassettes/repo.json");
+        let transport =
+            Transport::record(Client::new(), &cassette_path).with_secret("secret-token");
+
+        let url = format!(
+            "{}/repos/owner/repo?access_token=secret-token&ref=main",
+            server.uri()
+        );
+        let response = transport
+            .send(
+                transport
+                    .request(Method::GET, &url)
+                    .header("Authorization", "Bearer secret-token"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert!(response.text().await.unwrap().contains("secret-token"));
+
+        let written = std::fs::read_to_string(&cassette_path).unwrap();
+        assert!(!written.contains("secret-token"));
+        assert!(!written.contains("session=abc"));
+
+        let cassette = Cassette::from_json(&written).unwrap();
+        let interaction = &cassette.interactions[0];
+        assert!(
+            interaction
+                .request
+                .url
+                .contains("access_token=%5BREDACTED%5D")
+        );
+        assert!(interaction.request.url.contains("ref=main"));
+        assert_eq!(interaction.request.headers["authorization"], REDACTED);
+        assert_eq!(interaction.response.status, 200);
+    }
+
+    #[tokio::test]
+    async fn test_replay_serves_recorded_responses_in_order() {
+        let cassette = Cassette::from_json(
+            r#"{"interactions": [
+                {"request": {"method": "GET", "url": "https://forge.example/api?access_token=%5BREDACTED%5D"},
+                 "response": {"status": 200, "headers": {"content-type": "application/json"}, "body": "{\"n\":1}"}},
+                {"request": {"method": "GET", "url": "https://forge.example/api?access_token=%5BREDACTED%5D"},
+                 "response": {"status": 404, "body": "{\"message\":\"gone\"}"}}
+            ]}"#,
+        )
+        .unwrap();
+        let transport = Transport::replay(cassette).with_secret("live-token");
+        let url = "https://forge.example/api?access_token=live-token";
+
+        let first = transport
+            .send(transport.request(Method::GET, url))
+            .await
+            .unwrap();
+        assert_eq!(first.status(), 200);
+        assert_eq!(first.headers()["content-type"], "application/json");
+        assert_eq!(first.text().await.unwrap(), r#"{"n":1}"#);
+
+        let second = transport
+            .send(transport.request(Method::GET, url))
+            .await
+            .unwrap();
+        assert_eq!(second.status(), 404);
+
+        let exhausted = transport.send(transport.request(Method::GET, url)).await;
+        assert!(matches!(exhausted, Err(VktError::Api(_))));
+    }
+
+    #[tokio::test]
+    async fn test_replay_round_trips_binary_bodies() {
+        let transport = Transport::new(Client::new());
+        let (body, is_base64) = transport.encode_body(&[0xff, 0x00, 0xfe]);
+        assert!(is_base64);
+
+        let cassette = Cassette {
+            interactions: vec![Interaction {
+                request: RecordedRequest {
+                    method: "GET".to_string(),
+                    url: "https://forge.example/raw".to_string(),
+                    ..Default::default()
+                },
+                response: RecordedResponse {
+                    status: 200,
+                    body,
+                    body_base64: true,
+                    ..Default::default()
+                },
+            }],
+        };
+        let transport = Transport::replay(cassette);
+        let response = transport
+            .send(transport.request(Method::GET, "https://forge.example/raw"))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.bytes().await.unwrap().as_ref(),
+            &[0xff, 0x00, 0xfe]
+        );
+    }
+
+    fn fast_retry() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+            retry_mutations: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_idempotent_request_on_gateway_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let transport = Transport::new(Client::new()).with_retry(fast_retry());
+        let response = transport
+            .send(transport.request(Method::GET, &server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_post_on_gateway_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let transport = Transport::new(Client::new()).with_retry(fast_retry());
+        let response = transport
+            .send(transport.request(Method::POST, &server.uri()).body("{}"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 502);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_replay_put_on_gateway_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let transport = Transport::new(Client::new()).with_retry(fast_retry());
+        let response = transport
+            .send(transport.request(Method::PUT, &server.uri()).body("{}"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 503);
+    }
+
+    #[tokio::test]
+    async fn test_retries_rate_limited_post_when_opted_in() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(201))
+            .mount(&server)
+            .await;
+
+        // Writes are only retried after a response when the user opted in
+        let transport = Transport::new(Client::new()).with_retry(RetryPolicy {
+            retry_mutations: true,
+            ..fast_retry()
+        });
+        let response = transport
+            .send(transport.request(Method::POST, &server.uri()).body("{}"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 201);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_when_retry_after_exceeds_max_delay() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "3600"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let transport = Transport::new(Client::new()).with_retry(fast_retry());
+        let response = transport
+            .send(transport.request(Method::GET, &server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 429);
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+            retry_mutations: false,
+        };
+        for attempt in 0..10 {
+            let delay = policy.backoff(attempt);
+            assert!(delay <= Duration::from_millis(1000));
+            assert!(delay >= Duration::from_millis(50));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_budget_fails_before_sending() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-ratelimit-limit", "60")
+                    .insert_header("x-ratelimit-remaining", "0")
+                    .insert_header("x-ratelimit-reset", "3600"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let transport = Transport::new(Client::new()).with_retry(fast_retry());
+        let response = transport
+            .send(transport.request(Method::GET, &server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(transport.rate_limit().current().unwrap().limit, Some(60));
+
+        let err = transport
+            .send(transport.request(Method::GET, &server.uri()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VktError::RateLimited(ref m) if m.contains("resets at")));
+    }
+
+    #[tokio::test]
+    async fn test_client_uses_configured_proxy_and_user_agent() {
+        let proxy = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v5/repos"))
+            .and(wiremock::matchers::header("user-agent", "lab-vkt/1.0"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&proxy)
+            .await;
+
+        let network = NetworkConfig {
+            proxy: proxy.uri(),
+            user_agent: "lab-vkt/1.0".to_string(),
+            ..Default::default()
+        };
+        let client = client_builder(&network).unwrap().build().unwrap();
+        let response = client
+            .get("http://forge.invalid/api/v5/repos")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+    }
+
+    #[test]
+    fn test_client_builder_rejects_unreadable_ca_bundle() {
+        let network = NetworkConfig {
+            ca_bundle: "/nonexistent/corp-ca.pem".to_string(),
+            ..Default::default()
+        };
+        let err = client_builder(&network).unwrap_err();
+        assert!(matches!(err, VktError::Config(ref m) if m.contains("CA bundle")));
+    }
+
+    #[tokio::test]
+    async fn test_revalidates_cached_response_with_etag() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/contents/a.sh"))
+            .and(header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/contents/a.sh"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"v1\"")
+                    .set_body_string("echo hi"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let transport =
+            Transport::new(Client::new()).with_cache(Some(ResponseCache::at(dir.path())));
+        let url = format!("{}/contents/a.sh", server.uri());
+        for _ in 0..2 {
+            let response = transport
+                .send(transport.request(Method::GET, &url))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await.unwrap(), "echo hi");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_offline_serves_cache_and_refuses_mutations() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::at(dir.path());
+        let url = "https://forge.example/api/v5/repos/o/r/contents/a.sh?ref=main";
+        cache
+            .insert(url, "", 200, &HeaderMap::new(), b"echo hi")
+            .unwrap();
+        let fetched_at = cache.get(url, "").unwrap().fetched_at;
+
+        let transport = Transport::new(Client::new())
+            .with_cache(Some(cache))
+            .with_offline(true);
+        assert!(transport.stale_since().is_none());
+
+        let response = transport
+            .send(transport.request(Method::GET, url))
+            .await
+            .unwrap();
+        assert_eq!(response.text().await.unwrap(), "echo hi");
+        assert_eq!(transport.stale_since(), Some(fetched_at));
+
+        let err = transport
+            .send(transport.request(Method::GET, "https://forge.example/api/v5/other"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VktError::Offline(ref m) if m.contains("no cached response")));
+
+        let err = transport
+            .send(transport.request(Method::POST, url).body("{}"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VktError::Offline(_)));
+    }
+
+    #[tokio::test]
+    async fn test_cached_responses_are_kept_per_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::at(dir.path());
+        let url = "https://forge.example/api/v4/projects/1/repository/files/a.sh/raw";
+        let transport = Transport::new(Client::new())
+            .with_cache(Some(cache.clone()))
+            .with_offline(true);
+        let request = |token: &str| {
+            transport
+                .request(Method::GET, url)
+                .header("PRIVATE-TOKEN", token)
+        };
+
+        let owner = credential(&request("token-a").build().unwrap());
+        assert_eq!(owner.len(), 16);
+        assert!(!owner.contains("token-a"));
+        cache
+            .insert(url, &owner, 200, &HeaderMap::new(), b"echo hi")
+            .unwrap();
+
+        let response = transport.send(request("token-a")).await.unwrap();
+        assert_eq!(response.text().await.unwrap(), "echo hi");
+
+        // Neither another token nor an anonymous request reads the entry
+        let err = transport.send(request("token-b")).await.unwrap_err();
+        assert!(matches!(err, VktError::Offline(ref m) if m.contains("no cached response")));
+        let err = transport
+            .send(transport.request(Method::GET, url))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VktError::Offline(_)));
+
+        // Gitee passes the token as a query parameter
+        let gitee = |token: &str| {
+            transport
+                .request(Method::GET, url)
+                .query(&[("access_token", token)])
+                .build()
+                .unwrap()
+        };
+        assert_ne!(credential(&gitee("token-a")), credential(&gitee("token-b")));
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! Common types used across all Git/Forge API providers.
 
+use std::collections::{BTreeMap, BTreeSet};
+
 use serde::{Deserialize, Serialize};
 
 /// Repository tree entry
@@ -32,6 +34,36 @@ impl TreeItem {
     }
 }
 
+/// Filter a full recursive tree listing down to the requested path
+/// - For non-recursive: returns only immediate children of `parent_path`
+/// - For recursive: returns every item below `parent_path`
+pub fn filter_tree(
+    items: Vec<TreeItem>,
+    parent_path: Option<&str>,
+    recursive: bool,
+) -> Vec<TreeItem> {
+    let prefix = parent_path.map(|p| p.trim_matches('/')).unwrap_or("");
+
+    items
+        .into_iter()
+        .filter(|item| {
+            let relative = if prefix.is_empty() {
+                item.path.as_str()
+            } else {
+                match item
+                    .path
+                    .strip_prefix(prefix)
+                    .and_then(|rest| rest.strip_prefix('/'))
+                {
+                    Some(rest) => rest,
+                    None => return false,
+                }
+            };
+            recursive || !relative.contains('/')
+        })
+        .collect()
+}
+
 /// Branch information
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Branch {
@@ -177,6 +209,211 @@ pub struct RepositoryInfo {
     pub ssh_url: Option<String>,
 }
 
+/// Optional forge feature that a provider may or may not support
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// Reviewers can be requested on a pull request
+    ReviewerAssignment,
+    /// File contents are fetched raw rather than as size-limited base64 JSON
+    RawDownloads,
+    /// Pull requests can be opened as drafts
+    DraftPullRequests,
+    /// Labels can be set on pull requests
+    Labels,
+    /// Pull requests can be set to merge automatically once checks pass
+    AutoMerge,
+}
+
+impl Capability {
+    /// All known capabilities
+    pub const ALL: [Capability; 5] = [
+        Capability::ReviewerAssignment,
+        Capability::RawDownloads,
+        Capability::DraftPullRequests,
+        Capability::Labels,
+        Capability::AutoMerge,
+    ];
+
+    /// Human-readable description for messages
+    pub fn description(&self) -> &'static str {
+        match self {
+            Capability::ReviewerAssignment => "reviewer assignment",
+            Capability::RawDownloads => "raw file downloads",
+            Capability::DraftPullRequests => "draft pull requests",
+            Capability::Labels => "pull request labels",
+            Capability::AutoMerge => "auto-merge",
+        }
+    }
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// Set of optional features supported by a provider
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Capabilities {
+    supported: BTreeSet<Capability>,
+}
+
+impl Capabilities {
+    /// Create a set from a list of capabilities
+    pub fn new(capabilities: impl IntoIterator<Item = Capability>) -> Self {
+        Self {
+            supported: capabilities.into_iter().collect(),
+        }
+    }
+
+    /// Every known capability
+    pub fn all() -> Self {
+        Self::new(Capability::ALL)
+    }
+
+    /// Check whether a capability is supported
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.supported.contains(&capability)
+    }
+
+    /// Remove a capability (e.g. after a probe found it unavailable)
+    pub fn without(mut self, capability: Capability) -> Self {
+        self.supported.remove(&capability);
+        self
+    }
+
+    /// Iterate over the supported capabilities
+    pub fn iter(&self) -> impl Iterator<Item = Capability> + '_ {
+        self.supported.iter().copied()
+    }
+}
+
+/// Write access a submission needs, in the order it uses it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// Commits can be pushed to the repository
+    Push,
+    /// The submission branch can be created
+    CreateBranch,
+    /// A pull request into the base branch can be opened
+    PullRequest,
+}
+
+impl Permission {
+    /// All permissions a submission needs
+    pub const ALL: [Permission; 3] = [
+        Permission::Push,
+        Permission::CreateBranch,
+        Permission::PullRequest,
+    ];
+
+    /// Human-readable description for messages
+    pub fn description(&self) -> &'static str {
+        match self {
+            Permission::Push => "push access",
+            Permission::CreateBranch => "branch creation",
+            Permission::PullRequest => "pull requests",
+        }
+    }
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// Outcome of a pre-flight permission check
+///
+/// Permissions the forge does not report are neither granted nor denied; the
+/// forge still decides when the write happens.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PermissionReport {
+    granted: BTreeSet<Permission>,
+    denied: BTreeMap<Permission, String>,
+}
+
+impl PermissionReport {
+    /// Report derived from whether the token may push to `repo`
+    ///
+    /// Branches and pull requests from them need push access on every forge,
+    /// so all permissions follow it. None (not reported) leaves all unknown.
+    pub fn from_push_access(push: Option<bool>, repo: &str) -> Self {
+        let mut report = Self::default();
+        for permission in Permission::ALL {
+            match push {
+                Some(true) => report.grant(permission),
+                Some(false) => report.deny(
+                    permission,
+                    format!("the token has read-only access to {}", repo),
+                ),
+                None => {}
+            }
+        }
+        report
+    }
+
+    /// Record a permission the forge confirmed (unless it is already denied)
+    pub fn grant(&mut self, permission: Permission) {
+        if !self.denied.contains_key(&permission) {
+            self.granted.insert(permission);
+        }
+    }
+
+    /// Record a permission the forge will refuse, and why
+    ///
+    /// The first reason given for a permission is kept.
+    pub fn deny(&mut self, permission: Permission, reason: impl Into<String>) {
+        self.granted.remove(&permission);
+        self.denied
+            .entry(permission)
+            .or_insert_with(|| reason.into());
+    }
+
+    /// Check whether the forge confirmed a permission
+    pub fn is_granted(&self, permission: Permission) -> bool {
+        self.granted.contains(&permission)
+    }
+
+    /// Check whether nothing was denied
+    pub fn is_allowed(&self) -> bool {
+        self.denied.is_empty()
+    }
+
+    /// Denied permissions with their reasons
+    pub fn denied(&self) -> impl Iterator<Item = (Permission, &str)> + '_ {
+        self.denied
+            .iter()
+            .map(|(permission, reason)| (*permission, reason.as_str()))
+    }
+}
+
+/// Check whether a branch name matches a protection pattern
+///
+/// `*` matches any run of characters, including `/` (as GitLab wildcards do);
+/// anything else must match literally.
+pub(crate) fn branch_matches(pattern: &str, branch: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = branch.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard: the whole name must match
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
 /// Create branch request
 #[derive(Debug, Clone, Serialize)]
 pub struct CreateBranchRequest {
@@ -236,4 +473,36 @@ pub struct AssignReviewersRequest {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permission_report() {
+        let mut report = PermissionReport::from_push_access(Some(true), "owner/repo");
+        assert!(report.is_allowed());
+        assert!(report.is_granted(Permission::CreateBranch));
+
+        report.deny(Permission::CreateBranch, "protected");
+        report.grant(Permission::CreateBranch);
+        assert!(!report.is_granted(Permission::CreateBranch));
+        assert_eq!(
+            report.denied().collect::<Vec<_>>(),
+            vec![(Permission::CreateBranch, "protected")]
+        );
+
+        let read_only = PermissionReport::from_push_access(Some(false), "owner/repo");
+        assert_eq!(read_only.denied().count(), 3);
+        assert!(PermissionReport::from_push_access(None, "owner/repo").is_allowed());
+    }
+
+    #[test]
+    fn test_branch_matches() {
+        assert!(branch_matches("main", "main"));
+        assert!(!branch_matches("main", "main2"));
+        assert!(branch_matches("feat/*", "feat/vkt-submit-1-fix"));
+        assert!(branch_matches("*", "anything/at/all"));
+        assert!(branch_matches("release-*-stable", "release-2.1-stable"));
+        assert!(!branch_matches("release-*-stable", "release-2.1"));
+        assert!(!branch_matches("feat/*", "fix/x"));
+    }
+}
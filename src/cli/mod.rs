@@ -19,6 +19,43 @@ pub struct Cli {
     #[arg(short, long, global = true, help = "Specify configuration file path")]
     pub config: Option<String>,
 
+    /// Answer from the local cache only (also VKT_OFFLINE=1)
+    #[arg(
+        long,
+        global = true,
+        help = "Work from cached responses without network access"
+    )]
+    pub offline: bool,
+
+    /// Configuration profile (also VKT_PROFILE)
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_name = "NAME",
+        help = "Use a named configuration profile"
+    )]
+    pub profile: Option<String>,
+
+    /// Repository override for this invocation
+    #[arg(
+        short = 'R',
+        long,
+        global = true,
+        value_name = "[HOST/]OWNER/REPO[@REF]",
+        help = "Work on another repository (and branch) for this command"
+    )]
+    pub repo: Option<String>,
+
+    /// Git remote the project is inferred from (default: origin)
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        help = "Git remote to infer the project from [default: origin]"
+    )]
+    pub remote: Option<String>,
+
     /// Subcommand
     #[command(subcommand)]
     pub command: Commands,
@@ -39,6 +76,8 @@ pub enum Commands {
     /// Examples:
     ///   vkt get config.toml                    # Get file to current directory
     ///   vkt get scripts/qemu.sh -o ./local/    # Get to specified directory
+    ///   vkt get templates/ -j 16               # Download a directory with 16 parallel requests
+    ///   vkt -R alice/scripts@dev get kvm.sh    # Get from another repository and branch
     Get(GetArgs),
 
     /// Submit files to remote repository and create PR
@@ -56,8 +95,32 @@ pub enum Commands {
     ///   vkt config                    # Show all configuration
     ///   vkt config user.name          # Get specific value
     ///   vkt config user.name "John"   # Set specific value
+    ///   vkt config profiles.work.repo.project_id virt/kvm   # Set a value of a profile
+    ///   vkt config unset retry.max_retries   # Remove a value from the user file
+    ///   vkt config edit               # Edit the user file in $EDITOR
+    ///   vkt config doctor             # Check the setup from config file to push access
     ///   vkt config --setup            # Run interactive setup
+    ///   vkt config --show-origin      # Show where each value comes from
+    ///   vkt -p work config --setup    # Set up the "work" profile
     Config(ConfigArgs),
+
+    /// Log in to the forge and manage the stored token
+    ///
+    /// Examples:
+    ///   vkt auth login                                # Paste and store a personal access token
+    ///   vkt auth login --device --client-id Iv1.abc   # Approve the login in the browser (GitHub)
+    ///   vkt auth login --git-credential               # Store the token with git's credential helper
+    ///   vkt auth status                               # Show the account, token scopes and expiry
+    ///   vkt auth logout                               # Remove the stored token
+    Auth(AuthArgs),
+
+    /// Manage the HTTP response cache
+    ///
+    /// Examples:
+    ///   vkt cache stats                  # Show cache size and age
+    ///   vkt cache prune --older-than 7   # Drop entries not validated for a week
+    ///   vkt cache clear                  # Remove all cached responses
+    Cache(CacheArgs),
 }
 
 /// list command arguments
@@ -86,8 +149,22 @@ pub struct GetArgs {
     /// Force overwrite existing files
     #[arg(short, long, help = "Force overwrite existing files")]
     pub force: bool,
+
+    /// Maximum number of files downloaded at the same time
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        default_value_t = DEFAULT_JOBS,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        help = "Maximum number of parallel downloads for directories"
+    )]
+    pub jobs: usize,
 }
 
+/// Default number of parallel downloads
+pub const DEFAULT_JOBS: usize = 8;
+
 /// submit command arguments
 #[derive(Args, Debug, Clone)]
 pub struct SubmitArgs {
@@ -122,11 +199,26 @@ pub struct SubmitArgs {
     /// Specify branch name (auto-generated by default)
     #[arg(short, long, value_name = "BRANCH", help = "Specify branch name")]
     pub branch: Option<String>,
+
+    /// Reviewers to request on the created PR
+    #[arg(
+        short,
+        long = "reviewer",
+        value_name = "USER",
+        value_delimiter = ',',
+        help = "Request a reviewer on the PR (repeatable or comma-separated)"
+    )]
+    pub reviewers: Vec<String>,
 }
 
 /// config command arguments
 #[derive(Args, Debug, Clone)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct ConfigArgs {
+    /// Config operation other than get/set/list
+    #[command(subcommand)]
+    pub action: Option<ConfigAction>,
+
     /// Config key in dotted notation (e.g., user.name)
     #[arg(value_name = "KEY", help = "Configuration key (e.g., user.name, remote.token)")]
     pub key: Option<String>,
@@ -142,6 +234,107 @@ pub struct ConfigArgs {
     /// Run interactive setup
     #[arg(long, help = "Run interactive configuration setup")]
     pub setup: bool,
+
+    /// Show the file, environment variable or flag each value comes from
+    #[arg(
+        long,
+        conflicts_with_all = ["value", "setup"],
+        help = "Show where each configuration value comes from"
+    )]
+    pub show_origin: bool,
+}
+
+/// config subcommands
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum ConfigAction {
+    /// Remove a key from the user configuration file
+    Unset {
+        /// Config key in dotted notation
+        #[arg(
+            value_name = "KEY",
+            help = "Configuration key to remove (e.g., network.proxy)"
+        )]
+        key: String,
+    },
+
+    /// Open the user configuration file in $VISUAL or $EDITOR and validate it on save
+    Edit,
+
+    /// Check the configuration, the connection to the forge, the token and
+    /// access to the repository
+    Doctor,
+}
+
+/// auth command arguments
+#[derive(Args, Debug, Clone)]
+pub struct AuthArgs {
+    /// Auth operation
+    #[command(subcommand)]
+    pub action: AuthAction,
+}
+
+/// auth subcommands
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum AuthAction {
+    /// Validate a token for the configured forge and store it
+    Login {
+        /// Run the OAuth device-code flow instead of pasting a token
+        #[arg(
+            long,
+            requires = "client_id",
+            help = "Log in through the browser with the OAuth device flow (GitHub)"
+        )]
+        device: bool,
+
+        /// OAuth app for the device flow
+        #[arg(
+            long,
+            value_name = "ID",
+            requires = "device",
+            help = "Client ID of an OAuth app with device flow enabled"
+        )]
+        client_id: Option<String>,
+
+        /// Store the token with git's credential helper instead of a token file
+        #[arg(long, help = "Store the token with git's credential helper")]
+        git_credential: bool,
+    },
+
+    /// Show who the token belongs to, its scopes and expiry
+    Status,
+
+    /// Remove the stored token
+    Logout,
+}
+
+/// cache command arguments
+#[derive(Args, Debug, Clone)]
+pub struct CacheArgs {
+    /// Cache operation
+    #[command(subcommand)]
+    pub action: CacheAction,
+}
+
+/// cache subcommands
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum CacheAction {
+    /// Show the number, size and age of cached responses
+    Stats,
+
+    /// Remove all cached responses
+    Clear,
+
+    /// Remove responses the server has not confirmed recently
+    Prune {
+        /// Maximum age in days
+        #[arg(
+            long,
+            value_name = "DAYS",
+            default_value_t = 30,
+            help = "Remove entries not validated within this many days"
+        )]
+        older_than: u64,
+    },
 }
 
 /// Parse command line arguments
@@ -165,6 +358,26 @@ mod tests {
         Cli::command().debug_assert();
     }
 
+    #[test]
+    fn test_auth_command() {
+        let cli =
+            parse_args_from(&["vkt", "auth", "login", "--device", "--client-id", "Iv1.x"]).unwrap();
+        match cli.command {
+            Commands::Auth(args) => assert_eq!(
+                args.action,
+                AuthAction::Login {
+                    device: true,
+                    client_id: Some("Iv1.x".to_string()),
+                    git_credential: false,
+                }
+            ),
+            _ => panic!("Expected Auth command"),
+        }
+
+        assert!(parse_args_from(&["vkt", "auth", "login", "--device"]).is_err());
+        assert!(parse_args_from(&["vkt", "auth", "status"]).is_ok());
+    }
+
     #[test]
     fn test_list_command() {
         let cli = parse_args_from(&["vkt", "list"]).unwrap();
@@ -225,6 +438,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_jobs() {
+        let cli = parse_args_from(&["vkt", "get", "templates/"]).unwrap();
+        match cli.command {
+            Commands::Get(args) => assert_eq!(args.jobs, DEFAULT_JOBS),
+            _ => panic!("Expected Get command"),
+        }
+
+        let cli = parse_args_from(&["vkt", "get", "templates/", "-j", "16"]).unwrap();
+        match cli.command {
+            Commands::Get(args) => assert_eq!(args.jobs, 16),
+            _ => panic!("Expected Get command"),
+        }
+
+        assert!(parse_args_from(&["vkt", "get", "templates/", "--jobs", "0"]).is_err());
+    }
+
     #[test]
     fn test_get_force() {
         let cli = parse_args_from(&["vkt", "get", "config.toml", "-f"]).unwrap();
@@ -292,6 +522,38 @@ mod tests {
         assert!(cli.verbose);
     }
 
+    #[test]
+    fn test_offline_flag() {
+        let cli = parse_args_from(&["vkt", "list"]).unwrap();
+        assert!(!cli.offline);
+        let cli = parse_args_from(&["vkt", "get", "a.sh", "--offline"]).unwrap();
+        assert!(cli.offline);
+    }
+
+    #[test]
+    fn test_profile_flag() {
+        let cli = parse_args_from(&["vkt", "list"]).unwrap();
+        assert_eq!(cli.profile, None);
+        let cli = parse_args_from(&["vkt", "-p", "work", "config", "remote.token"]).unwrap();
+        assert_eq!(cli.profile.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn test_repo_flag() {
+        let cli = parse_args_from(&["vkt", "list"]).unwrap();
+        assert_eq!(cli.repo, None);
+        let cli = parse_args_from(&["vkt", "-R", "alice/scripts@dev", "get", "a.sh"]).unwrap();
+        assert_eq!(cli.repo.as_deref(), Some("alice/scripts@dev"));
+    }
+
+    #[test]
+    fn test_remote_flag() {
+        let cli = parse_args_from(&["vkt", "list"]).unwrap();
+        assert_eq!(cli.remote, None);
+        let cli = parse_args_from(&["vkt", "list", "--remote", "upstream"]).unwrap();
+        assert_eq!(cli.remote.as_deref(), Some("upstream"));
+    }
+
     #[test]
     fn test_config_flag() {
         let cli = parse_args_from(&["vkt", "-c", "/path/to/config.toml", "list"]).unwrap();
@@ -367,6 +629,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_config_command_show_origin() {
+        let cli = parse_args_from(&["vkt", "config", "--show-origin", "repo.project_id"]).unwrap();
+        match cli.command {
+            Commands::Config(args) => {
+                assert!(args.show_origin);
+                assert_eq!(args.key, Some("repo.project_id".to_string()));
+            }
+            _ => panic!("Expected Config command"),
+        }
+
+        assert!(parse_args_from(&["vkt", "config", "--show-origin", "user.name", "x"]).is_err());
+    }
+
+    #[test]
+    fn test_config_subcommands() {
+        let cli = parse_args_from(&["vkt", "config", "unset", "network.proxy"]).unwrap();
+        match cli.command {
+            Commands::Config(args) => assert_eq!(
+                args.action,
+                Some(ConfigAction::Unset {
+                    key: "network.proxy".to_string()
+                })
+            ),
+            _ => panic!("Expected Config command"),
+        }
+
+        let cli = parse_args_from(&["vkt", "config", "edit"]).unwrap();
+        match cli.command {
+            Commands::Config(args) => assert_eq!(args.action, Some(ConfigAction::Edit)),
+            _ => panic!("Expected Config command"),
+        }
+
+        let cli = parse_args_from(&["vkt", "config", "doctor"]).unwrap();
+        match cli.command {
+            Commands::Config(args) => assert_eq!(args.action, Some(ConfigAction::Doctor)),
+            _ => panic!("Expected Config command"),
+        }
+
+        // Keys still work as before
+        let cli =
+            parse_args_from(&["vkt", "config", "profiles.work.repo.project_id", "a/b"]).unwrap();
+        match cli.command {
+            Commands::Config(args) => {
+                assert!(args.action.is_none());
+                assert_eq!(args.value, Some("a/b".to_string()));
+            }
+            _ => panic!("Expected Config command"),
+        }
+        assert!(parse_args_from(&["vkt", "config", "unset"]).is_err());
+    }
+
     #[test]
     fn test_config_command_setup_flag() {
         let cli = parse_args_from(&["vkt", "config", "--setup"]).unwrap();
@@ -377,4 +691,31 @@ mod tests {
             _ => panic!("Expected Config command"),
         }
     }
+
+    #[test]
+    fn test_cache_command() {
+        let cli = parse_args_from(&["vkt", "cache", "stats"]).unwrap();
+        match cli.command {
+            Commands::Cache(args) => assert_eq!(args.action, CacheAction::Stats),
+            _ => panic!("Expected Cache command"),
+        }
+
+        let cli = parse_args_from(&["vkt", "cache", "prune"]).unwrap();
+        match cli.command {
+            Commands::Cache(args) => {
+                assert_eq!(args.action, CacheAction::Prune { older_than: 30 });
+            }
+            _ => panic!("Expected Cache command"),
+        }
+
+        let cli = parse_args_from(&["vkt", "cache", "prune", "--older-than", "7"]).unwrap();
+        match cli.command {
+            Commands::Cache(args) => {
+                assert_eq!(args.action, CacheAction::Prune { older_than: 7 });
+            }
+            _ => panic!("Expected Cache command"),
+        }
+
+        assert!(parse_args_from(&["vkt", "cache"]).is_err());
+    }
 }
@@ -0,0 +1,502 @@
+//! auth command implementation
+//!
+//! Log in to the configured forge, show who the token belongs to and remove
+//! stored tokens (login/status/logout)
+
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+
+use crate::api::auth::{DeviceLogin, TokenInfo, token_info};
+use crate::api::auto::{ProviderCache, resolve_provider};
+use crate::cli::{AuthAction, AuthArgs};
+use crate::commands::Command;
+use crate::config::credentials::{
+    git_credential_approve, git_credential_reject, is_stored_token, stored_token_path,
+    write_token_file,
+};
+use crate::config::layers::{save_value, unset_value};
+use crate::config::{Config, LayeredConfig, Origin, ProviderType, RemoteConfig};
+use crate::context::RuntimeContext;
+use crate::error::VktError;
+use anyhow::{Context, Result, bail};
+use colored::Colorize;
+
+/// Token sources, in the order they take precedence
+const TOKEN_SOURCES: [&str; 4] = [
+    "remote.token",
+    "remote.token_command",
+    "remote.token_file",
+    "remote.git_credential",
+];
+
+/// auth command
+pub struct AuthCommand {
+    args: AuthArgs,
+}
+
+impl AuthCommand {
+    /// Create a new auth command
+    pub fn new(args: AuthArgs) -> Self {
+        Self { args }
+    }
+
+    /// Host of the forge, for messages
+    fn host(config: &Config) -> String {
+        reqwest::Url::parse(&config.remote.api_url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| config.remote.api_url.clone())
+    }
+
+    /// The configured provider, detecting it for `provider = "auto"`
+    pub(crate) async fn provider(config: &Config, token: &str) -> Result<ProviderType> {
+        match config.remote.provider_type() {
+            ProviderType::Auto => Ok(resolve_provider(
+                &config.remote.api_url,
+                token,
+                &config.network,
+                ProviderCache::default_location().as_ref(),
+            )
+            .await?),
+            provider => Ok(provider),
+        }
+    }
+
+    /// Read a pasted token from stdin
+    fn read_token(host: &str) -> Result<String> {
+        if std::io::stdin().is_terminal() {
+            print!("Paste an access token for {}: ", host);
+            std::io::stdout().flush()?;
+        }
+        let mut token = String::new();
+        std::io::stdin()
+            .read_line(&mut token)
+            .context("Failed to read the token")?;
+        let token = token.trim().to_string();
+        if token.is_empty() {
+            bail!("No token given");
+        }
+        Ok(token)
+    }
+
+    async fn login(
+        &self,
+        ctx: &RuntimeContext,
+        client_id: Option<&str>,
+        git_credential: bool,
+    ) -> Result<()> {
+        let mut layered = ctx.load_layered()?;
+        let config = &layered.config;
+        let host = Self::host(config);
+        if config.remote.provider_type() == ProviderType::Local {
+            println!("{} is a local repository, no token needed", host);
+            return Ok(());
+        }
+
+        let (token, provider) = match client_id {
+            Some(client_id) => {
+                // GitHub serves /meta without a token, so detection works before login
+                let provider = Self::provider(config, "").await?;
+                let device = DeviceLogin::new(
+                    &provider,
+                    &config.remote.api_url,
+                    client_id,
+                    &config.network,
+                )?;
+                let code = device.start().await?;
+                println!(
+                    "{} First copy your one-time code: {}",
+                    "!".yellow(),
+                    code.user_code.bold()
+                );
+                println!(
+                    "  Then open {} in your browser",
+                    code.verification_uri.cyan()
+                );
+                println!("⏳ Waiting for the login to be approved...");
+                (device.wait(&code).await?, provider)
+            }
+            None => {
+                let token = Self::read_token(&host)?;
+                let provider = Self::provider(config, &token).await?;
+                (token, provider)
+            }
+        };
+
+        let info = token_info(&provider, &config.remote.api_url, &token, &config.network).await?;
+        println!(
+            "{} Logged in to {} as {}",
+            "✓".green(),
+            host,
+            info.login.green()
+        );
+
+        let stored = if git_credential {
+            git_credential_approve(&config.remote.api_url, &info.login, &token)?;
+            layered.config.remote.git_credential = true;
+            "remote.git_credential"
+        } else {
+            let path = stored_token_path(&config.remote.api_url, layered.profile())?;
+            write_token_file(&path, &token)?;
+            layered.config.remote.token_file = path.to_string_lossy().into_owned();
+            "remote.token_file"
+        };
+        self.store_source(ctx, &mut layered, stored)?;
+        match stored {
+            "remote.token_file" => println!(
+                "{} Token stored in {}",
+                "✓".green(),
+                layered.config.remote.token_file.cyan()
+            ),
+            _ => println!("{} Token stored with git's credential helper", "✓".green()),
+        }
+        Ok(())
+    }
+
+    /// Point the user file at the new token source, and drop the sources of
+    /// the user file that would take precedence over it
+    fn store_source(
+        &self,
+        ctx: &RuntimeContext,
+        layered: &mut LayeredConfig,
+        stored: &str,
+    ) -> Result<()> {
+        let path = &ctx.config_files.user;
+        let profile = layered.profile().map(str::to_string);
+        let user_file = Origin::File(path.clone());
+
+        let value = match stored {
+            "remote.git_credential" => toml::Value::Boolean(true),
+            _ => toml::Value::String(layered.config.remote.token_file.clone()),
+        };
+        save_value(path, stored, value, profile.as_deref())?;
+        let shadowing: Vec<&str> = TOKEN_SOURCES
+            .into_iter()
+            .take_while(|key| *key != stored)
+            .filter(|key| is_set(&layered.config.remote, key))
+            .collect();
+        for key in shadowing {
+            let origin = layered.origin(key);
+            if origin != user_file {
+                println!(
+                    "{} {} from {} takes precedence over the stored token",
+                    "⚠".yellow(),
+                    key,
+                    origin
+                );
+                continue;
+            }
+            clear(&mut layered.config.remote, key);
+            unset_source(path, key, profile.as_deref())?;
+        }
+        Ok(())
+    }
+
+    async fn status(&self, ctx: &RuntimeContext) -> Result<()> {
+        let layered = ctx.load_layered()?;
+        let config = &layered.config;
+        let host = Self::host(config);
+        if config.remote.provider_type() == ProviderType::Local {
+            println!("{} is a local repository, no token needed", host);
+            return Ok(());
+        }
+
+        let token = config.access_token()?;
+        let provider = Self::provider(config, &token).await?;
+        println!("{}", host.bold());
+
+        let info = match token_info(&provider, &config.remote.api_url, &token, &config.network)
+            .await
+        {
+            Ok(info) => info,
+            Err(VktError::AuthInvalid(message) | VktError::PermissionDenied(message)) => {
+                println!("  {} Token rejected: {}", "✗".red(), message);
+                bail!(
+                    "The token for {} is invalid or expired. Run `vkt auth login` to replace it",
+                    host
+                );
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        match &info.name {
+            Some(name) => println!(
+                "  {} Logged in as {} ({})",
+                "✓".green(),
+                info.login.green(),
+                name
+            ),
+            None => println!("  {} Logged in as {}", "✓".green(), info.login.green()),
+        }
+        if let Some(key) = TOKEN_SOURCES
+            .into_iter()
+            .find(|key| is_set(&config.remote, key))
+        {
+            println!(
+                "  {} {} ({})",
+                "Token:".cyan(),
+                describe(&config.remote, key),
+                layered.origin(key)
+            );
+        }
+        println!(
+            "  {} {}",
+            "Scopes:".cyan(),
+            describe_scopes(&info, &provider)
+        );
+        println!(
+            "  {} {}",
+            "Expires:".cyan(),
+            describe_expiry(&info, &provider)
+        );
+        Ok(())
+    }
+
+    fn logout(&self, ctx: &RuntimeContext) -> Result<()> {
+        let mut layered = ctx.load_layered()?;
+        let path = &ctx.config_files.user;
+        let profile = layered.profile().map(str::to_string);
+        let user_file = Origin::File(path.clone());
+        let host = Self::host(&layered.config);
+
+        let mut removed = Vec::new();
+        for key in TOKEN_SOURCES {
+            if !is_set(&layered.config.remote, key) {
+                continue;
+            }
+            let origin = layered.origin(key);
+            if origin != user_file {
+                println!("{} {} is set by {}, left alone", "⚠".yellow(), key, origin);
+                continue;
+            }
+
+            let remote = &layered.config.remote;
+            match key {
+                "remote.token_command" => {
+                    // The token lives wherever the command reads it from
+                    println!(
+                        "{} remote.token_command is kept; revoke the token where it is stored",
+                        "⚠".yellow()
+                    );
+                    continue;
+                }
+                "remote.token_file" if is_stored_token(&remote.token_file) => {
+                    match std::fs::remove_file(&remote.token_file) {
+                        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                            return Err(e).with_context(|| {
+                                format!("Failed to remove {}", remote.token_file)
+                            });
+                        }
+                        _ => {}
+                    }
+                    removed.push(format!("token file {}", remote.token_file));
+                }
+                "remote.git_credential" => {
+                    git_credential_reject(&remote.api_url)?;
+                    removed.push("git credential helper entry".to_string());
+                }
+                _ => removed.push(key.to_string()),
+            }
+            clear(&mut layered.config.remote, key);
+            unset_source(path, key, profile.as_deref())?;
+        }
+
+        // The file's top-level [remote] may still set a source the profile overrode
+        let remaining = ctx.load_layered()?;
+        for key in TOKEN_SOURCES {
+            if is_set(&remaining.config.remote, key) && remaining.origin(key) == user_file {
+                println!(
+                    "{} {} from the top-level [remote] of {} still applies",
+                    "⚠".yellow(),
+                    key,
+                    path.display()
+                );
+            }
+        }
+
+        if removed.is_empty() {
+            println!("No stored token for {}", host);
+        } else {
+            println!("{} Logged out of {}", "✓".green(), host);
+            for item in removed {
+                println!("  removed {}", item);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether a token source is configured
+fn is_set(remote: &RemoteConfig, key: &str) -> bool {
+    match key {
+        "remote.token" => !remote.token.is_empty(),
+        "remote.token_command" => !remote.token_command.is_empty(),
+        "remote.token_file" => !remote.token_file.is_empty(),
+        "remote.git_credential" => remote.git_credential,
+        _ => false,
+    }
+}
+
+/// Remove a token source from the user file
+///
+/// A profile that doesn't set the key itself inherits it from the file's
+/// top-level `[remote]`, so it is removed there.
+fn unset_source(path: &Path, key: &str, profile: Option<&str>) -> Result<()> {
+    if !unset_value(path, key, profile)? && profile.is_some() {
+        unset_value(path, key, None)?;
+    }
+    Ok(())
+}
+
+/// Unset a token source
+fn clear(remote: &mut RemoteConfig, key: &str) {
+    match key {
+        "remote.token" => remote.token.clear(),
+        "remote.token_command" => remote.token_command.clear(),
+        "remote.token_file" => remote.token_file.clear(),
+        "remote.git_credential" => remote.git_credential = false,
+        _ => {}
+    }
+}
+
+/// Describe where the token comes from, without showing it
+fn describe(remote: &RemoteConfig, key: &str) -> String {
+    match key {
+        "remote.token_command" => format!("output of `{}`", remote.token_command),
+        "remote.token_file" => format!("file {}", remote.token_file),
+        "remote.git_credential" => "git credential helper".to_string(),
+        _ => key.to_string(),
+    }
+}
+
+fn describe_scopes(info: &TokenInfo, provider: &ProviderType) -> String {
+    match &info.scopes {
+        Some(scopes) if scopes.is_empty() => "none".to_string(),
+        Some(scopes) => scopes.join(", "),
+        None => format!("not reported by {}", provider.as_str()),
+    }
+}
+
+fn describe_expiry(info: &TokenInfo, provider: &ProviderType) -> String {
+    match &info.expires_at {
+        Some(expires_at) => expires_at.clone(),
+        // GitHub sends an expiry header for every expiring token
+        None if *provider == ProviderType::GitHub || info.scopes.is_some() => "never".to_string(),
+        None => format!("not reported by {}", provider.as_str()),
+    }
+}
+
+#[async_trait::async_trait]
+impl Command for AuthCommand {
+    async fn execute(&self, ctx: &RuntimeContext) -> Result<()> {
+        if ctx.offline && self.args.action != AuthAction::Logout {
+            return Err(VktError::Offline("vkt auth needs network access".to_string()).into());
+        }
+        match &self.args.action {
+            AuthAction::Login {
+                client_id,
+                git_credential,
+                ..
+            } => self.login(ctx, client_id.as_deref(), *git_credential).await,
+            AuthAction::Status => self.status(ctx).await,
+            AuthAction::Logout => self.logout(ctx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigFiles;
+
+    #[tokio::test]
+    async fn test_logout_removes_token_from_user_file_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("config.toml");
+        let project = dir.path().join(".vkt.toml");
+        std::fs::write(
+            &user,
+            r#"[user]
+name = "Test User"
+email = "test@example.com"
+
+[remote]
+provider = "GitHub"
+api_url = "https://api.github.com"
+token = "ghp_secret"
+
+[repo]
+project_id = "owner/repo"
+"#,
+        )
+        .unwrap();
+        std::fs::write(&project, "[remote]\ntoken_command = \"pass show forge\"\n").unwrap();
+
+        let ctx = RuntimeContext {
+            config_files: ConfigFiles {
+                system: None,
+                user: user.clone(),
+                project: Some(project.clone()),
+                git_remote: None,
+            },
+            profile: None,
+            repo: None,
+            log_level: tracing::Level::INFO,
+            offline: false,
+            color: false,
+        };
+        let cmd = AuthCommand::new(AuthArgs {
+            action: AuthAction::Logout,
+        });
+        cmd.execute(&ctx).await.unwrap();
+
+        let saved = std::fs::read_to_string(&user).unwrap();
+        assert!(!saved.contains("ghp_secret"));
+        assert!(saved.contains("project_id"));
+        let layered = ctx.load_layered().unwrap();
+        assert_eq!(layered.config.remote.token, "");
+        assert_eq!(layered.config.remote.token_command, "pass show forge");
+    }
+
+    #[tokio::test]
+    async fn test_logout_removes_token_inherited_by_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("config.toml");
+        std::fs::write(
+            &user,
+            r#"[user]
+name = "Test User"
+email = "test@example.com"
+
+[remote]
+provider = "GitHub"
+api_url = "https://api.github.com"
+token = "ghp_secret"
+
+[repo]
+project_id = "owner/repo"
+
+[profiles.fork.repo]
+project_id = "me/repo"
+"#,
+        )
+        .unwrap();
+
+        let ctx = RuntimeContext {
+            config_files: ConfigFiles::user_only(&user),
+            profile: Some("fork".to_string()),
+            repo: None,
+            log_level: tracing::Level::INFO,
+            offline: false,
+            color: false,
+        };
+        let cmd = AuthCommand::new(AuthArgs {
+            action: AuthAction::Logout,
+        });
+        cmd.execute(&ctx).await.unwrap();
+
+        let saved = std::fs::read_to_string(&user).unwrap();
+        assert!(!saved.contains("ghp_secret"));
+        assert!(saved.contains("me/repo"));
+    }
+}
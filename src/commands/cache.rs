@@ -0,0 +1,86 @@
+//! cache command implementation
+//!
+//! Inspect and clean the on-disk HTTP response cache (stats/clear/prune)
+
+use std::time::Duration;
+
+use crate::api::ResponseCache;
+use crate::cli::{CacheAction, CacheArgs};
+use crate::commands::Command;
+use crate::commands::get::GetCommand;
+use crate::config::CacheConfig;
+use crate::context::RuntimeContext;
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+/// cache command
+pub struct CacheCommand {
+    args: CacheArgs,
+}
+
+impl CacheCommand {
+    /// Create a new cache command
+    pub fn new(args: CacheArgs) -> Self {
+        Self { args }
+    }
+
+    /// Run the action against a cache
+    pub fn run(&self, cache: &ResponseCache) -> Result<()> {
+        match &self.args.action {
+            CacheAction::Stats => {
+                let stats = cache.stats()?;
+                println!("{} {}", "Location:".cyan(), cache.dir().display());
+                println!(
+                    "{} {}",
+                    "Entries:".cyan(),
+                    stats.entries.to_string().yellow()
+                );
+                println!(
+                    "{} {}",
+                    "Size:".cyan(),
+                    GetCommand::format_bytes(stats.bytes as usize).yellow()
+                );
+                println!("{} {}", "Hosts:".cyan(), stats.hosts.to_string().yellow());
+                if let (Some(oldest), Some(newest)) = (stats.oldest, stats.newest) {
+                    println!(
+                        "{} {}",
+                        "Oldest:".cyan(),
+                        oldest.format("%Y-%m-%d %H:%M:%S UTC")
+                    );
+                    println!(
+                        "{} {}",
+                        "Newest:".cyan(),
+                        newest.format("%Y-%m-%d %H:%M:%S UTC")
+                    );
+                }
+            }
+            CacheAction::Clear => {
+                let removed = cache.clear()?;
+                println!("✓ Removed {} cached response(s)", removed);
+            }
+            CacheAction::Prune { older_than } => {
+                let max_age = Duration::from_secs(older_than.saturating_mul(24 * 60 * 60));
+                let removed = cache.prune(max_age)?;
+                println!(
+                    "✓ Removed {} cached response(s) not validated in the last {} day(s)",
+                    removed, older_than
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Command for CacheCommand {
+    async fn execute(&self, ctx: &RuntimeContext) -> Result<()> {
+        // The cache can be inspected and cleaned even while it is disabled
+        let config = ctx.load_config().map(|c| c.cache).unwrap_or_default();
+        let cache = ResponseCache::from_config(&CacheConfig {
+            enabled: true,
+            ..config
+        })
+        .context("Cannot determine the cache directory")?;
+        self.run(&cache)
+    }
+}
@@ -1,12 +1,21 @@
 //! config command implementation
 //!
-//! Manage VKT configuration (get/set/list)
+//! Manage VKT configuration (get/set/unset/list/edit, with the origin of each
+//! value) and check the whole setup with `vkt config doctor`
 
-use crate::cli::ConfigArgs;
-use crate::commands::Command;
-use crate::config::Config;
-use anyhow::{Context, Result};
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+
+use crate::api::auto::{ProviderCache, resolve_provider};
+use crate::cli::{ConfigAction, ConfigArgs};
+use crate::commands::{Command, doctor};
+use crate::config::document;
+use crate::config::layers::{save_config, save_value, split_profile_key, unset_value};
+use crate::config::{Config, ConfigFiles, LayeredConfig, Origin, detect_provider};
+use crate::context::RuntimeContext;
+use anyhow::{Context, Result, bail};
 use colored::Colorize;
+use toml::{Table, Value};
 
 pub struct ConfigCommand {
     args: ConfigArgs,
@@ -18,57 +27,282 @@ impl ConfigCommand {
     }
 
     /// Display single config value
-    fn show_value(&self, config: &Config, key: &str) -> Result<()> {
-        let value = config.get_value(key)?;
+    fn show_value(&self, layered: &LayeredConfig, key: &str) -> Result<()> {
+        let value = layered.get_value(key)?;
         println!("{}", value);
         Ok(())
     }
 
+    /// Display config values with the file, variable or flag each came from
+    ///
+    /// # Arguments
+    /// * `key` - Single key to show, or None for all keys
+    fn show_origins(&self, layered: &LayeredConfig, key: Option<&str>) -> Result<()> {
+        let keys = match key {
+            Some(key) => vec![key.to_string()],
+            None => layered.config.keys()?,
+        };
+        let rows = keys
+            .into_iter()
+            .map(|key| {
+                let value = layered.get_value(&key)?;
+                Ok((layered.origin(&key).to_string(), key, value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let width = rows
+            .iter()
+            .map(|(origin, _, _)| origin.len())
+            .max()
+            .unwrap_or(0);
+        for (origin, key, value) in rows {
+            println!("{:<width$}  {} = {}", origin.blue(), key, value.green());
+        }
+        Ok(())
+    }
+
     /// Display all config values
-    fn list_all(&self, config: &Config) {
-        println!("{}", "[user]".cyan());
-        println!("  name = {}", config.user.name.green());
-        println!("  email = {}", config.user.email.green());
-        println!(
-            "  auto_signoff = {}",
-            config.user.auto_signoff.to_string().yellow()
-        );
+    fn list_all(&self, config: &Config) -> Result<()> {
+        self.print_table(&config.to_table()?, "");
+        Ok(())
+    }
 
-        println!("{}", "[remote]".cyan());
-        println!("  provider = {}", config.remote.provider.green());
-        println!("  api_url = {}", config.remote.api_url.green());
-        println!("  token = {}", "********".green());
+    /// Print a table the way it is written in a file: its values first, then
+    /// a `[section]` header for each nested table
+    fn print_table(&self, table: &Table, prefix: &str) {
+        let indent = if prefix.is_empty() { "" } else { "  " };
+        for (key, value) in table.iter().filter(|(_, value)| !value.is_table()) {
+            let shown = document::display(&document::masked(key, value));
+            let shown = match value {
+                Value::String(_) => shown.green(),
+                _ => shown.yellow(),
+            };
+            println!("{}{} = {}", indent, key, shown);
+        }
+        for (key, value) in table {
+            let Value::Table(child) = value else {
+                continue;
+            };
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            if child.values().any(|value| !value.is_table()) {
+                println!("{}", format!("[{}]", path).cyan());
+            }
+            self.print_table(child, &path);
+        }
+    }
 
-        println!("{}", "[repo]".cyan());
-        println!("  project_id = {}", config.repo.project_id.green());
-        println!("  default_branch = {}", config.repo.default_branch.green());
+    /// Remove a key from the user file, unless the configuration would no
+    /// longer load without it
+    fn unset(&self, ctx: &RuntimeContext, layered: &LayeredConfig, key: &str) -> Result<()> {
+        let path = &ctx.config_files.user;
+        let (field, profile, explicit) = match split_profile_key(key)? {
+            Some((profile, field)) => (field, Some(profile), true),
+            None => (key, layered.profile(), false),
+        };
+
+        let previous = std::fs::read_to_string(path).ok();
+        if !unset_value(path, field, profile)? {
+            println!(
+                "{} {} is not set in {}",
+                "INFO:".blue(),
+                key,
+                path.to_string_lossy()
+            );
+            return Ok(());
+        }
+        let reloaded = match ctx.load_layered() {
+            Ok(reloaded) => reloaded,
+            Err(e) => {
+                if let Some(previous) = previous {
+                    std::fs::write(path, previous)?;
+                }
+                return Err(e).with_context(|| format!("{} is required and was not removed", key));
+            }
+        };
+        println!("{} Unset {}", "✓".green(), key.yellow());
 
-        println!("{}", "[template]".cyan());
-        println!("  pr_prefix = {}", config.template.pr_prefix.green());
+        let origin = reloaded.origin(field);
+        if !explicit && origin != Origin::Default {
+            println!("{} {} is still set by {}", "⚠".yellow(), key, origin);
+        }
+        Ok(())
     }
+
+    /// Edit the user file with `editor` and save it once the configuration loads
+    ///
+    /// The editor works on a copy next to the file, so an invalid edit never
+    /// replaces it. With `retry`, the user is offered to correct the copy.
+    fn edit(
+        &self,
+        files: &ConfigFiles,
+        profile: Option<&str>,
+        editor: &str,
+        retry: bool,
+    ) -> Result<()> {
+        let path = &files.user;
+        let original = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Config::example(),
+            Err(e) => return Err(e.into()),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let draft = path.with_extension("edit.toml");
+        std::fs::write(&draft, &original)?;
+
+        let draft_files = ConfigFiles {
+            user: draft.clone(),
+            ..files.clone()
+        };
+        let result = loop {
+            if let Err(e) = run_editor(editor, &draft) {
+                break Err(e);
+            }
+            if std::fs::read_to_string(&draft)? == original {
+                println!("{} No changes", "INFO:".blue());
+                break Ok(());
+            }
+            match LayeredConfig::load(&draft_files, profile) {
+                Ok(_) => {
+                    std::fs::rename(&draft, path)?;
+                    println!(
+                        "{} Configuration saved to {}",
+                        "✓".green(),
+                        path.to_string_lossy().cyan()
+                    );
+                    break Ok(());
+                }
+                Err(e) => {
+                    println!("{} {}", "✗".red(), e);
+                    if !retry || !confirm("Edit again?")? {
+                        break Err(anyhow::anyhow!(
+                            "Configuration not saved; {} is unchanged",
+                            path.display()
+                        ));
+                    }
+                }
+            }
+        };
+        if draft.exists() {
+            std::fs::remove_file(&draft)?;
+        }
+        result
+    }
+}
+
+/// The user's editor: `$VISUAL`, then `$EDITOR`, then the platform default
+fn editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        })
+}
+
+/// Run `editor` on a file; the editor may carry arguments (e.g. "code --wait")
+fn run_editor(editor: &str, path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    let mut command = {
+        let mut command = std::process::Command::new("sh");
+        command
+            .arg("-c")
+            .arg(format!("{} \"$@\"", editor))
+            .arg(editor)
+            .arg(path);
+        command
+    };
+    #[cfg(not(unix))]
+    let mut command = {
+        let mut command = std::process::Command::new("cmd");
+        command.arg("/C").arg(editor).arg(path);
+        command
+    };
+    let status = command
+        .status()
+        .with_context(|| format!("Failed to run editor '{}'", editor))?;
+    if !status.success() {
+        bail!("Editor '{}' exited with {}", editor, status);
+    }
+    Ok(())
+}
+
+/// Ask a yes/no question, defaulting to yes
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} [Y/n] ", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(!matches!(answer.trim().to_lowercase().as_str(), "n" | "no"))
 }
 
 #[async_trait::async_trait]
 impl Command for ConfigCommand {
-    async fn execute(&self) -> Result<()> {
-        let config_path = Config::default_path()?;
+    async fn execute(&self, ctx: &RuntimeContext) -> Result<()> {
+        let config_path = &ctx.config_files.user;
 
         // Handle setup mode
         if self.args.setup {
-            let config = Config::interactive_setup()?;
-            Config::ensure_config_dir()?;
-            config.save_to_file(&config_path)?;
-            println!(
-                "{} Configuration saved to {}",
-                "✓".green(),
-                config_path.to_string_lossy().cyan()
-            );
+            // Probe the entered URL so the provider prompt is pre-filled for self-hosted forges
+            let cache = ProviderCache::default_location();
+            // Reuse proxy/TLS settings of an existing configuration for probing
+            let network = ctx.load_config().map(|c| c.network).unwrap_or_default();
+            let checkout = ctx.config_files.git_remote.as_ref();
+            let config = Config::interactive_setup_with(checkout, |api_url, token| {
+                println!("🔍 Detecting provider for {}...", api_url);
+                let probed = tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(resolve_provider(
+                        api_url,
+                        token,
+                        &network,
+                        cache.as_ref(),
+                    ))
+                });
+                probed.unwrap_or_else(|e| {
+                    println!("⚠️  {}", e);
+                    detect_provider(api_url)
+                })
+            })?;
+            save_config(config_path, &config, ctx.profile.as_deref())?;
+            match &ctx.profile {
+                Some(profile) => println!(
+                    "{} Profile {} saved to {}",
+                    "✓".green(),
+                    profile.yellow(),
+                    config_path.to_string_lossy().cyan()
+                ),
+                None => println!(
+                    "{} Configuration saved to {}",
+                    "✓".green(),
+                    config_path.to_string_lossy().cyan()
+                ),
+            }
             return Ok(());
         }
 
+        if self.args.action == Some(ConfigAction::Edit) {
+            let retry = std::io::stdin().is_terminal();
+            return self.edit(&ctx.config_files, ctx.profile.as_deref(), &editor(), retry);
+        }
+
+        // The doctor reports a missing or broken configuration itself
+        if self.args.action == Some(ConfigAction::Doctor) {
+            return doctor::run(ctx).await;
+        }
+
         // Load or check if config exists
-        let mut config = if config_path.exists() {
-            Config::load()
+        let mut layered = if ctx.config_files.paths().any(|path| path.exists()) {
+            ctx.load_layered()
         } else {
             println!(
                 "{} No configuration found at {}",
@@ -80,9 +314,30 @@ impl Command for ConfigCommand {
             return Ok(());
         }?;
 
+        if let Some(ConfigAction::Unset { key }) = &self.args.action {
+            return self.unset(ctx, &layered, key);
+        }
+
+        if self.args.show_origin {
+            return self.show_origins(&layered, self.args.key.as_deref());
+        }
+
         // Handle list mode (no args or --list flag)
         if self.args.list || (self.args.key.is_none() && self.args.value.is_none()) {
-            self.list_all(&config);
+            if !layered.profiles().is_empty() {
+                println!(
+                    "{} {} (available: {})",
+                    "Profile:".blue(),
+                    layered.profile().unwrap_or("none").green(),
+                    layered.profiles().join(", ")
+                );
+                println!();
+            }
+            self.list_all(&layered.config)?;
+            if !layered.profile_tables().is_empty() {
+                println!();
+                self.print_table(layered.profile_tables(), "profiles");
+            }
             return Ok(());
         }
 
@@ -94,7 +349,7 @@ impl Command for ConfigCommand {
 
         // Handle get mode (key only, no value)
         if self.args.value.is_none() {
-            return self.show_value(&config, key);
+            return self.show_value(&layered, key);
         }
 
         // Handle set mode (key + value)
@@ -104,9 +359,34 @@ impl Command for ConfigCommand {
             .as_ref()
             .context("Value is required")?;
 
-        config.set_value(key, value)?;
-        config.save_to_file(&config_path)?;
-        println!("{} {} = {}", "✓".green(), key.yellow(), value.green());
+        // Only the key is written to the user file (into the named or the
+        // active profile for profile sections); other layers stay untouched
+        let (field, profile, explicit) = match split_profile_key(key)? {
+            Some((profile, field)) => (field, Some(profile.to_string()), true),
+            None => (key.as_str(), layered.profile().map(str::to_string), false),
+        };
+        let config = &mut layered.config;
+        let parsed = config.parse_value(field, value)?;
+        config.set_value(field, value)?;
+        save_value(config_path, field, parsed, profile.as_deref())?;
+        let shown = if document::is_secret(key) {
+            "********"
+        } else {
+            value
+        };
+        println!("{} {} = {}", "✓".green(), key.yellow(), shown.green());
+
+        let origin = layered.origin(field);
+        let overridden = match &origin {
+            _ if explicit => false,
+            // The user file takes precedence over values inferred from git
+            Origin::Default | Origin::Git(_) => false,
+            Origin::File(path) => ctx.config_files.project.as_ref() == Some(path),
+            Origin::Env(_) | Origin::Flag(_) => true,
+        };
+        if overridden {
+            println!("{} {} is still overridden by {}", "⚠".yellow(), key, origin);
+        }
 
         Ok(())
     }
@@ -117,67 +397,230 @@ mod tests {
     use super::*;
 
     fn create_test_config() -> Config {
-        Config {
-            user: crate::config::UserConfig {
-                name: "Test User".to_string(),
-                email: "test@example.com".to_string(),
-                auto_signoff: true,
-            },
-            remote: crate::config::RemoteConfig {
-                provider: "Gitcode".to_string(),
-                api_url: "https://api.example.com".to_string(),
-                token: "test-token".to_string(),
-            },
-            repo: crate::config::RepoConfig {
-                project_id: "owner/repo".to_string(),
-                default_branch: "main".to_string(),
-            },
-            template: crate::config::TemplateConfig {
-                pr_prefix: "[TEST]".to_string(),
-            },
+        let mut config = Config::for_tests("Gitcode", "https://api.example.com");
+        config.user.name = "Test User".to_string();
+        config.template.pr_prefix = "[TEST]".to_string();
+        config
+    }
+
+    fn create_test_layered(dir: &Path) -> LayeredConfig {
+        let user = dir.join("config.toml");
+        create_test_config().save_to_file(&user).unwrap();
+        LayeredConfig::load(&ConfigFiles::user_only(user), None).unwrap()
+    }
+
+    fn test_context(user: &Path) -> RuntimeContext {
+        RuntimeContext {
+            config_files: ConfigFiles::user_only(user),
+            profile: None,
+            repo: None,
+            log_level: tracing::Level::INFO,
+            offline: false,
+            color: false,
         }
     }
 
+    fn action(action: ConfigAction) -> ConfigCommand {
+        ConfigCommand::new(ConfigArgs {
+            action: Some(action),
+            key: None,
+            value: None,
+            list: false,
+            setup: false,
+            show_origin: false,
+        })
+    }
+
     #[test]
     fn test_config_command_list_all() {
         let args = ConfigArgs {
+            action: None,
             key: None,
             value: None,
             list: false,
             setup: false,
+            show_origin: false,
         };
         let cmd = ConfigCommand::new(args);
         let config = create_test_config();
 
         // Should not panic
-        cmd.list_all(&config);
+        cmd.list_all(&config).unwrap();
     }
 
     #[test]
     fn test_config_command_show_value() {
         let args = ConfigArgs {
+            action: None,
             key: Some("user.name".to_string()),
             value: None,
             list: false,
             setup: false,
+            show_origin: false,
         };
         let cmd = ConfigCommand::new(args);
-        let config = create_test_config();
+        let dir = tempfile::tempdir().unwrap();
+        let layered = create_test_layered(dir.path());
 
-        assert!(cmd.show_value(&config, "user.name").is_ok());
+        assert!(cmd.show_value(&layered, "user.name").is_ok());
     }
 
     #[test]
     fn test_config_command_show_value_unknown_key() {
         let args = ConfigArgs {
+            action: None,
             key: Some("unknown.key".to_string()),
             value: None,
             list: false,
             setup: false,
+            show_origin: false,
         };
         let cmd = ConfigCommand::new(args);
-        let config = create_test_config();
+        let dir = tempfile::tempdir().unwrap();
+        let layered = create_test_layered(dir.path());
+
+        assert!(cmd.show_value(&layered, "unknown.key").is_err());
+        assert!(
+            cmd.show_value(&layered, "profiles.none.remote.token")
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_writes_only_the_key_to_the_user_file() {
+        use crate::config::ConfigFiles;
+
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("config.toml");
+        let project = dir.path().join(".vkt.toml");
+        create_test_config().save_to_file(&user).unwrap();
+        let project_content = "[repo]\nproject_id = \"team/tools\"\n";
+        std::fs::write(&project, project_content).unwrap();
+
+        let ctx = RuntimeContext {
+            config_files: ConfigFiles {
+                system: None,
+                user: user.clone(),
+                project: Some(project.clone()),
+                git_remote: None,
+            },
+            profile: None,
+            repo: None,
+            log_level: tracing::Level::INFO,
+            offline: false,
+            color: false,
+        };
+        let cmd = ConfigCommand::new(ConfigArgs {
+            action: None,
+            key: Some("template.pr_prefix".to_string()),
+            value: Some("[NET]".to_string()),
+            list: false,
+            setup: false,
+            show_origin: false,
+        });
+        cmd.execute(&ctx).await.unwrap();
+
+        let saved = Config::parse_from_file(&user).unwrap();
+        assert_eq!(saved.template.pr_prefix, "[NET]");
+        assert_eq!(saved.repo.project_id, "owner/repo");
+        assert_eq!(std::fs::read_to_string(&project).unwrap(), project_content);
+
+        let layered = ctx.load_layered().unwrap();
+        assert_eq!(layered.config.repo.project_id, "team/tools");
+        assert_eq!(layered.origin("repo.project_id"), Origin::File(project));
+        assert_eq!(layered.origin("template.pr_prefix"), Origin::File(user));
+    }
+
+    #[tokio::test]
+    async fn test_set_and_unset_profile_and_nested_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("config.toml");
+        create_test_config().save_to_file(&user).unwrap();
+        let ctx = test_context(&user);
+
+        let set = |key: &str, value: &str| {
+            ConfigCommand::new(ConfigArgs {
+                action: None,
+                key: Some(key.to_string()),
+                value: Some(value.to_string()),
+                list: false,
+                setup: false,
+                show_origin: false,
+            })
+        };
+        set("profiles.work.repo.project_id", "virt/kvm")
+            .execute(&ctx)
+            .await
+            .unwrap();
+        set("retry.max_retries", "7").execute(&ctx).await.unwrap();
+        assert!(
+            set("profiles.work.retry.max_retries", "1")
+                .execute(&ctx)
+                .await
+                .is_err()
+        );
+        assert!(
+            set("retry.max_retries", "many")
+                .execute(&ctx)
+                .await
+                .is_err()
+        );
+
+        let layered = ctx.load_layered().unwrap();
+        assert_eq!(
+            layered.get_value("profiles.work.repo.project_id").unwrap(),
+            "virt/kvm"
+        );
+        assert_eq!(layered.config.retry.max_retries, 7);
+        assert_eq!(layered.get_value("remote.token").unwrap(), "********");
+
+        action(ConfigAction::Unset {
+            key: "retry.max_retries".to_string(),
+        })
+        .execute(&ctx)
+        .await
+        .unwrap();
+        assert_eq!(ctx.load_config().unwrap().retry.max_retries, 3);
+
+        // Required keys stay unless another layer sets them
+        let err = action(ConfigAction::Unset {
+            key: "user.email".to_string(),
+        })
+        .execute(&ctx)
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("was not removed"), "{}", err);
+        assert_eq!(ctx.load_config().unwrap().user.email, "test@example.com");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_edit_saves_only_valid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("config.toml");
+        create_test_config().save_to_file(&user).unwrap();
+        let files = ConfigFiles::user_only(&user);
+        let cmd = action(ConfigAction::Edit);
+        // A portable stand-in for an editor (BSD and GNU sed differ on -i)
+        let sed = |script: &str| {
+            format!(
+                "e() {{ sed '{}' \"$1\" > \"$1.new\" && mv \"$1.new\" \"$1\"; }}; e",
+                script
+            )
+        };
+
+        cmd.edit(&files, None, &sed("s/Test User/Edited/"), false)
+            .unwrap();
+        assert_eq!(Config::parse_from_file(&user).unwrap().user.name, "Edited");
+
+        let before = std::fs::read_to_string(&user).unwrap();
+        let err = cmd
+            .edit(&files, None, &sed("s/test@example.com/nobody/"), false)
+            .unwrap_err();
+        assert!(err.to_string().contains("unchanged"), "{}", err);
+        assert_eq!(std::fs::read_to_string(&user).unwrap(), before);
+        assert!(!user.with_extension("edit.toml").exists());
 
-        assert!(cmd.show_value(&config, "unknown.key").is_err());
+        assert!(cmd.edit(&files, None, "false", false).is_err());
     }
 }
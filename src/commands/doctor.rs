@@ -0,0 +1,463 @@
+//! config doctor implementation
+//!
+//! Check the setup one step at a time, from the configuration files to push
+//! access, and say how to fix the first problem found. Each check relies on
+//! the ones before it, so the checks after a failure are skipped.
+
+use anyhow::{Result, bail};
+use colored::Colorize;
+
+use crate::api::ApiClient;
+use crate::api::Permission;
+use crate::api::auth::token_info;
+use crate::api::transport::probe;
+use crate::commands::auth::AuthCommand;
+use crate::config::{Config, ProviderType};
+use crate::context::RuntimeContext;
+use crate::error::VktError;
+
+/// Checks in the order they run
+const CHECKS: [&str; 6] = [
+    "Configuration",
+    "Forge",
+    "Token",
+    "Project",
+    "Default branch",
+    "Push access",
+];
+
+/// Branch the permission check asks about, shaped like the branches `vkt
+/// submit` creates so that the same branch rules apply
+const PROBE_BRANCH: &str = "feat/vkt-submit-doctor";
+
+/// Result of a single check
+#[derive(Debug, Clone, PartialEq)]
+enum Outcome {
+    /// The check passed, with what was found
+    Pass(String),
+    /// The check did not run or does not apply, and why
+    Skip(String),
+    /// The check failed: what is wrong and how to fix it
+    Fail { problem: String, hint: String },
+}
+
+fn fail(problem: impl Into<String>, hint: impl Into<String>) -> Outcome {
+    Outcome::Fail {
+        problem: problem.into(),
+        hint: hint.into(),
+    }
+}
+
+/// Outcomes of the checks run so far, printed as they come in
+#[derive(Debug, Default)]
+struct Report {
+    outcomes: Vec<Outcome>,
+}
+
+impl Report {
+    /// Record and print the outcome of the next check
+    ///
+    /// # Returns
+    /// Whether the checks that depend on it may run
+    fn record(&mut self, outcome: Outcome) -> bool {
+        let name = CHECKS[self.outcomes.len()];
+        match &outcome {
+            Outcome::Pass(detail) => println!("  {} {}: {}", "✓".green(), name, detail),
+            Outcome::Skip(reason) => println!("  {} {}: {}", "-".dimmed(), name, reason),
+            Outcome::Fail { problem, hint } => {
+                println!("  {} {}: {}", "✗".red(), name, problem);
+                println!("      {} {}", "→".yellow(), hint);
+            }
+        }
+        let passed = !matches!(outcome, Outcome::Fail { .. });
+        self.outcomes.push(outcome);
+        passed
+    }
+
+    /// Mark the checks that did not run as skipped, and fail if any check did
+    fn finish(&mut self) -> Result<()> {
+        while self.outcomes.len() < CHECKS.len() {
+            self.record(Outcome::Skip("skipped after the failure above".to_string()));
+        }
+        println!();
+
+        let failed = self
+            .outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome, Outcome::Fail { .. }))
+            .count();
+        if failed > 0 {
+            bail!("{} of {} checks failed", failed, CHECKS.len());
+        }
+        println!("{} Ready to submit", "✓".green());
+        Ok(())
+    }
+}
+
+/// Run all checks
+///
+/// # Errors
+/// Fails if any check fails, or in offline mode
+pub async fn run(ctx: &RuntimeContext) -> Result<()> {
+    if ctx.offline {
+        return Err(VktError::Offline("vkt config doctor needs network access".to_string()).into());
+    }
+
+    println!("🩺 Checking the vkt setup...");
+    let mut report = Report::default();
+    match ctx.load_layered() {
+        Ok(layered) => {
+            let files: Vec<String> = ctx
+                .config_files
+                .paths()
+                .filter(|path| path.exists())
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect();
+            let detail = match layered.profile() {
+                Some(profile) => format!("{} (profile {})", files.join(", "), profile),
+                None => files.join(", "),
+            };
+            report.record(Outcome::Pass(detail));
+            diagnose(&layered.config, &mut report).await;
+        }
+        Err(e) if !ctx.config_files.paths().any(|path| path.exists()) => {
+            report.record(fail(
+                e.to_string(),
+                "Run `vkt config --setup` to create one",
+            ));
+        }
+        Err(e) => {
+            report.record(fail(
+                e.to_string(),
+                "Fix the value in the file or variable it comes from (`vkt config edit` opens the user file)",
+            ));
+        }
+    }
+    report.finish()
+}
+
+/// Check everything after the configuration itself
+async fn diagnose(config: &Config, report: &mut Report) {
+    if config.remote.provider_type() == ProviderType::Local {
+        report.record(Outcome::Skip("local repository".to_string()));
+        report.record(Outcome::Skip(
+            "local repository, no token needed".to_string(),
+        ));
+    } else {
+        if !report.record(check_forge(config).await) {
+            return;
+        }
+        if !report.record(check_token(config).await) {
+            return;
+        }
+    }
+
+    match ApiClient::new(config) {
+        Ok(api) => check_repository(config, &api, report).await,
+        Err(e) => {
+            report.record(fail(
+                e.to_string(),
+                "Check remote.provider and remote.api_url",
+            ));
+        }
+    }
+}
+
+/// Whether the API URL answers, with TLS working
+async fn check_forge(config: &Config) -> Outcome {
+    let api_url = &config.remote.api_url;
+    match probe(api_url, &config.network).await {
+        Ok(status) if status.is_server_error() => fail(
+            format!("{} answered {}", api_url, status),
+            "The forge has a problem of its own; try again later",
+        ),
+        Ok(status) if api_url.starts_with("https://") => {
+            Outcome::Pass(format!("{} answered {} over TLS", api_url, status))
+        }
+        Ok(status) => Outcome::Pass(format!("{} answered {} (without TLS)", api_url, status)),
+        Err(VktError::Config(message)) => {
+            fail(message, "Fix the [network] settings with `vkt config edit`")
+        }
+        Err(e) => {
+            let message = e.to_string();
+            let hint = network_hint(&message);
+            fail(message, hint)
+        }
+    }
+}
+
+/// How to fix a failed connection, judging by its cause
+fn network_hint(message: &str) -> &'static str {
+    let message = message.to_lowercase();
+    if ["certificate", "tls", "ssl", "handshake"]
+        .iter()
+        .any(|word| message.contains(word))
+    {
+        "TLS failed. If the forge uses a private CA, set network.ca_bundle to its PEM certificates"
+    } else if message.contains("timed out") {
+        "No answer in time. Raise network.connect_timeout_secs, or set network.proxy if the forge is only reachable through a proxy"
+    } else {
+        "Check remote.api_url, and network.proxy and network.no_proxy if you are behind a proxy"
+    }
+}
+
+/// Whether the token authenticates
+async fn check_token(config: &Config) -> Outcome {
+    let token = match config.access_token() {
+        Ok(token) if token.is_empty() => {
+            return fail(
+                "No access token configured",
+                "Run `vkt auth login` to store one",
+            );
+        }
+        Ok(token) => token,
+        Err(e) => {
+            return fail(
+                e.to_string(),
+                "Fix the token source, or run `vkt auth login`",
+            );
+        }
+    };
+    let provider = match AuthCommand::provider(config, &token).await {
+        Ok(provider) => provider,
+        Err(e) => {
+            return fail(
+                e.to_string(),
+                "Set remote.provider to the type of the forge (e.g. GitLab)",
+            );
+        }
+    };
+
+    match token_info(&provider, &config.remote.api_url, &token, &config.network).await {
+        Ok(info) => Outcome::Pass(format!("authenticated as {}", info.login)),
+        Err(VktError::AuthInvalid(message) | VktError::PermissionDenied(message)) => fail(
+            format!("the token was rejected: {}", message),
+            "The token is invalid or expired. Run `vkt auth login` to replace it",
+        ),
+        Err(e) => fail(e.to_string(), "Check remote.provider and remote.api_url"),
+    }
+}
+
+/// Whether the project and its default branch exist, and the token may push
+async fn check_repository(config: &Config, api: &ApiClient, report: &mut Report) {
+    let project = &config.repo.project_id;
+    let info = match api.get_repository_info().await {
+        Ok(info) => info,
+        Err(e) if e.is_not_found() => {
+            report.record(fail(
+                format!("{} not found", project),
+                "Check repo.project_id; private projects are only visible to tokens with read access",
+            ));
+            return;
+        }
+        Err(e) => {
+            report.record(fail(
+                e.to_string(),
+                "Check repo.project_id and the token's scopes (`vkt auth status`)",
+            ));
+            return;
+        }
+    };
+    report.record(Outcome::Pass(format!("{} found", info.full_name)));
+
+    if report.record(check_default_branch(config, api, &info.default_branch).await) {
+        report.record(check_push(config, api).await);
+    }
+}
+
+/// Whether `repo.default_branch` exists and has commits to branch off
+async fn check_default_branch(config: &Config, api: &ApiClient, forge_default: &str) -> Outcome {
+    let branch = config.repo.default_branch.trim();
+    let suggestion = if forge_default.is_empty() {
+        "main"
+    } else {
+        forge_default
+    };
+    if branch.is_empty() {
+        return fail(
+            "repo.default_branch is empty",
+            format!(
+                "Set it with `vkt config repo.default_branch {}`",
+                suggestion
+            ),
+        );
+    }
+
+    // An empty tree means no commits, which `vkt submit` cannot branch off
+    let has_commits = match api.list_repository_tree(None, false, Some(branch)).await {
+        Ok(tree) => !tree.is_empty(),
+        Err(e) if e.is_not_found() => false,
+        Err(e) => return fail(e.to_string(), "Check repo.default_branch"),
+    };
+    if has_commits {
+        Outcome::Pass(format!("{} exists", branch))
+    } else if forge_default != branch && !forge_default.is_empty() {
+        fail(
+            format!("{} does not exist", branch),
+            format!(
+                "The project's default branch is {}. Set it with `vkt config repo.default_branch {}`",
+                forge_default, forge_default
+            ),
+        )
+    } else {
+        fail(
+            format!(
+                "{} has no commits, the repository is not initialized",
+                branch
+            ),
+            "Create a first commit (e.g. a README file) in the forge's web UI",
+        )
+    }
+}
+
+/// Whether the token may push, create the submission branch and open a pull
+/// request
+async fn check_push(config: &Config, api: &ApiClient) -> Outcome {
+    let permissions = match api
+        .check_permissions(PROBE_BRANCH, &config.repo.default_branch)
+        .await
+    {
+        Ok(permissions) => permissions,
+        Err(e) => {
+            return fail(
+                e.to_string(),
+                "Check the token's scopes with `vkt auth status`",
+            );
+        }
+    };
+
+    if !permissions.is_allowed() {
+        let reasons: Vec<String> = permissions
+            .denied()
+            .map(|(permission, reason)| format!("no {} ({})", permission, reason))
+            .collect();
+        return fail(
+            reasons.join(", "),
+            format!(
+                "Ask a maintainer of {} for write access, or use a token with write scope",
+                config.repo.project_id
+            ),
+        );
+    }
+    if permissions.is_granted(Permission::Push) {
+        Outcome::Pass(format!("the token may push to {}", config.repo.project_id))
+    } else {
+        Outcome::Skip("not reported by the forge, which decides on submit".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{MockProvider, PermissionReport};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn create_test_config(provider: &str, api_url: &str) -> Config {
+        let mut config = Config::for_tests(provider, api_url);
+        config.user.name = "Test User".to_string();
+        config
+    }
+
+    /// Report with the checks before the repository ones passed
+    fn report_before_repository() -> Report {
+        Report {
+            outcomes: vec![Outcome::Pass(String::new()); 3],
+        }
+    }
+
+    async fn check(config: &Config, mock: MockProvider) -> Vec<Outcome> {
+        let api = ApiClient::from_provider(Box::new(mock));
+        let mut report = report_before_repository();
+        check_repository(config, &api, &mut report).await;
+        report.outcomes.split_off(3)
+    }
+
+    fn hint(outcome: &Outcome) -> &str {
+        match outcome {
+            Outcome::Fail { hint, .. } => hint,
+            _ => panic!("Expected a failure, got {:?}", outcome),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repository_checks() {
+        let config = create_test_config("GitHub", "https://api.github.com");
+        let ready = MockProvider::new("main")
+            .with_file("README.md", "# repo\n")
+            .with_permissions(PermissionReport::from_push_access(Some(true), "owner/repo"));
+        let outcomes = check(&config, ready).await;
+        assert!(matches!(
+            &outcomes[..],
+            [Outcome::Pass(_), Outcome::Pass(_), Outcome::Pass(_)]
+        ));
+
+        // The case `vkt submit` only finds out about after checking the file
+        let empty = check(&config, MockProvider::new("main")).await;
+        assert_eq!(empty.len(), 2);
+        assert!(hint(&empty[1]).contains("first commit"));
+
+        let mut master = config.clone();
+        master.repo.default_branch = "master".to_string();
+        let renamed = MockProvider::new("main").with_file("README.md", "# repo\n");
+        let outcomes = check(&master, renamed).await;
+        assert!(hint(&outcomes[1]).contains("repo.default_branch main"));
+
+        let read_only = MockProvider::new("main")
+            .with_file("README.md", "# repo\n")
+            .with_permissions(PermissionReport::from_push_access(
+                Some(false),
+                "owner/repo",
+            ));
+        let outcomes = check(&config, read_only).await;
+        assert!(hint(&outcomes[2]).contains("write access"));
+
+        // Forges that do not report permissions leave the decision to the push
+        let unreported = MockProvider::new("main").with_file("README.md", "# repo\n");
+        let outcomes = check(&config, unreported).await;
+        assert!(matches!(outcomes[2], Outcome::Skip(_)));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_token_skips_the_remaining_checks() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/user"))
+            .respond_with(
+                ResponseTemplate::new(401)
+                    .set_body_json(serde_json::json!({"message": "Bad credentials"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let config = create_test_config("GitHub", &server.uri());
+        let mut report = Report {
+            outcomes: vec![Outcome::Pass(String::new())],
+        };
+        diagnose(&config, &mut report).await;
+        assert!(report.finish().is_err());
+
+        let outcomes = &report.outcomes;
+        assert!(matches!(&outcomes[1], Outcome::Pass(detail) if detail.contains("without TLS")));
+        assert!(hint(&outcomes[2]).contains("vkt auth login"));
+        assert!(
+            outcomes[3..]
+                .iter()
+                .all(|outcome| matches!(outcome, Outcome::Skip(_)))
+        );
+    }
+
+    #[test]
+    fn test_network_hint() {
+        assert!(
+            network_hint("error sending request: certificate verify failed").contains("ca_bundle")
+        );
+        assert!(network_hint("operation timed out").contains("connect_timeout_secs"));
+        assert!(network_hint("dns error: failed to lookup address").contains("remote.api_url"));
+    }
+}
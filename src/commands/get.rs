@@ -5,11 +5,15 @@
 use crate::api::ApiClient;
 use crate::api::types::TreeItem;
 use crate::cli::GetArgs;
-use crate::commands::Command;
+use crate::commands::{Command, print_stale_notice};
 use crate::config::Config;
+use crate::context::RuntimeContext;
+use crate::error::VktError;
 use anyhow::{Context, Result};
 use colored::Colorize;
 use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 /// get command
 pub struct GetCommand {
@@ -84,8 +88,11 @@ impl GetCommand {
     }
 
     /// Download file task (for concurrent downloads)
+    ///
+    /// `client` is a clone of the command's client, so every task shares one
+    /// provider, and with it one connection pool and rate-limit budget.
     async fn download_file_task(
-        config: Config,
+        client: ApiClient,
         remote_path: String,
         local_path: std::path::PathBuf,
         branch: String,
@@ -104,19 +111,6 @@ impl GetCommand {
             };
         }
 
-        // Create client inside the task
-        let client = match ApiClient::new(&config) {
-            Ok(c) => c,
-            Err(e) => {
-                return DownloadResult {
-                    path: remote_path,
-                    success: false,
-                    size: 0,
-                    error: Some(format!("Failed to create API client: {}", e)),
-                };
-            }
-        };
-
         match client.get_file_raw(&remote_path, Some(&branch)).await {
             Ok(content) => {
                 let size = content.len();
@@ -164,7 +158,6 @@ impl GetCommand {
         remote_dir: &str,
         output_dir: &Path,
         branch: &str,
-        config: &Config,
     ) -> Result<Vec<DownloadResult>> {
         // Get all items in directory
         let items = client
@@ -181,15 +174,17 @@ impl GetCommand {
 
         let total_files = files.len();
         println!(
-            "{} Found {} files, starting concurrent download...",
+            "{} Found {} files, downloading with up to {} parallel requests...",
             "→".blue(),
-            total_files.to_string().cyan()
+            total_files.to_string().cyan(),
+            self.args.jobs.to_string().cyan()
         );
 
         let branch = branch.to_string();
         let force = self.args.force;
         let base_remote_dir = remote_dir.to_string();
-        let config = config.clone();
+        // Bounds the requests in flight; tasks wait here for a free slot
+        let slots = Arc::new(Semaphore::new(self.args.jobs));
 
         // Create download tasks
         let mut tasks = Vec::new();
@@ -203,12 +198,14 @@ impl GetCommand {
             };
 
             let local_path = output_dir.join(relative_path);
-            let config = config.clone();
+            let client = client.clone();
             let branch = branch.clone();
             let remote_path_clone = remote_path.clone();
+            let slots = Arc::clone(&slots);
 
             let task = tokio::spawn(async move {
-                Self::download_file_task(config, remote_path_clone, local_path, branch, force).await
+                let _slot = slots.acquire_owned().await;
+                Self::download_file_task(client, remote_path_clone, local_path, branch, force).await
             });
 
             tasks.push((remote_path, task));
@@ -269,7 +266,7 @@ impl GetCommand {
     }
 
     /// Format byte size
-    fn format_bytes(bytes: usize) -> String {
+    pub(crate) fn format_bytes(bytes: usize) -> String {
         if bytes < 1024 {
             format!("{}B", bytes)
         } else if bytes < 1024 * 1024 {
@@ -282,10 +279,25 @@ impl GetCommand {
 
 #[async_trait::async_trait]
 impl Command for GetCommand {
-    async fn execute(&self) -> Result<()> {
-        let config = Config::load()?;
+    async fn execute(&self, ctx: &RuntimeContext) -> Result<()> {
+        let config = ctx.load_config()?;
         let client = ApiClient::new(&config)?;
+        self.run(&config, &client).await
+    }
+}
 
+impl GetCommand {
+    /// Run the download against an already constructed client
+    ///
+    /// Ends with a stale notice when the files came from the offline cache.
+    pub async fn run(&self, config: &Config, client: &ApiClient) -> Result<()> {
+        self.download(config, client).await?;
+        print_stale_notice(client);
+        Ok(())
+    }
+
+    /// Download the file or directory
+    async fn download(&self, config: &Config, client: &ApiClient) -> Result<()> {
         let remote_path = &self.args.path;
         let output_dir = self.args.output.as_deref().unwrap_or(".");
         let branch = &config.repo.default_branch;
@@ -300,6 +312,9 @@ impl Command for GetCommand {
                     .await
                 {
                     Ok(items) => !items.is_empty(), // If has content, it's a directory
+                    Err(e @ VktError::Offline(_)) => {
+                        anyhow::bail!("Path '{}' is not available offline: {}", remote_path, e);
+                    }
                     Err(_) => {
                         // Neither file nor directory, probably 404
                         anyhow::bail!(
@@ -324,7 +339,7 @@ impl Command for GetCommand {
             );
 
             let results = self
-                .download_directory(&client, remote_path, &output_path, branch, &config)
+                .download_directory(client, remote_path, &output_path, branch)
                 .await?;
 
             // Statistics
@@ -383,7 +398,7 @@ impl Command for GetCommand {
             println!("{} Fetching: {}", "→".blue(), remote_path.cyan());
 
             let size = self
-                .download_file(&client, remote_path, &output_path, branch)
+                .download_file(client, remote_path, &output_path, branch)
                 .await?;
 
             println!(
@@ -401,6 +416,8 @@ impl Command for GetCommand {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::MockProvider;
+    use crate::cli::DEFAULT_JOBS;
 
     #[test]
     fn test_get_file_name() {
@@ -408,6 +425,7 @@ mod tests {
             path: "scripts/config.sh".to_string(),
             output: None,
             force: false,
+            jobs: DEFAULT_JOBS,
         });
         assert_eq!(cmd.get_file_name("scripts/config.sh"), "config.sh");
 
@@ -415,6 +433,7 @@ mod tests {
             path: "README.md".to_string(),
             output: None,
             force: false,
+            jobs: DEFAULT_JOBS,
         });
         assert_eq!(cmd2.get_file_name("README.md"), "README.md");
     }
@@ -425,6 +444,7 @@ mod tests {
             path: "path/to/file-name_v1.0.txt".to_string(),
             output: None,
             force: false,
+            jobs: DEFAULT_JOBS,
         });
         assert_eq!(
             cmd.get_file_name("path/to/file-name_v1.0.txt"),
@@ -439,4 +459,59 @@ mod tests {
         assert_eq!(GetCommand::format_bytes(1536), "1.5KB");
         assert_eq!(GetCommand::format_bytes(1024 * 1024), "1.0MB");
     }
+
+    fn test_config() -> Config {
+        Config::for_tests("Gitcode", "https://api.gitcode.com/api/v5")
+    }
+
+    #[tokio::test]
+    async fn test_run_downloads_directory_from_mock_provider() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cmd = GetCommand::new(GetArgs {
+            path: "scripts".to_string(),
+            output: Some(dir.path().to_string_lossy().into_owned()),
+            force: false,
+            jobs: 1,
+        });
+
+        let mock = MockProvider::new("main")
+            .with_file("scripts/run.sh", "run\n")
+            .with_file("scripts/qemu/boot.sh", "boot\n");
+        let client = ApiClient::from_provider(Box::new(mock));
+        cmd.run(&test_config(), &client).await.unwrap();
+
+        let saved = dir.path().join("scripts");
+        assert_eq!(std::fs::read(saved.join("run.sh")).unwrap(), b"run\n");
+        assert_eq!(
+            std::fs::read(saved.join("qemu/boot.sh")).unwrap(),
+            b"boot\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_directory_download_shares_one_provider_within_jobs() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cmd = GetCommand::new(GetArgs {
+            path: "scripts".to_string(),
+            output: Some(dir.path().to_string_lossy().into_owned()),
+            force: false,
+            jobs: 2,
+        });
+
+        let mut mock = MockProvider::new("main").with_latency(std::time::Duration::from_millis(10));
+        for i in 0..8 {
+            mock = mock.with_file(&format!("scripts/{}.sh", i), format!("{}\n", i));
+        }
+        let client = ApiClient::from_provider(Box::new(mock.clone()));
+        cmd.run(&test_config(), &client).await.unwrap();
+
+        for i in 0..8 {
+            let saved = dir.path().join("scripts").join(format!("{}.sh", i));
+            assert_eq!(std::fs::read(saved).unwrap(), format!("{}\n", i).as_bytes());
+        }
+        // Every task went through the client's provider (no handles beyond the
+        // test's and the client's), with --jobs downloads at a time
+        assert_eq!(mock.handles(), 2);
+        assert_eq!(mock.peak_downloads(), 2);
+    }
 }
@@ -4,8 +4,9 @@
 
 use crate::api::ApiClient;
 use crate::cli::ListArgs;
-use crate::commands::Command;
+use crate::commands::{Command, print_stale_notice};
 use crate::config::Config;
+use crate::context::RuntimeContext;
 use anyhow::Result;
 use colored::Colorize;
 
@@ -23,10 +24,25 @@ impl ListCommand {
 
 #[async_trait::async_trait]
 impl Command for ListCommand {
-    async fn execute(&self) -> Result<()> {
-        let config = Config::load()?;
+    async fn execute(&self, ctx: &RuntimeContext) -> Result<()> {
+        let config = ctx.load_config()?;
         let client = ApiClient::new(&config)?;
+        self.run(&config, &client).await
+    }
+}
+
+impl ListCommand {
+    /// Run the listing against an already constructed client
+    ///
+    /// Ends with a stale notice when the listing came from the offline cache.
+    pub async fn run(&self, config: &Config, client: &ApiClient) -> Result<()> {
+        self.list(config, client).await?;
+        print_stale_notice(client);
+        Ok(())
+    }
 
+    /// Print the file or directory listing
+    async fn list(&self, config: &Config, client: &ApiClient) -> Result<()> {
         let path = self.args.path.as_deref();
         let recursive = self.args.recursive;
 
@@ -1,17 +1,92 @@
 //! Command implementation module
 //!
-//! Contains implementations for list, get, and submit commands
+//! Contains implementations for list, get, submit, config (with its doctor),
+//! auth and cache commands
 
+pub mod auth;
+pub mod cache;
 pub mod config;
+pub mod doctor;
 pub mod get;
 pub mod list;
 pub mod submit;
 
 use anyhow::Result;
+use chrono::Utc;
+use colored::Colorize;
+
+use crate::api::ApiClient;
+use crate::context::RuntimeContext;
 
 /// Command trait
 #[async_trait::async_trait]
 pub trait Command {
     /// Execute the command
-    async fn execute(&self) -> Result<()>;
+    ///
+    /// # Arguments
+    /// * `ctx` - Global settings (configuration path, offline mode, ...)
+    async fn execute(&self, ctx: &RuntimeContext) -> Result<()>;
+}
+
+/// Notice that the output came from the offline cache, with its age
+///
+/// None when every answer came from the forge.
+pub(crate) fn stale_notice(client: &ApiClient) -> Option<String> {
+    let fetched_at = client.stale_since()?;
+    Some(format!(
+        "{} Offline: cached data fetched {} ({} ago)",
+        "STALE:".yellow(),
+        fetched_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        format_age(Utc::now() - fetched_at)
+    ))
+}
+
+/// Print the stale notice as the last line of a command's output
+///
+/// Offline entries are served regardless of age, so the reader needs it next
+/// to the data rather than in a log.
+pub(crate) fn print_stale_notice(client: &ApiClient) {
+    if let Some(notice) = stale_notice(client) {
+        println!("{}", notice);
+    }
+}
+
+/// Format an age with its largest unit, e.g. "42s", "5m", "3h", "2d"
+fn format_age(age: chrono::Duration) -> String {
+    let seconds = age.num_seconds().max(0);
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m", seconds / 60),
+        3600..86400 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_notice() {
+        use crate::api::MockProvider;
+
+        let fresh = ApiClient::from_provider(Box::new(MockProvider::new("main")));
+        assert!(stale_notice(&fresh).is_none());
+
+        let fetched_at = Utc::now() - chrono::Duration::days(3);
+        let stale = ApiClient::from_provider(Box::new(
+            MockProvider::new("main").with_stale_since(fetched_at),
+        ));
+        let notice = stale_notice(&stale).unwrap();
+        assert!(notice.contains(&fetched_at.format("%Y-%m-%d %H:%M:%S UTC").to_string()));
+        assert!(notice.contains("(3d ago)"));
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(chrono::Duration::seconds(42)), "42s");
+        assert_eq!(format_age(chrono::Duration::minutes(5)), "5m");
+        assert_eq!(format_age(chrono::Duration::hours(26)), "1d");
+        assert_eq!(format_age(chrono::Duration::seconds(-3)), "0s");
+    }
 }
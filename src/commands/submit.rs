@@ -2,10 +2,12 @@
 //!
 //! Submit files to remote repository and create PR
 
-use crate::api::ApiClient;
+use crate::api::{ApiClient, Capability};
 use crate::cli::SubmitArgs;
 use crate::commands::Command;
 use crate::config::Config;
+use crate::context::RuntimeContext;
+use crate::error::VktError;
 use anyhow::{bail, Context as AnyhowContext, Result};
 use base64::Engine;
 use sha2::{Digest, Sha256};
@@ -92,10 +94,23 @@ impl SubmitCommand {
 
 #[async_trait::async_trait]
 impl Command for SubmitCommand {
-    async fn execute(&self) -> Result<()> {
+    async fn execute(&self, ctx: &RuntimeContext) -> Result<()> {
         // 1. Load configuration
-        let config = Config::load()?;
+        let config = ctx.load_config()?;
         let api = ApiClient::new(&config)?;
+        self.run(&config, &api).await
+    }
+}
+
+impl SubmitCommand {
+    /// Run the submission against an already constructed client
+    pub async fn run(&self, config: &Config, api: &ApiClient) -> Result<()> {
+        // Fail before touching anything: every step below needs the forge
+        if config.cache.offline {
+            bail!(VktError::Offline(
+                "vkt submit needs network access (drop --offline / unset VKT_OFFLINE)".to_string()
+            ));
+        }
 
         // 2. Check local file exists
         let local_path = Path::new(&self.args.local_path);
@@ -161,6 +176,38 @@ impl Command for SubmitCommand {
         println!("✅ Repository has been initialized");
         let target_branch = self.generate_branch_name(&self.args.msg);
 
+        // Decide on optional steps up front, before anything is changed remotely
+        let capabilities = api.capabilities().await?;
+        let request_reviewers = !self.args.reviewers.is_empty()
+            && capabilities.supports(Capability::ReviewerAssignment);
+        if !self.args.reviewers.is_empty() && !request_reviewers {
+            println!(
+                "⚠️  This provider does not support {}, --reviewer will be ignored",
+                Capability::ReviewerAssignment
+            );
+        }
+
+        // Fail before the first write rather than leave an orphan branch behind
+        println!("🔐 Checking permissions...");
+        let permissions = api
+            .check_permissions(&target_branch, &config.repo.default_branch)
+            .await?;
+        if !permissions.is_allowed() {
+            println!("❌ Missing permissions:");
+            for (permission, reason) in permissions.denied() {
+                println!("   ✗ {}: {}", permission, reason);
+            }
+            let missing: Vec<String> = permissions
+                .denied()
+                .map(|(permission, _)| permission.to_string())
+                .collect();
+            bail!(VktError::PermissionDenied(format!(
+                "cannot submit to {} without {}",
+                config.repo.project_id,
+                missing.join(", ")
+            )));
+        }
+
         // 6. Dry run mode
         if self.args.dry_run {
             println!("📋 Dry run mode - Planned operations:");
@@ -171,6 +218,9 @@ impl Command for SubmitCommand {
                 "   - Based on branch: {} -> new branch: {}",
                 config.repo.default_branch, target_branch
             );
+            if request_reviewers {
+                println!("   - Reviewers: {}", self.args.reviewers.join(", "));
+            }
             return Ok(());
         }
 
@@ -193,7 +243,7 @@ impl Command for SubmitCommand {
             &self.args.msg,
             &self.args.local_path,
             &content_hash,
-            &config,
+            config,
         );
 
         // 10. Upload file
@@ -211,12 +261,8 @@ impl Command for SubmitCommand {
 
         // 11. Create PR
         let pr_title = format!("{} {}", config.template.pr_prefix, self.args.msg);
-        let pr_body = self.generate_pr_body(
-            &self.args.msg,
-            &self.args.local_path,
-            &content_hash,
-            &config,
-        );
+        let pr_body =
+            self.generate_pr_body(&self.args.msg, &self.args.local_path, &content_hash, config);
 
         println!("📨 Creating Pull Request...");
         let pr = api
@@ -229,6 +275,18 @@ impl Command for SubmitCommand {
             .await?;
         println!("✅ PR #{} created successfully: {}", pr.number, pr.title);
 
+        // 12. Request reviewers (the PR already exists, so a failure is only a warning)
+        if request_reviewers {
+            println!(
+                "👀 Requesting reviewers: {}",
+                self.args.reviewers.join(", ")
+            );
+            match api.assign_reviewers(pr.number, &self.args.reviewers).await {
+                Ok(()) => println!("✅ Reviewers requested"),
+                Err(e) => println!("⚠️  Failed to request reviewers: {}", e),
+            }
+        }
+
         // 13. Output results
         println!();
         println!("🎉 Submission complete!");
@@ -246,25 +304,10 @@ mod tests {
     use super::*;
 
     fn create_test_config() -> Config {
-        Config {
-            user: crate::config::UserConfig {
-                name: "Test User".to_string(),
-                email: "test@example.com".to_string(),
-                auto_signoff: true,
-            },
-            remote: crate::config::RemoteConfig {
-                provider: "Gitcode".to_string(),
-                api_url: "https://api.gitcode.com/api/v5".to_string(),
-                token: "test-token".to_string(),
-            },
-            repo: crate::config::RepoConfig {
-                project_id: "owner/repo".to_string(),
-                default_branch: "main".to_string(),
-            },
-            template: crate::config::TemplateConfig {
-                pr_prefix: "[TEST]".to_string(),
-            },
-        }
+        let mut config = Config::for_tests("Gitcode", "https://api.gitcode.com/api/v5");
+        config.user.name = "Test User".to_string();
+        config.template.pr_prefix = "[TEST]".to_string();
+        config
     }
 
     fn create_test_args() -> SubmitArgs {
@@ -275,6 +318,7 @@ mod tests {
             force: false,
             dry_run: false,
             branch: None,
+            reviewers: Vec::new(),
         }
     }
 
@@ -360,4 +404,148 @@ mod tests {
         // Note: Branch format is "feat/vkt-submit-{timestamp}-{msg_prefix}", slash in prefix is expected
         assert!(branch.starts_with("feat/vkt-submit-")); // Branch prefix remains unchanged
     }
+    #[tokio::test]
+    async fn test_run_against_mock_provider() {
+        use crate::api::{MockCall, MockProvider};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let local_path = dir.path().join("test.sh");
+        std::fs::write(&local_path, "echo hi\n").unwrap();
+
+        let mut args = create_test_args();
+        args.local_path = local_path.to_string_lossy().into_owned();
+        args.branch = Some("feat/test".to_string());
+        let cmd = SubmitCommand::new(args);
+
+        let mock = MockProvider::new("main").with_file("README.md", "# repo\n");
+        let api = ApiClient::from_provider(Box::new(mock.clone()));
+        cmd.run(&create_test_config(), &api).await.unwrap();
+
+        assert_eq!(
+            mock.file("feat/test", "scripts/test.sh"),
+            Some(b"echo hi\n".to_vec())
+        );
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 3);
+        assert!(matches!(&calls[0], MockCall::CreateBranch { name, source }
+            if name == "feat/test" && source == "main"));
+        assert!(
+            mock.commits()[0]
+                .message
+                .contains("Signed-off-by: Test User")
+        );
+
+        let pulls = mock.pull_requests();
+        assert_eq!(pulls[0].title, "[TEST] feat: add test script");
+        assert!(
+            pulls[0]
+                .body
+                .as_deref()
+                .unwrap()
+                .contains("## Trace Information")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_refuses_existing_file_without_force() {
+        use crate::api::MockProvider;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let local_path = dir.path().join("test.sh");
+        std::fs::write(&local_path, "echo hi\n").unwrap();
+
+        let mut args = create_test_args();
+        args.local_path = local_path.to_string_lossy().into_owned();
+        let cmd = SubmitCommand::new(args);
+
+        let mock = MockProvider::new("main").with_file("scripts/test.sh", "old\n");
+        let api = ApiClient::from_provider(Box::new(mock.clone()));
+        let err = cmd.run(&create_test_config(), &api).await.unwrap_err();
+
+        assert!(err.to_string().contains("already exists"));
+        assert!(mock.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_fails_fast_offline() {
+        use crate::api::MockProvider;
+
+        let mut config = create_test_config();
+        config.cache.offline = true;
+        let cmd = SubmitCommand::new(create_test_args());
+
+        let mock = MockProvider::new("main");
+        let api = ApiClient::from_provider(Box::new(mock.clone()));
+        let err = cmd.run(&config, &api).await.unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<VktError>(),
+            Some(VktError::Offline(_))
+        ));
+        assert!(mock.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_requests_reviewers_only_when_supported() {
+        use crate::api::{Capabilities, MockCall, MockProvider};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let local_path = dir.path().join("test.sh");
+        std::fs::write(&local_path, "echo hi\n").unwrap();
+
+        let mut args = create_test_args();
+        args.local_path = local_path.to_string_lossy().into_owned();
+        args.branch = Some("feat/test".to_string());
+        args.reviewers = vec!["alice".to_string(), "bob".to_string()];
+        let cmd = SubmitCommand::new(args);
+
+        let mock = MockProvider::new("main").with_file("README.md", "# repo\n");
+        let api = ApiClient::from_provider(Box::new(mock.clone()));
+        cmd.run(&create_test_config(), &api).await.unwrap();
+        assert!(
+            matches!(mock.calls().last(), Some(MockCall::AssignReviewers { reviewers, .. })
+            if reviewers == &["alice".to_string(), "bob".to_string()])
+        );
+
+        let mock = MockProvider::new("main")
+            .with_file("README.md", "# repo\n")
+            .with_capabilities(Capabilities::default());
+        let api = ApiClient::from_provider(Box::new(mock.clone()));
+        cmd.run(&create_test_config(), &api).await.unwrap();
+        assert!(
+            !mock
+                .calls()
+                .iter()
+                .any(|call| matches!(call, MockCall::AssignReviewers { .. }))
+        );
+        assert_eq!(mock.pull_requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_before_writing_without_permissions() {
+        use crate::api::{MockProvider, Permission, PermissionReport};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let local_path = dir.path().join("test.sh");
+        std::fs::write(&local_path, "echo hi\n").unwrap();
+
+        let mut args = create_test_args();
+        args.local_path = local_path.to_string_lossy().into_owned();
+        let cmd = SubmitCommand::new(args);
+
+        let mut report = PermissionReport::from_push_access(Some(true), "test/repo");
+        report.deny(Permission::CreateBranch, "feat/* is protected");
+        let mock = MockProvider::new("main")
+            .with_file("README.md", "# repo\n")
+            .with_permissions(report);
+        let api = ApiClient::from_provider(Box::new(mock.clone()));
+        let err = cmd.run(&create_test_config(), &api).await.unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<VktError>(),
+            Some(VktError::PermissionDenied(m))
+                if m.contains("branch creation") && !m.contains("scopes")
+        ));
+        assert!(mock.calls().is_empty());
+    }
 }
@@ -0,0 +1,352 @@
+//! Credential Sources
+//!
+//! The access token does not have to be stored in the configuration file. It
+//! is taken from the first configured source:
+//! 1. `remote.token` (or `VKT_REMOTE_TOKEN`)
+//! 2. `remote.token_command`: a shell command printing the token on stdout
+//!    (e.g. `pass show forge/token`)
+//! 3. `remote.token_file`: a file holding the token, which should only be
+//!    readable by its owner
+//! 4. `remote.git_credential`: the password git's credential helpers store for
+//!    the forge host (`git credential fill`)
+//!
+//! Sources are only consulted when a provider is created, so commands that do
+//! not talk to a forge never run a command or prompt a helper.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::RemoteConfig;
+use crate::error::{Result, VktError};
+
+/// Resolve the access token of a remote
+///
+/// # Returns
+/// The token, or an empty string if no source is configured
+///
+/// # Errors
+/// Fails if the configured source cannot produce a non-empty token
+pub fn resolve_token(remote: &RemoteConfig) -> Result<String> {
+    if !remote.token.is_empty() {
+        return Ok(remote.token.clone());
+    }
+    let token = if !remote.token_command.is_empty() {
+        run_token_command(&remote.token_command)?
+    } else if !remote.token_file.is_empty() {
+        read_token_file(&expand_home(&remote.token_file))?
+    } else if remote.git_credential {
+        git_credential_fill(&remote.api_url)?
+    } else {
+        return Ok(String::new());
+    };
+
+    if token.is_empty() {
+        return Err(VktError::Config(
+            "The configured token source returned an empty token".to_string(),
+        ));
+    }
+    Ok(token)
+}
+
+/// Run `command` through the shell and read the token from its first line
+fn run_token_command(command: &str) -> Result<String> {
+    tracing::debug!("Running token_command");
+    let output = shell(command)
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| VktError::Config(format!("Failed to run token_command: {}", e)))?;
+    if !output.status.success() {
+        return Err(VktError::Config(format!(
+            "token_command failed ({})",
+            output.status
+        )));
+    }
+    Ok(first_line(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+/// Read the token from a file, warning if other users can read it
+fn read_token_file(path: &Path) -> Result<String> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        VktError::Config(format!(
+            "Failed to read token_file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)?.permissions().mode() & 0o777;
+        if mode & 0o077 != 0 {
+            tracing::warn!(
+                "token_file {} is accessible by other users (mode {:o}); run chmod 600 on it",
+                path.display(),
+                mode
+            );
+        }
+    }
+
+    Ok(first_line(&content))
+}
+
+/// Ask git's credential helpers for the password of the forge host
+fn git_credential_fill(api_url: &str) -> Result<String> {
+    let (protocol, host) = credential_host(api_url)?;
+    tracing::debug!("Asking git credential helpers for {}", host);
+    let output = git_credential("fill", &format!("protocol={}\nhost={}\n\n", protocol, host))?;
+    if !output.status.success() {
+        return Err(VktError::Config(format!(
+            "git credential fill found no credentials for {}",
+            host
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("password="))
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Store a token with git's credential helpers (`git credential approve`)
+pub fn git_credential_approve(api_url: &str, username: &str, token: &str) -> Result<()> {
+    let (protocol, host) = credential_host(api_url)?;
+    let input = format!(
+        "protocol={}\nhost={}\nusername={}\npassword={}\n\n",
+        protocol, host, username, token
+    );
+    if !git_credential("approve", &input)?.status.success() {
+        return Err(VktError::Config(format!(
+            "git credential approve failed for {}",
+            host
+        )));
+    }
+    Ok(())
+}
+
+/// Erase the forge host's credentials from git's credential helpers
+/// (`git credential reject`)
+pub fn git_credential_reject(api_url: &str) -> Result<()> {
+    let (protocol, host) = credential_host(api_url)?;
+    if !git_credential(
+        "reject",
+        &format!("protocol={}\nhost={}\n\n", protocol, host),
+    )?
+    .status
+    .success()
+    {
+        return Err(VktError::Config(format!(
+            "git credential reject failed for {}",
+            host
+        )));
+    }
+    Ok(())
+}
+
+/// Protocol and host under which git's credential helpers store the forge's
+/// credentials
+///
+/// The `api.` prefix is dropped from the API host, as helpers store
+/// credentials for the web host (e.g. github.com for api.github.com).
+fn credential_host(api_url: &str) -> Result<(String, String)> {
+    let url = reqwest::Url::parse(api_url)
+        .map_err(|e| VktError::Config(format!("Invalid API URL {}: {}", api_url, e)))?;
+    let host = url.host_str().unwrap_or_default();
+    let host = host.strip_prefix("api.").unwrap_or(host);
+    let host = match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    };
+    Ok((url.scheme().to_string(), host))
+}
+
+/// Run `git credential <action>` with `input` on stdin, never prompting
+fn git_credential(action: &str, input: &str) -> Result<std::process::Output> {
+    let mut child = Command::new("git")
+        .args(["credential", action])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| VktError::Config(format!("Failed to run git credential: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    Ok(child.wait_with_output()?)
+}
+
+/// Directory holding the tokens stored by `vkt auth login`
+pub fn token_dir() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| VktError::Config("Cannot determine config directory".to_string()))?;
+    Ok(config_dir.join("vkt").join("tokens"))
+}
+
+/// Token file `vkt auth login` writes for the forge at `api_url`
+///
+/// One file per host, and per profile when one is active, so that profiles
+/// for different accounts on the same forge do not share a token.
+pub fn stored_token_path(api_url: &str, profile: Option<&str>) -> Result<PathBuf> {
+    let (_, host) = credential_host(api_url)?;
+    let name = match profile {
+        Some(profile) => format!("{}@{}", profile, host),
+        None => host,
+    };
+    Ok(token_dir()?.join(name.replace(':', "_")))
+}
+
+/// Whether `path` is a token file written by `vkt auth login`
+pub fn is_stored_token(path: &str) -> bool {
+    token_dir().is_ok_and(|dir| expand_home(path).starts_with(dir))
+}
+
+/// Write a token file that only its owner can read
+pub fn write_token_file(path: &Path, token: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        // The mode only applies to new files
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    writeln!(file, "{}", token)?;
+    Ok(())
+}
+
+/// The first line of `text`, trimmed
+fn first_line(text: &str) -> String {
+    text.lines().next().unwrap_or_default().trim().to_string()
+}
+
+/// Expand a leading `~/` to the home directory
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn github_remote() -> RemoteConfig {
+        RemoteConfig {
+            provider: "GitHub".to_string(),
+            api_url: "https://api.github.com".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_inline_token_wins() {
+        let remote = RemoteConfig {
+            token: "inline".to_string(),
+            token_command: "exit 1".to_string(),
+            ..github_remote()
+        };
+        assert_eq!(resolve_token(&remote).unwrap(), "inline");
+        assert_eq!(resolve_token(&github_remote()).unwrap(), "");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_token_command() {
+        let remote = RemoteConfig {
+            token_command: "printf 'from-command\\nignored\\n'".to_string(),
+            ..github_remote()
+        };
+        assert_eq!(resolve_token(&remote).unwrap(), "from-command");
+
+        let failing = RemoteConfig {
+            token_command: "exit 3".to_string(),
+            ..github_remote()
+        };
+        assert!(resolve_token(&failing).is_err());
+
+        let empty = RemoteConfig {
+            token_command: "true".to_string(),
+            ..github_remote()
+        };
+        let err = resolve_token(&empty).unwrap_err();
+        assert!(err.to_string().contains("empty token"));
+    }
+
+    #[test]
+    fn test_token_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token");
+        std::fs::write(&path, "from-file\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        let remote = RemoteConfig {
+            token_file: path.to_string_lossy().into_owned(),
+            ..github_remote()
+        };
+        assert_eq!(resolve_token(&remote).unwrap(), "from-file");
+
+        let missing = RemoteConfig {
+            token_file: dir.path().join("missing").to_string_lossy().into_owned(),
+            ..github_remote()
+        };
+        assert!(resolve_token(&missing).is_err());
+    }
+
+    #[test]
+    fn test_write_token_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tokens/github.com");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "old\n").unwrap();
+        write_token_file(&path, "ghp_new").unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let remote = RemoteConfig {
+            token_file: path.to_string_lossy().into_owned(),
+            ..github_remote()
+        };
+        assert_eq!(resolve_token(&remote).unwrap(), "ghp_new");
+
+        let stored = stored_token_path("https://api.github.com", Some("work")).unwrap();
+        assert!(stored.ends_with("work@github.com"));
+        assert!(is_stored_token(&stored.to_string_lossy()));
+        assert!(!is_stored_token(&path.to_string_lossy()));
+    }
+}
@@ -0,0 +1,250 @@
+//! Configuration Document
+//!
+//! `vkt config` reads and writes settings as dotted key paths into the TOML
+//! form of the configuration (e.g. `retry.max_retries` or
+//! `profiles.work.remote.api_url`), so every field of [`super::Config`] is
+//! reachable without listing it anywhere. Values typed on the command line
+//! are converted to the type of the value they replace.
+
+use toml::{Table, Value};
+
+use crate::error::{Result, VktError};
+
+/// Keys whose values are never shown
+const SECRET_KEYS: &[&str] = &["token"];
+
+/// Placeholder shown instead of a secret
+const MASK: &str = "********";
+
+/// Split a dotted key path (e.g. "user.name") into its parts
+pub fn parse_key(key: &str) -> Result<Vec<&str>> {
+    let parts: Vec<&str> = key.split('.').collect();
+    if parts.iter().any(|part| part.trim().is_empty()) {
+        return Err(VktError::Validation(format!(
+            "Invalid key format '{}'. Use dotted notation (e.g., user.name)",
+            key
+        )));
+    }
+    Ok(parts)
+}
+
+/// The value at a key path
+pub fn get_path<'a>(table: &'a Table, path: &[&str]) -> Option<&'a Value> {
+    let (first, rest) = path.split_first()?;
+    let value = table.get(*first)?;
+    if rest.is_empty() {
+        Some(value)
+    } else {
+        get_path(value.as_table()?, rest)
+    }
+}
+
+/// Set the value at a key path, creating intermediate tables
+pub fn insert_path(table: &mut Table, path: &[&str], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut current = table;
+    for part in parents {
+        let entry = current
+            .entry(*part)
+            .or_insert_with(|| Value::Table(Table::new()));
+        if !entry.is_table() {
+            *entry = Value::Table(Table::new());
+        }
+        let Value::Table(next) = entry else {
+            return;
+        };
+        current = next;
+    }
+    current.insert(last.to_string(), value);
+}
+
+/// Remove the value at a key path, dropping tables it leaves empty
+///
+/// # Returns
+/// Whether a value was removed
+pub fn remove_path(table: &mut Table, path: &[&str]) -> bool {
+    let Some((first, rest)) = path.split_first() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return table.remove(*first).is_some();
+    }
+    let Some(Value::Table(child)) = table.get_mut(*first) else {
+        return false;
+    };
+    let removed = remove_path(child, rest);
+    if child.is_empty() {
+        table.remove(*first);
+    }
+    removed
+}
+
+/// Dotted paths of all values in a table that are not tables themselves
+pub fn leaf_keys(table: &Table) -> Vec<String> {
+    fn collect(table: &Table, prefix: &str, keys: &mut Vec<String>) {
+        for (key, value) in table {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                Value::Table(child) => collect(child, &path, keys),
+                _ => keys.push(path),
+            }
+        }
+    }
+    let mut keys = Vec::new();
+    collect(table, "", &mut keys);
+    keys
+}
+
+/// Whether a key holds a secret
+pub fn is_secret(key: &str) -> bool {
+    key.rsplit('.')
+        .next()
+        .is_some_and(|field| SECRET_KEYS.contains(&field))
+}
+
+/// A copy of `value` with the secrets below `key` masked
+///
+/// Empty secrets stay empty, so that an unset token can be told apart.
+pub fn masked(key: &str, value: &Value) -> Value {
+    match value {
+        Value::String(secret) if is_secret(key) && !secret.is_empty() => {
+            Value::String(MASK.to_string())
+        }
+        Value::Table(table) => Value::Table(
+            table
+                .iter()
+                .map(|(field, child)| (field.clone(), masked(field, child)))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+/// Format a value for display: strings without quotes, tables as TOML
+pub fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Table(table) => toml::to_string_pretty(table)
+            .unwrap_or_default()
+            .trim_end()
+            .to_string(),
+        value => value.to_string(),
+    }
+}
+
+/// Convert a value typed on the command line to the type of `current`
+///
+/// Arrays are given as TOML (`["a", "b"]`) or comma-separated
+/// (`a,b`); an empty string clears them.
+pub fn parse_as(current: &Value, key: &str, value: &str) -> Result<Value> {
+    let invalid = |expected: &str| {
+        VktError::Validation(format!("Expected {} for {}: {}", expected, key, value))
+    };
+    match current {
+        Value::String(_) => Ok(Value::String(value.to_string())),
+        Value::Boolean(_) => value
+            .parse()
+            .map(Value::Boolean)
+            .map_err(|_| invalid("boolean value")),
+        Value::Integer(_) => value
+            .parse::<u64>()
+            .ok()
+            .and_then(|number| i64::try_from(number).ok())
+            .map(Value::Integer)
+            .ok_or_else(|| invalid("non-negative integer")),
+        Value::Float(_) => value
+            .parse()
+            .map(Value::Float)
+            .map_err(|_| invalid("number")),
+        Value::Array(items) => {
+            if value.trim_start().starts_with('[') {
+                let table: Table =
+                    toml::from_str(&format!("value = {}", value)).map_err(|_| invalid("array"))?;
+                return Ok(table["value"].clone());
+            }
+            let element = items
+                .first()
+                .cloned()
+                .unwrap_or(Value::String(String::new()));
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| parse_as(&element, key, item))
+                .collect::<Result<Vec<_>>>()
+                .map(Value::Array)
+        }
+        Value::Datetime(_) => value
+            .parse()
+            .map(Value::Datetime)
+            .map_err(|_| invalid("date")),
+        Value::Table(_) => Err(VktError::Validation(format!(
+            "{} is a section; set its keys individually (e.g., {}.<key>)",
+            key, key
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths() {
+        let mut table: Table =
+            toml::from_str("[profiles.work.remote]\napi_url = \"https://gitlab.example/api/v4\"\n")
+                .unwrap();
+        let path = parse_key("profiles.work.remote.token").unwrap();
+        insert_path(&mut table, &path, Value::String("secret".to_string()));
+        assert_eq!(
+            leaf_keys(&table),
+            ["profiles.work.remote.api_url", "profiles.work.remote.token"]
+        );
+
+        let remote = get_path(&table, &["profiles", "work", "remote"]).unwrap();
+        let shown = display(&masked("profiles.work.remote", remote));
+        assert!(shown.contains("token = \"********\""), "{}", shown);
+        assert!(!shown.contains("secret"));
+
+        assert!(remove_path(&mut table, &path));
+        assert!(!remove_path(&mut table, &path));
+        assert!(remove_path(
+            &mut table,
+            &["profiles", "work", "remote", "api_url"]
+        ));
+        assert!(table.is_empty());
+
+        assert!(parse_key("user..name").is_err());
+        assert!(parse_key("").is_err());
+    }
+
+    #[test]
+    fn test_parse_as() {
+        let string = Value::String(String::new());
+        assert_eq!(
+            parse_as(&string, "k", "true").unwrap(),
+            Value::String("true".into())
+        );
+        assert_eq!(
+            parse_as(&Value::Integer(3), "k", "5").unwrap(),
+            Value::Integer(5)
+        );
+        assert!(parse_as(&Value::Integer(3), "k", "-1").is_err());
+        assert!(parse_as(&Value::Boolean(true), "k", "yes").is_err());
+
+        let list = Value::Array(vec![]);
+        let expected = Value::Array(vec![Value::String("a".into()), Value::String("b".into())]);
+        assert_eq!(parse_as(&list, "k", "a, b").unwrap(), expected);
+        assert_eq!(parse_as(&list, "k", "[\"a\", \"b\"]").unwrap(), expected);
+        assert_eq!(parse_as(&list, "k", "").unwrap(), Value::Array(vec![]));
+
+        let err = parse_as(&Value::Table(Table::new()), "user", "x").unwrap_err();
+        assert!(err.to_string().contains("is a section"));
+    }
+}
@@ -0,0 +1,844 @@
+//! Layered Configuration
+//!
+//! The effective configuration is assembled from up to six layers, each
+//! overriding the previous ones field by field:
+//! 1. the system file (`/etc/vkt/config.toml`)
+//! 2. the user file (`~/.config/vkt/config.toml`, or `--config` / `VKT_CONFIG`)
+//! 3. the project file: the nearest `.vkt.toml` in the current directory or
+//!    one of its parents
+//! 4. the git checkout: `repo.project_id`, `remote.provider` and
+//!    `remote.api_url` inferred from a remote, where no file sets them
+//! 5. `VKT_*` environment variables
+//! 6. command line flags
+//!
+//! Files may be partial (e.g. a project file with only `[repo]` and
+//! `[template]`); only the merged result has to be complete. Every field
+//! remembers the layer it came from, which `vkt config --show-origin` reports.
+//!
+//! Files can also define named profiles (`[profiles.<name>.remote]`, ...), one
+//! per forge or account. The selected profile (`--profile`, `VKT_PROFILE` or
+//! `default_profile`) is applied on top of the merged files.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use toml::{Table, Value};
+
+use super::checkout::{DEFAULT_REMOTE, GitRemote, api_url_on_host, same_api_host};
+use super::document::{self, get_path, insert_path, remove_path};
+use super::{Config, ENV_PREFIX, ProviderType};
+use crate::error::{Result, VktError};
+
+/// System-wide configuration file
+pub const SYSTEM_CONFIG_PATH: &str = "/etc/vkt/config.toml";
+
+/// Name of the project configuration file
+pub const PROJECT_CONFIG_FILE: &str = ".vkt.toml";
+
+/// Sections a profile sets; `vkt config` writes these keys into the active profile
+pub const PROFILE_SECTIONS: &[&str] = &["user", "remote", "repo", "template"];
+
+/// `[remote]` keys holding a token, which never carry over to another host
+const TOKEN_KEYS: &[&str] = &["token", "token_command", "token_file"];
+
+/// Where a configuration value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// Built-in default
+    Default,
+    /// A configuration file
+    File(PathBuf),
+    /// A remote of the surrounding git checkout
+    Git(String),
+    /// An environment variable
+    Env(String),
+    /// A command line flag
+    Flag(String),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Default => write!(f, "default"),
+            Origin::File(path) => write!(f, "file:{}", path.display()),
+            Origin::Git(remote) => write!(f, "git:{}", remote),
+            Origin::Env(var) => write!(f, "env:{}", var),
+            Origin::Flag(flag) => write!(f, "flag:{}", flag),
+        }
+    }
+}
+
+/// Configuration files to layer
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigFiles {
+    /// System file, if any
+    pub system: Option<PathBuf>,
+    /// User file; `vkt config` writes here
+    pub user: PathBuf,
+    /// Project file, if one was found
+    pub project: Option<PathBuf>,
+    /// Remote of the git checkout around the current directory, if any
+    pub git_remote: Option<GitRemote>,
+}
+
+impl ConfigFiles {
+    /// Only the user file
+    pub fn user_only(user: impl Into<PathBuf>) -> Self {
+        Self {
+            system: None,
+            user: user.into(),
+            project: None,
+            git_remote: None,
+        }
+    }
+
+    /// The standard layers: system file, `user`, the nearest project file
+    /// above `cwd`, and the `origin` remote of the checkout containing `cwd`
+    pub fn discover(user: PathBuf, cwd: &Path) -> Self {
+        let project = find_project_config(cwd).filter(|project| *project != user);
+        Self {
+            system: Some(PathBuf::from(SYSTEM_CONFIG_PATH)),
+            user,
+            project,
+            git_remote: GitRemote::discover(cwd, DEFAULT_REMOTE),
+        }
+    }
+
+    /// The files in precedence order, lowest first
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.system
+            .iter()
+            .chain(std::iter::once(&self.user))
+            .chain(self.project.iter())
+            .map(PathBuf::as_path)
+    }
+}
+
+/// Find the nearest `.vkt.toml` in `start` or one of its parents
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Name of the environment variable overriding a key (e.g. `VKT_USER_NAME`)
+pub fn env_var_name(key: &str) -> String {
+    format!("{}_{}", ENV_PREFIX, key.replace('.', "_").to_uppercase())
+}
+
+/// A configuration together with the origin of each value
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    /// The effective configuration
+    pub config: Config,
+    origins: BTreeMap<String, Origin>,
+    profile: Option<String>,
+    profiles: Vec<String>,
+    profile_tables: Table,
+}
+
+impl LayeredConfig {
+    /// Load and merge the configuration files, then apply the environment
+    ///
+    /// # Arguments
+    /// * `files` - Files to layer; missing files are skipped, but at least one must exist
+    /// * `profile` - Profile to apply, or None for `default_profile` (if set)
+    ///
+    /// # Errors
+    /// Fails if the selected profile is not defined in any file
+    pub fn load(files: &ConfigFiles, profile: Option<&str>) -> Result<Self> {
+        let mut merged = Table::new();
+        let mut origins = BTreeMap::new();
+        let mut found = false;
+
+        for path in files.paths() {
+            let Some(table) = read_table(path)? else {
+                continue;
+            };
+            merge(
+                &mut merged,
+                table,
+                "",
+                &Origin::File(path.to_path_buf()),
+                &mut origins,
+            );
+            found = true;
+        }
+        if !found {
+            return Err(VktError::Config(format!(
+                "No configuration found at {}",
+                files.user.display()
+            )));
+        }
+
+        let profile_tables = merged
+            .get("profiles")
+            .and_then(Value::as_table)
+            .cloned()
+            .unwrap_or_default();
+        let profiles: Vec<String> = profile_tables.keys().cloned().collect();
+        let profile = profile
+            .filter(|name| !name.is_empty())
+            .or_else(|| merged.get("default_profile").and_then(Value::as_str))
+            .filter(|name| !name.is_empty())
+            .map(str::to_string);
+        if let Some(name) = &profile {
+            apply_profile(&mut merged, name, &profiles, &mut origins)?;
+        }
+
+        if let Some(remote) = &files.git_remote {
+            apply_git_remote(&mut merged, remote, &mut origins);
+        }
+
+        let mut config: Config = Value::Table(merged)
+            .try_into()
+            .map_err(|e| VktError::Config(format!("Incomplete configuration: {}", e)))?;
+        config.apply_env_overrides();
+        for key in config.keys()? {
+            let var = env_var_name(&key);
+            if std::env::var_os(&var).is_some() {
+                origins.insert(key, Origin::Env(var));
+            }
+        }
+        config.validate()?;
+
+        Ok(Self {
+            config,
+            origins,
+            profile,
+            profiles,
+            profile_tables,
+        })
+    }
+
+    /// The applied profile, if any
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Names of all profiles defined in the files
+    pub fn profiles(&self) -> &[String] {
+        &self.profiles
+    }
+
+    /// The first profile whose `remote.api_url` is served by `host`
+    pub fn profile_for_host(&self, host: &str) -> Option<&str> {
+        self.profiles.iter().map(String::as_str).find(|name| {
+            get_path(&self.profile_tables, &[name, "remote", "api_url"])
+                .and_then(Value::as_str)
+                .is_some_and(|api_url| api_url_on_host(api_url, host))
+        })
+    }
+
+    /// The `[profiles]` tables of the files
+    pub fn profile_tables(&self) -> &Table {
+        &self.profile_tables
+    }
+
+    /// Get a value by key path, including `profiles.<name>.<key>` keys
+    ///
+    /// Secrets are masked as in [`Config::get_value`].
+    pub fn get_value(&self, key: &str) -> Result<String> {
+        match document::parse_key(key)?.split_first() {
+            Some((&"profiles", rest)) => {
+                let value = get_path(&self.profile_tables, rest)
+                    .ok_or_else(|| VktError::Validation(format!("Unknown config key: {}", key)))?;
+                Ok(document::display(&document::masked(key, value)))
+            }
+            _ => self.config.get_value(key),
+        }
+    }
+
+    /// Where the value of a key came from
+    pub fn origin(&self, key: &str) -> Origin {
+        self.origins.get(key).cloned().unwrap_or(Origin::Default)
+    }
+
+    /// Override a key from a command line flag
+    pub fn set_flag(&mut self, key: &str, value: &str, flag: &str) -> Result<()> {
+        self.config.set_value(key, value)?;
+        self.origins
+            .insert(key.to_string(), Origin::Flag(flag.to_string()));
+        Ok(())
+    }
+}
+
+/// Parse a configuration file into a table, or None if it does not exist
+fn read_table(path: &Path) -> Result<Option<Table>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(VktError::Config(format!(
+                "Failed to read configuration file {}: {}",
+                path.display(),
+                e
+            )));
+        }
+    };
+    toml::from_str(&content).map(Some).map_err(|e| {
+        VktError::Config(format!(
+            "Failed to parse configuration file {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Merge `layer` into `base`, recording the origin of every value it sets
+fn merge(
+    base: &mut Table,
+    layer: Table,
+    prefix: &str,
+    origin: &Origin,
+    origins: &mut BTreeMap<String, Origin>,
+) {
+    for (key, value) in layer {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Value::Table(table) => {
+                let entry = base
+                    .entry(key)
+                    .or_insert_with(|| Value::Table(Table::new()));
+                if !entry.is_table() {
+                    *entry = Value::Table(Table::new());
+                }
+                if let Value::Table(base_table) = entry {
+                    merge(base_table, table, &path, origin, origins);
+                }
+            }
+            value => {
+                origins.insert(path, origin.clone());
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Apply `[profiles.<name>]` on top of the merged files
+///
+/// Profile values keep the origin of the file that defined them. A profile
+/// whose `remote.api_url` is on another host than the files' does not
+/// inherit their token, so a token is never sent to a forge it wasn't
+/// configured for.
+fn apply_profile(
+    table: &mut Table,
+    name: &str,
+    profiles: &[String],
+    origins: &mut BTreeMap<String, Origin>,
+) -> Result<()> {
+    let Some(profile) = table
+        .get("profiles")
+        .and_then(|profiles| profiles.get(name))
+        .and_then(Value::as_table)
+        .cloned()
+    else {
+        let available = if profiles.is_empty() {
+            "none defined".to_string()
+        } else {
+            profiles.join(", ")
+        };
+        return Err(VktError::Config(format!(
+            "Unknown profile '{}' (available: {})",
+            name, available
+        )));
+    };
+
+    let profile_url = profile
+        .get("remote")
+        .and_then(|remote| remote.get("api_url"))
+        .and_then(Value::as_str);
+    if let Some(profile_url) = profile_url {
+        let base_url = get_str(table, "remote.api_url").unwrap_or_default();
+        if !same_api_host(base_url, profile_url) {
+            drop_token(table, origins);
+        }
+    }
+
+    let prefix = format!("profiles.{}.", name);
+    let inherited: Vec<(String, Origin)> = origins
+        .iter()
+        .filter_map(|(key, origin)| Some((key.strip_prefix(&prefix)?.to_string(), origin.clone())))
+        .collect();
+    merge(table, profile, "", &Origin::Default, origins);
+    origins.extend(inherited);
+    Ok(())
+}
+
+/// Remove the token sources from the `[remote]` section of a table
+fn drop_token(table: &mut Table, origins: &mut BTreeMap<String, Origin>) {
+    let Some(remote) = table.get_mut("remote").and_then(Value::as_table_mut) else {
+        return;
+    };
+    for key in TOKEN_KEYS {
+        if remote.remove(*key).is_some() {
+            tracing::debug!("Not applying remote.{} to a profile on another host", key);
+        }
+        origins.remove(&format!("remote.{}", key));
+    }
+}
+
+/// Fill the project and forge from a git remote where the files leave them unset
+///
+/// The project is only taken from the checkout if the configured API URL
+/// belongs to the same forge as the remote (or is inferred from it as well),
+/// so a checkout of another forge never gets paired with the wrong server.
+fn apply_git_remote(table: &mut Table, remote: &GitRemote, origins: &mut BTreeMap<String, Origin>) {
+    let origin = Origin::Git(remote.name.clone());
+    let configured_url = get_str(table, "remote.api_url")
+        .filter(|url| !url.is_empty())
+        .map(str::to_string);
+    let configured_provider = get_str(table, "remote.provider")
+        .filter(|provider| !provider.is_empty())
+        .map(ProviderType::parse);
+
+    let same_forge = match (&configured_url, &configured_provider, remote.provider()) {
+        (Some(api_url), _, _) => remote.matches_api_url(api_url),
+        (None, Some(configured), Some(detected)) => *configured == detected,
+        _ => true,
+    };
+    if !same_forge {
+        tracing::debug!(
+            "Not inferring the project from git remote {}: {} is not the configured forge",
+            remote.name,
+            remote.host
+        );
+        return;
+    }
+
+    let Some(provider) = configured_provider.or_else(|| remote.provider()) else {
+        return;
+    };
+    if configured_url.is_none() {
+        let Some(api_url) = remote.api_url(&provider) else {
+            return;
+        };
+        set_str(table, "remote.api_url", &api_url, &origin, origins);
+    }
+    if get_str(table, "remote.provider").is_none_or(str::is_empty) {
+        set_str(
+            table,
+            "remote.provider",
+            provider.as_str(),
+            &origin,
+            origins,
+        );
+    }
+    if get_str(table, "repo.project_id").is_none_or(str::is_empty) {
+        set_str(
+            table,
+            "repo.project_id",
+            &remote.project_id,
+            &origin,
+            origins,
+        );
+    }
+}
+
+/// Read a dotted `section.field` string value from a table
+fn get_str<'a>(table: &'a Table, key: &str) -> Option<&'a str> {
+    let (section, field) = key.split_once('.')?;
+    table.get(section)?.get(field)?.as_str()
+}
+
+/// Set a dotted `section.field` string value in a table, recording its origin
+fn set_str(
+    table: &mut Table,
+    key: &str,
+    value: &str,
+    origin: &Origin,
+    origins: &mut BTreeMap<String, Origin>,
+) {
+    let Some((section, field)) = key.split_once('.') else {
+        return;
+    };
+    let mut layer = Table::new();
+    layer.insert(field.to_string(), Value::String(value.to_string()));
+    let mut section_layer = Table::new();
+    section_layer.insert(section.to_string(), Value::Table(layer));
+    merge(table, section_layer, "", origin, origins);
+}
+
+/// Write one key into the file at `path`, leaving the other contents of the
+/// file alone
+///
+/// With a `profile`, keys of the [`PROFILE_SECTIONS`] are written to
+/// `[profiles.<profile>]` instead. The value is written even if it is the
+/// built-in default, so it can override a lower layer.
+pub fn save_value(path: &Path, key: &str, value: Value, profile: Option<&str>) -> Result<()> {
+    let source = document::parse_key(key)?;
+    let target = target_path(&source, profile);
+
+    let mut table = read_table(path)?.unwrap_or_default();
+    insert_path(&mut table, &target, value);
+    write_table(path, &table)
+}
+
+/// Remove one key from the file at `path`, leaving the other contents of
+/// the file alone
+///
+/// With a `profile`, keys of the [`PROFILE_SECTIONS`] are removed from
+/// `[profiles.<profile>]` instead.
+///
+/// # Returns
+/// Whether the file set the key
+pub fn unset_value(path: &Path, key: &str, profile: Option<&str>) -> Result<bool> {
+    let source = document::parse_key(key)?;
+    let target = target_path(&source, profile);
+    let Some(mut table) = read_table(path)? else {
+        return Ok(false);
+    };
+    if !remove_path(&mut table, &target) {
+        return Ok(false);
+    }
+    write_table(path, &table)?;
+    Ok(true)
+}
+
+/// Split a `profiles.<name>.<key>` key into the profile and the key it sets
+///
+/// # Returns
+/// None for keys outside `[profiles]`
+///
+/// # Errors
+/// Fails for profile keys outside the [`PROFILE_SECTIONS`]
+pub fn split_profile_key(key: &str) -> Result<Option<(&str, &str)>> {
+    let Some(rest) = key.strip_prefix("profiles.") else {
+        return Ok(None);
+    };
+    let (profile, key) = rest.split_once('.').ok_or_else(|| {
+        VktError::Validation(format!(
+            "Invalid key format 'profiles.{}'. Use profiles.<name>.<section>.<field>",
+            rest
+        ))
+    })?;
+    let section = key.split('.').next().unwrap_or_default();
+    if !PROFILE_SECTIONS.contains(&section) {
+        return Err(VktError::Validation(format!(
+            "Profiles can only set {}, not {}",
+            PROFILE_SECTIONS.join(", "),
+            section
+        )));
+    }
+    Ok(Some((profile, key)))
+}
+
+/// Where a key is stored in a file: in `[profiles.<profile>]` for the
+/// [`PROFILE_SECTIONS`] when a profile is given, at the top level otherwise
+fn target_path<'a>(source: &[&'a str], profile: Option<&'a str>) -> Vec<&'a str> {
+    let mut target = match profile {
+        Some(profile) if PROFILE_SECTIONS.contains(&source[0]) => vec!["profiles", profile],
+        _ => Vec::new(),
+    };
+    target.extend(source);
+    target
+}
+
+/// Write a whole configuration (e.g. from `vkt config --setup`) to the file at `path`
+///
+/// With a `profile`, the [`PROFILE_SECTIONS`] of `config` replace
+/// `[profiles.<profile>]` and the rest of the file is left alone. Otherwise
+/// `config` replaces the file, except for its profiles and `default_profile`.
+pub fn save_config(path: &Path, config: &Config, profile: Option<&str>) -> Result<()> {
+    let serialized = serialize(config)?;
+    let mut table = read_table(path)?.unwrap_or_default();
+    match profile {
+        Some(profile) => {
+            for section in PROFILE_SECTIONS {
+                let target = ["profiles", profile, section];
+                match serialized.get(*section) {
+                    Some(value) => insert_path(&mut table, &target, value.clone()),
+                    None => {
+                        remove_path(&mut table, &target);
+                    }
+                }
+            }
+        }
+        None => {
+            table.retain(|key, _| key == "profiles" || key == "default_profile");
+            for (key, value) in serialized {
+                if key != "default_profile" || !table.contains_key(&key) {
+                    table.insert(key, value);
+                }
+            }
+        }
+    }
+    write_table(path, &table)
+}
+
+fn serialize(config: &Config) -> Result<Table> {
+    Table::try_from(config)
+        .map_err(|e| VktError::Config(format!("Failed to serialize config: {}", e)))
+}
+
+fn write_table(path: &Path, table: &Table) -> Result<()> {
+    let content = toml::to_string_pretty(table)
+        .map_err(|e| VktError::Config(format!("Failed to serialize config: {}", e)))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, content)?;
+    std::fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: &str = r#"
+[user]
+name = "Dev"
+email = "dev@example.com"
+
+[remote]
+provider = "GitHub"
+api_url = "https://api.github.com"
+token = "secret"
+
+[repo]
+project_id = "dev/scratch"
+"#;
+
+    const PROJECT: &str = r#"
+[repo]
+project_id = "team/kernel-tools"
+default_branch = "develop"
+
+[template]
+pr_prefix = "[KERNEL]"
+"#;
+
+    #[test]
+    fn test_later_layers_override_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let system = dir.path().join("system.toml");
+        let user = dir.path().join("user.toml");
+        let project_dir = dir.path().join("repo/src/drivers");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        let project = dir.path().join("repo").join(PROJECT_CONFIG_FILE);
+        std::fs::write(
+            &system,
+            "[network]\nproxy = \"http://proxy.lab:3128\"\n[repo]\nproject_id = \"corp/default\"\n",
+        )
+        .unwrap();
+        std::fs::write(&user, USER).unwrap();
+        std::fs::write(&project, PROJECT).unwrap();
+
+        let mut files = ConfigFiles::discover(user.clone(), &project_dir);
+        assert_eq!(files.project.as_deref(), Some(project.as_path()));
+        files.system = Some(system.clone());
+
+        let layered = LayeredConfig::load(&files, None).unwrap();
+        let config = &layered.config;
+        assert_eq!(config.user.name, "Dev");
+        assert_eq!(config.repo.project_id, "team/kernel-tools");
+        assert_eq!(config.repo.default_branch, "develop");
+        assert_eq!(config.template.pr_prefix, "[KERNEL]");
+        assert_eq!(config.network.proxy, "http://proxy.lab:3128");
+
+        assert_eq!(layered.origin("user.name"), Origin::File(user));
+        assert_eq!(layered.origin("repo.project_id"), Origin::File(project));
+        assert_eq!(layered.origin("network.proxy"), Origin::File(system));
+        assert_eq!(layered.origin("retry.max_retries"), Origin::Default);
+    }
+
+    #[test]
+    fn test_missing_files_and_incomplete_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = ConfigFiles::user_only(dir.path().join("missing.toml"));
+        let err = LayeredConfig::load(&files, None).unwrap_err();
+        assert!(err.to_string().contains("No configuration found"));
+
+        std::fs::write(dir.path().join("missing.toml"), PROJECT).unwrap();
+        let err = LayeredConfig::load(&files, None).unwrap_err();
+        assert!(err.to_string().contains("Incomplete configuration"));
+    }
+
+    #[test]
+    fn test_git_remote_fills_unset_project_and_forge() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("user.toml");
+        std::fs::write(
+            &user,
+            "[user]\nname = \"Dev\"\nemail = \"dev@example.com\"\n[remote]\ntoken = \"secret\"\n",
+        )
+        .unwrap();
+        let mut files = ConfigFiles::user_only(user.clone());
+        files.git_remote = GitRemote::parse("origin", "git@github.com:owner/repo.git");
+
+        let layered = LayeredConfig::load(&files, None).unwrap();
+        assert_eq!(layered.config.repo.project_id, "owner/repo");
+        assert_eq!(layered.config.remote.provider, "github");
+        assert_eq!(layered.config.remote.api_url, "https://api.github.com");
+        let origin = Origin::Git("origin".to_string());
+        assert_eq!(layered.origin("repo.project_id"), origin);
+        assert_eq!(origin.to_string(), "git:origin");
+
+        // Values from the files win
+        std::fs::write(&user, USER).unwrap();
+        let layered = LayeredConfig::load(&files, None).unwrap();
+        assert_eq!(layered.config.repo.project_id, "dev/scratch");
+        assert_eq!(
+            layered.origin("repo.project_id"),
+            Origin::File(user.clone())
+        );
+
+        // A checkout of another forge is not paired with the configured server
+        std::fs::write(&user, USER.replace("dev/scratch", "")).unwrap();
+        files.git_remote = GitRemote::parse("origin", "https://gitlab.com/group/repo.git");
+        let err = LayeredConfig::load(&files, None).unwrap_err();
+        assert!(err.to_string().contains("Project ID"), "{}", err);
+    }
+
+    const PROFILES: &str = r#"
+default_profile = "personal"
+
+[profiles.personal.remote]
+provider = "Gitcode"
+api_url = "https://api.gitcode.com/api/v5"
+token = "personal-token"
+
+[profiles.personal.repo]
+project_id = "dev/dotfiles"
+
+[profiles.corp.user]
+email = "dev@corp.example"
+
+[profiles.corp.remote]
+provider = "GitLab"
+api_url = "https://gitlab.corp.example/api/v4"
+
+[profiles.corp.repo]
+project_id = "virt/kernel"
+"#;
+
+    #[test]
+    fn test_profiles_override_the_merged_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("user.toml");
+        std::fs::write(&user, format!("{}{}", PROFILES, USER)).unwrap();
+        let files = ConfigFiles::user_only(user.clone());
+
+        // default_profile applies when no profile is selected
+        let layered = LayeredConfig::load(&files, None).unwrap();
+        assert_eq!(layered.profile(), Some("personal"));
+        assert_eq!(layered.profiles(), ["corp", "personal"]);
+        assert_eq!(layered.config.remote.token, "personal-token");
+        assert_eq!(layered.config.repo.project_id, "dev/dotfiles");
+
+        let layered = LayeredConfig::load(&files, Some("corp")).unwrap();
+        let config = &layered.config;
+        assert_eq!(config.user.name, "Dev");
+        assert_eq!(config.user.email, "dev@corp.example");
+        assert_eq!(config.remote.api_url, "https://gitlab.corp.example/api/v4");
+        assert_eq!(config.repo.project_id, "virt/kernel");
+        assert_eq!(layered.origin("repo.project_id"), Origin::File(user));
+
+        let err = LayeredConfig::load(&files, Some("upstream")).unwrap_err();
+        assert!(
+            err.to_string().contains("available: corp, personal"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_profiles_on_another_host_do_not_inherit_the_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("user.toml");
+        let same_host = r#"
+[profiles.fork.repo]
+project_id = "dev/fork"
+
+[profiles.mirror.remote]
+api_url = "https://API.github.com/"
+"#;
+        std::fs::write(&user, format!("{}{}{}", PROFILES, same_host, USER)).unwrap();
+        let files = ConfigFiles::user_only(user);
+
+        // The GitHub token is never sent to the corp GitLab host
+        let layered = LayeredConfig::load(&files, Some("corp")).unwrap();
+        assert!(!layered.config.remote.has_token_source());
+        assert_eq!(layered.origin("remote.token"), Origin::Default);
+
+        // Profiles on the same host keep using it
+        for profile in ["fork", "mirror"] {
+            let layered = LayeredConfig::load(&files, Some(profile)).unwrap();
+            assert_eq!(layered.config.remote.token, "secret");
+        }
+    }
+
+    #[test]
+    fn test_save_into_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("user.toml");
+        std::fs::write(&path, format!("{}{}", PROFILES, USER)).unwrap();
+        let files = ConfigFiles::user_only(path.clone());
+
+        let mut config = LayeredConfig::load(&files, Some("corp")).unwrap().config;
+        save_value(&path, "remote.token", "corp-token".into(), Some("corp")).unwrap();
+        save_value(&path, "retry.max_retries", 5.into(), Some("corp")).unwrap();
+
+        let table = read_table(&path).unwrap().unwrap();
+        assert_eq!(
+            table["profiles"]["corp"]["remote"]["token"].as_str(),
+            Some("corp-token")
+        );
+        assert_eq!(table["remote"]["token"].as_str(), Some("secret"));
+        assert_eq!(table["retry"]["max_retries"].as_integer(), Some(5));
+
+        // A new profile from setup leaves the rest of the file alone
+        config
+            .set_value("repo.project_id", "upstream/linux")
+            .unwrap();
+        save_config(&path, &config, Some("upstream")).unwrap();
+        let layered = LayeredConfig::load(&files, Some("upstream")).unwrap();
+        assert_eq!(layered.config.repo.project_id, "upstream/linux");
+        assert_eq!(layered.profiles(), ["corp", "personal", "upstream"]);
+
+        // Setup without a profile keeps the profiles
+        save_config(&path, &Config::parse_from_str(USER).unwrap(), None).unwrap();
+        let layered = LayeredConfig::load(&files, None).unwrap();
+        assert_eq!(layered.profile(), Some("personal"));
+        assert_eq!(layered.profiles().len(), 3);
+        assert!(read_table(&path).unwrap().unwrap().get("retry").is_none());
+    }
+
+    #[test]
+    fn test_save_value_only_touches_one_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PROJECT_CONFIG_FILE);
+        std::fs::write(&path, PROJECT).unwrap();
+
+        save_value(&path, "template.pr_prefix", "[NET]".into(), None).unwrap();
+        save_value(&path, "retry.max_retries", 5.into(), None).unwrap();
+
+        let table = read_table(&path).unwrap().unwrap();
+        assert_eq!(table["template"]["pr_prefix"].as_str(), Some("[NET]"));
+        assert_eq!(table["retry"]["max_retries"].as_integer(), Some(5));
+        assert_eq!(
+            table["repo"]["project_id"].as_str(),
+            Some("team/kernel-tools")
+        );
+        assert!(table.get("user").is_none());
+
+        // The default is written too, so it overrides the files below
+        save_value(&path, "retry.max_retries", 3.into(), None).unwrap();
+        let table = read_table(&path).unwrap().unwrap();
+        assert_eq!(table["retry"]["max_retries"].as_integer(), Some(3));
+    }
+
+    #[test]
+    fn test_env_var_name() {
+        assert_eq!(env_var_name("user.name"), "VKT_USER_NAME");
+        assert_eq!(env_var_name("retry.max_retries"), "VKT_RETRY_MAX_RETRIES");
+    }
+}
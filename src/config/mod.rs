@@ -1,15 +1,36 @@
 //! Configuration management module
 //!
-//! Handles loading and validation of TOML configuration files
+//! Handles loading and validation of TOML configuration files, layered from
+//! system, user and project files (see [`layers`]). The effective
+//! configuration of an invocation, with profiles, `--repo` and the
+//! environment applied, comes from
+//! [`RuntimeContext::load_config`](crate::context::RuntimeContext::load_config).
+
+pub mod checkout;
+pub mod credentials;
+pub mod document;
+pub mod layers;
+pub mod repo_spec;
 
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use toml::{Table, Value};
 
 use crate::error::{Result, VktError};
 
+pub use checkout::GitRemote;
+pub use layers::{ConfigFiles, LayeredConfig, Origin};
+pub use repo_spec::RepoSpec;
+
 /// Environment variable prefix
 const ENV_PREFIX: &str = "VKT";
 
+/// Environment variable naming the configuration file
+pub const CONFIG_PATH_ENV: &str = "VKT_CONFIG";
+
+/// Environment variable selecting the profile
+pub const PROFILE_ENV: &str = "VKT_PROFILE";
+
 /// Main configuration struct
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
@@ -22,6 +43,18 @@ pub struct Config {
     /// Template configuration
     #[serde(default)]
     pub template: TemplateConfig,
+    /// Retry policy for transient API failures
+    #[serde(default, skip_serializing_if = "RetryConfig::is_default")]
+    pub retry: RetryConfig,
+    /// HTTP client settings (proxy, TLS, timeouts)
+    #[serde(default, skip_serializing_if = "NetworkConfig::is_default")]
+    pub network: NetworkConfig,
+    /// On-disk response cache
+    #[serde(default, skip_serializing_if = "CacheConfig::is_default")]
+    pub cache: CacheConfig,
+    /// Profile applied when neither `--profile` nor `VKT_PROFILE` selects one
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub default_profile: String,
 }
 
 /// User configuration
@@ -42,6 +75,13 @@ pub enum ProviderType {
     GitCode,
     GitLab,
     GitHub,
+    /// Gitea and Forgejo (API-compatible)
+    Gitea,
+    Gitee,
+    /// Local bare or non-bare git repository
+    Local,
+    /// Identify the forge by probing the API URL
+    Auto,
     Unknown(String),
 }
 
@@ -52,6 +92,10 @@ impl ProviderType {
             "gitcode" => ProviderType::GitCode,
             "gitlab" => ProviderType::GitLab,
             "github" => ProviderType::GitHub,
+            "gitea" | "forgejo" => ProviderType::Gitea,
+            "gitee" => ProviderType::Gitee,
+            "local" => ProviderType::Local,
+            "auto" => ProviderType::Auto,
             other => ProviderType::Unknown(other.to_string()),
         }
     }
@@ -62,6 +106,10 @@ impl ProviderType {
             ProviderType::GitCode => "gitcode",
             ProviderType::GitLab => "gitlab",
             ProviderType::GitHub => "github",
+            ProviderType::Gitea => "gitea",
+            ProviderType::Gitee => "gitee",
+            ProviderType::Local => "local",
+            ProviderType::Auto => "auto",
             ProviderType::Unknown(s) => s.as_str(),
         }
     }
@@ -72,20 +120,32 @@ impl ProviderType {
             ProviderType::GitCode => Some("https://api.gitcode.com/api/v5"),
             ProviderType::GitLab => Some("https://gitlab.com/api/v4"),
             ProviderType::GitHub => Some("https://api.github.com"),
-            ProviderType::Unknown(_) => None,
+            ProviderType::Gitea => Some("https://gitea.com/api/v1"),
+            ProviderType::Gitee => Some("https://gitee.com/api/v5"),
+            ProviderType::Local | ProviderType::Auto | ProviderType::Unknown(_) => None,
         }
     }
 }
 
 /// Remote repository configuration
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct RemoteConfig {
-    /// Provider (Gitcode, GitLab, GitHub, etc.)
+    /// Provider (Gitcode, GitLab, GitHub, Gitea, Gitee, Local, Auto, etc.)
     pub provider: String,
     /// API URL
     pub api_url: String,
-    /// Access token
+    /// Access token (not used by the local provider)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub token: String,
+    /// Shell command printing the access token
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub token_command: String,
+    /// File holding the access token
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub token_file: String,
+    /// Take the access token from git's credential helpers
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub git_credential: bool,
 }
 
 impl RemoteConfig {
@@ -93,17 +153,53 @@ impl RemoteConfig {
     pub fn provider_type(&self) -> ProviderType {
         ProviderType::parse(&self.provider)
     }
+
+    /// Whether any token source is configured
+    pub fn has_token_source(&self) -> bool {
+        !self.token.is_empty()
+            || !self.token_command.is_empty()
+            || !self.token_file.is_empty()
+            || self.git_credential
+    }
+
+    /// Resolve the access token from the configured source (see [`credentials`])
+    ///
+    /// Token commands and credential helpers run on every call, so callers
+    /// should resolve once and keep the result.
+    pub fn resolve_token(&self) -> Result<String> {
+        credentials::resolve_token(self)
+    }
 }
 
-/// Auto-detect provider type from API URL
+/// Guess the provider type from an API URL without network access
+///
+/// Matches well-known host names first, then the conventional API path
+/// (`/api/v4` for GitLab, `/api/v3` for GitHub Enterprise, `/api/v1` for Gitea).
+/// Use `provider = "auto"` to identify self-hosted forges by probing instead.
 pub fn detect_provider(api_url: &str) -> ProviderType {
     let url_lower = api_url.to_lowercase();
-    if url_lower.contains("gitcode.com") {
+    let path = url_lower.trim_end_matches('/');
+    if url_lower.starts_with("file://") {
+        ProviderType::Local
+    } else if url_lower.contains("gitcode.com") {
         ProviderType::GitCode
+    } else if url_lower.contains("gitee.com") {
+        ProviderType::Gitee
     } else if url_lower.contains("gitlab") || url_lower.contains("git-lab") {
         ProviderType::GitLab
     } else if url_lower.contains("github.com") {
         ProviderType::GitHub
+    } else if url_lower.contains("gitea")
+        || url_lower.contains("forgejo")
+        || url_lower.contains("codeberg.org")
+    {
+        ProviderType::Gitea
+    } else if path.ends_with("/api/v4") {
+        ProviderType::GitLab
+    } else if path.ends_with("/api/v3") {
+        ProviderType::GitHub
+    } else if path.ends_with("/api/v1") {
+        ProviderType::Gitea
     } else {
         ProviderType::Unknown("unknown".to_string())
     }
@@ -127,6 +223,124 @@ pub struct TemplateConfig {
     pub pr_prefix: String,
 }
 
+/// Retry configuration for transient API failures (5xx gateway errors, rate limits)
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Retries after the first attempt (0 disables retrying)
+    pub max_retries: u32,
+    /// Delay before the first retry in milliseconds, doubled on every attempt
+    pub initial_delay_ms: u64,
+    /// Upper bound for a single delay in milliseconds; a longer Retry-After is not waited for
+    pub max_delay_ms: u64,
+    /// Also retry writes (POST, PUT, PATCH, DELETE) that the server may have received
+    ///
+    /// Writes that never reached the server are retried regardless.
+    pub retry_mutations: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay_ms: 500,
+            max_delay_ms: 30_000,
+            retry_mutations: false,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Whether all settings are at their defaults (omitted when saving)
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// HTTP client configuration shared by all providers
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Proxy URL for all requests (empty: use the HTTPS_PROXY/HTTP_PROXY environment)
+    pub proxy: String,
+    /// Comma-separated hosts, domains or CIDR ranges that bypass the proxy
+    pub no_proxy: String,
+    /// PEM bundle of additional trusted CA certificates
+    pub ca_bundle: String,
+    /// PEM client certificate for mutual TLS
+    pub client_cert: String,
+    /// PEM (PKCS#8) private key for the client certificate
+    pub client_key: String,
+    /// Connection timeout in seconds
+    pub connect_timeout_secs: u64,
+    /// Timeout in seconds for each read from the server
+    pub read_timeout_secs: u64,
+    /// User-Agent header (empty: vkt/<version>)
+    pub user_agent: String,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            proxy: String::new(),
+            no_proxy: String::new(),
+            ca_bundle: String::new(),
+            client_cert: String::new(),
+            client_key: String::new(),
+            connect_timeout_secs: 10,
+            read_timeout_secs: 30,
+            user_agent: String::new(),
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// Whether all settings are at their defaults (omitted when saving)
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The User-Agent header to send
+    pub fn user_agent(&self) -> String {
+        if self.user_agent.is_empty() {
+            format!("vkt/{}", crate::VERSION)
+        } else {
+            self.user_agent.clone()
+        }
+    }
+}
+
+/// Response cache configuration
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Cache GET responses and revalidate them with ETag/Last-Modified
+    pub enabled: bool,
+    /// Cache directory (empty: ~/.cache/vkt/http)
+    pub dir: String,
+    /// Answer from the cache only, without network access (set by --offline
+    /// or VKT_OFFLINE, never saved)
+    #[serde(skip)]
+    pub offline: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: String::new(),
+            offline: false,
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Whether all settings are at their defaults (omitted when saving)
+    pub fn is_default(&self) -> bool {
+        self.enabled == Self::default().enabled && self.dir.is_empty()
+    }
+}
+
 fn default_branch() -> String {
     "main".to_string()
 }
@@ -139,99 +353,161 @@ impl Config {
         Ok(config_dir.join("vkt").join("config.toml"))
     }
 
-    /// Parse dotted key path (e.g., "user.name") into section and field
-    pub fn parse_key(key: &str) -> Result<(&str, &str)> {
-        let parts: Vec<&str> = key.split('.').collect();
-        if parts.len() != 2 {
-            return Err(VktError::Validation(format!(
-                "Invalid key format '{}'. Use format: section.field (e.g., user.name)",
-                key
-            )));
+    /// A valid configuration for tests, talking to `api_url` with a fixed
+    /// token and the response cache disabled
+    #[cfg(any(test, feature = "testing"))]
+    pub fn for_tests(provider: &str, api_url: &str) -> Config {
+        Config {
+            user: UserConfig {
+                name: "Test".to_string(),
+                email: "test@example.com".to_string(),
+                auto_signoff: true,
+            },
+            remote: RemoteConfig {
+                provider: provider.to_string(),
+                api_url: api_url.to_string(),
+                token: "test-token".to_string(),
+                ..Default::default()
+            },
+            repo: RepoConfig {
+                project_id: "owner/repo".to_string(),
+                default_branch: default_branch(),
+            },
+            template: TemplateConfig::default(),
+            retry: Default::default(),
+            network: Default::default(),
+            cache: CacheConfig {
+                enabled: false,
+                ..Default::default()
+            },
+            default_profile: Default::default(),
         }
-        Ok((parts[0], parts[1]))
+    }
+
+    /// Resolve the configuration file path
+    ///
+    /// An explicit path (from `--config`) wins over `VKT_CONFIG`, which wins
+    /// over [`Config::default_path`].
+    pub fn resolve_path(explicit: Option<&str>) -> Result<PathBuf> {
+        let from_env = std::env::var(CONFIG_PATH_ENV).ok();
+        match explicit.or(from_env.as_deref()) {
+            Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+            _ => Self::default_path(),
+        }
+    }
+
+    /// The configuration as a TOML table, including the settings that saved
+    /// files leave out while they are empty or at their defaults
+    pub fn to_table(&self) -> Result<Table> {
+        let mut table = Table::try_from(self)
+            .map_err(|e| VktError::Config(format!("Failed to serialize config: {}", e)))?;
+        let omitted = [
+            (
+                "default_profile",
+                Value::from(self.default_profile.as_str()),
+            ),
+            ("remote.token", Value::from(self.remote.token.as_str())),
+            (
+                "remote.token_command",
+                Value::from(self.remote.token_command.as_str()),
+            ),
+            (
+                "remote.token_file",
+                Value::from(self.remote.token_file.as_str()),
+            ),
+            (
+                "remote.git_credential",
+                Value::from(self.remote.git_credential),
+            ),
+            ("retry", Self::section(&self.retry)?),
+            ("network", Self::section(&self.network)?),
+            ("cache", Self::section(&self.cache)?),
+        ];
+        for (key, value) in omitted {
+            let path: Vec<&str> = key.split('.').collect();
+            if document::get_path(&table, &path).is_none() {
+                document::insert_path(&mut table, &path, value);
+            }
+        }
+        Ok(table)
+    }
+
+    fn section<T: Serialize>(section: &T) -> Result<Value> {
+        Value::try_from(section)
+            .map_err(|e| VktError::Config(format!("Failed to serialize config: {}", e)))
+    }
+
+    /// Every settable key in dotted notation (e.g. "user.name")
+    pub fn keys(&self) -> Result<Vec<String>> {
+        Ok(document::leaf_keys(&self.to_table()?))
     }
 
     /// Get a config value as string by key path
-    /// Note: remote.token is masked for security; use environment variable for scripting
+    ///
+    /// A section (e.g. "retry") is shown as TOML. Tokens are masked for
+    /// security; use environment variables for scripting.
     pub fn get_value(&self, key: &str) -> Result<String> {
-        let (section, field) = Self::parse_key(key)?;
+        let path = document::parse_key(key)?;
+        let table = self.to_table()?;
+        let value = document::get_path(&table, &path)
+            .ok_or_else(|| VktError::Validation(format!("Unknown config key: {}", key)))?;
+        Ok(document::display(&document::masked(key, value)))
+    }
 
-        match (section, field) {
-            ("user", "name") => Ok(self.user.name.clone()),
-            ("user", "email") => Ok(self.user.email.clone()),
-            ("user", "auto_signoff") => Ok(self.user.auto_signoff.to_string()),
-            ("remote", "provider") => Ok(self.remote.provider.clone()),
-            ("remote", "api_url") => Ok(self.remote.api_url.clone()),
-            ("remote", "token") => Ok("********".to_string()),
-            ("repo", "project_id") => Ok(self.repo.project_id.clone()),
-            ("repo", "default_branch") => Ok(self.repo.default_branch.clone()),
-            ("template", "pr_prefix") => Ok(self.template.pr_prefix.clone()),
-            _ => Err(VktError::Validation(format!("Unknown config key: {}", key))),
-        }
+    /// Convert a value given as a string to the TOML value of a key
+    ///
+    /// The value is converted to the type of the setting and checked by its
+    /// validator, if it has one.
+    pub fn parse_value(&self, key: &str, value: &str) -> Result<Value> {
+        let path = document::parse_key(key)?;
+        let table = self.to_table()?;
+        let current = document::get_path(&table, &path)
+            .ok_or_else(|| VktError::Validation(format!("Unknown config key: {}", key)))?;
+        let parsed = document::parse_as(current, key, value)?;
+        self.validate_value(key, value)?;
+        Ok(parsed)
     }
 
     /// Update a single config value by key path
+    ///
+    /// The value is converted and checked as in [`Config::parse_value`].
     pub fn set_value(&mut self, key: &str, value: &str) -> Result<()> {
-        let (section, field) = Self::parse_key(key)?;
+        let parsed = self.parse_value(key, value)?;
+        let path = document::parse_key(key)?;
+        let mut table = self.to_table()?;
+        document::insert_path(&mut table, &path, parsed);
+
+        // The offline switch is never serialized
+        let offline = self.cache.offline;
+        *self = Value::Table(table)
+            .try_into()
+            .map_err(|e| VktError::Validation(format!("Invalid value for {}: {}", key, e)))?;
+        self.cache.offline = offline;
+        Ok(())
+    }
 
-        match (section, field) {
-            ("user", "name") => {
-                if value.is_empty() {
-                    return Err(VktError::Validation("User name cannot be empty".to_string()));
-                }
-                self.user.name = value.to_string();
+    /// Check a value against the format rules of its key, if there are any
+    fn validate_value(&self, key: &str, value: &str) -> Result<()> {
+        let provider = self.remote.provider_type();
+        let error = match key {
+            "user.name" | "remote.provider" | "remote.token" | "repo.default_branch"
+                if value.is_empty() =>
+            {
+                format!("{} cannot be empty", key)
             }
-            ("user", "email") => {
-                if !Self::is_valid_email(value) {
-                    return Err(VktError::Validation(format!("Invalid email: {}", value)));
-                }
-                self.user.email = value.to_string();
+            "user.email" if !Self::is_valid_email(value) => format!("Invalid email: {}", value),
+            "remote.api_url" if !Self::is_valid_api_url(value, &provider) => {
+                format!("Invalid URL: {}", value)
             }
-            ("user", "auto_signoff") => {
-                self.user.auto_signoff = value.parse().map_err(|_| {
-                    VktError::Validation(format!("Expected boolean value: {}", value))
-                })?;
+            "repo.project_id" if !Self::is_valid_project_id(value, &provider) => {
+                format!("Project ID must be {}", Self::project_id_format(&provider))
             }
-            ("remote", "provider") => {
-                if value.is_empty() {
-                    return Err(VktError::Validation("Provider cannot be empty".to_string()));
-                }
-                self.remote.provider = value.to_string();
-            }
-            ("remote", "api_url") => {
-                if !Self::is_valid_url(value) {
-                    return Err(VktError::Validation(format!("Invalid URL: {}", value)));
-                }
-                self.remote.api_url = value.to_string();
-            }
-            ("remote", "token") => {
-                if value.is_empty() {
-                    return Err(VktError::Validation("Token cannot be empty".to_string()));
-                }
-                self.remote.token = value.to_string();
-            }
-            ("repo", "project_id") => {
-                if !value.contains('/') {
-                    return Err(VktError::Validation(
-                        "Project ID must be in format: owner/repo".to_string(),
-                    ));
-                }
-                self.repo.project_id = value.to_string();
-            }
-            ("repo", "default_branch") => {
-                if value.is_empty() {
-                    return Err(VktError::Validation(
-                        "Default branch cannot be empty".to_string(),
-                    ));
-                }
-                self.repo.default_branch = value.to_string();
+            "network.proxy" if !value.is_empty() && reqwest::Url::parse(value).is_err() => {
+                format!("Invalid proxy URL: {}", value)
             }
-            ("template", "pr_prefix") => {
-                self.template.pr_prefix = value.to_string();
-            }
-            _ => return Err(VktError::Validation(format!("Unknown config key: {}", key))),
-        }
-        Ok(())
+            _ => return Ok(()),
+        };
+        Err(VktError::Validation(error))
     }
 
     /// Save config to file atomically
@@ -258,7 +534,24 @@ impl Config {
     }
 
     /// Run interactive configuration setup
+    ///
+    /// The API URL and project ID are pre-filled from the `origin` remote of
+    /// the current git checkout, and the provider from the API URL using
+    /// [`detect_provider`].
     pub fn interactive_setup() -> Result<Self> {
+        let checkout = GitRemote::discover(&std::env::current_dir()?, checkout::DEFAULT_REMOTE);
+        Self::interactive_setup_with(checkout.as_ref(), |api_url, _token| {
+            detect_provider(api_url)
+        })
+    }
+
+    /// Run interactive configuration setup, pre-filling the API URL and
+    /// project ID from `checkout` and the provider with `detect(api_url, token)`
+    /// (e.g. a network probe)
+    pub fn interactive_setup_with(
+        checkout: Option<&GitRemote>,
+        detect: impl Fn(&str, &str) -> ProviderType,
+    ) -> Result<Self> {
         use std::io::{self, Write};
 
         println!("Welcome to ForgeFlow! Let's set up your configuration.\n");
@@ -320,38 +613,84 @@ impl Config {
 
         let auto_signoff = prompt_bool("Auto sign-off commits? (y/n): ")?;
 
-        // Remote section
-        let provider = prompt_with_default("Provider (Gitcode/GitLab/GitHub)", "Gitcode")?;
-
-        // Get default API URL based on provider
-        let provider_type = ProviderType::parse(&provider);
-        let default_url = provider_type
-            .default_api_url()
-            .unwrap_or("https://api.example.com");
-
+        // Remote section: the URL comes first so the provider can be pre-filled from it
+        let checkout_api_url =
+            checkout.and_then(|remote| remote.provider().and_then(|p| remote.api_url(&p)));
         let api_url = loop {
-            let url = prompt_with_default("API URL", default_url)?;
-            if Self::is_valid_url(&url) {
+            let url = prompt_with_default(
+                "API URL",
+                checkout_api_url
+                    .as_deref()
+                    .unwrap_or(ProviderType::GitCode.default_api_url().unwrap_or_default()),
+            )?;
+            if Self::is_valid_url(&url) || Self::is_valid_api_url(&url, &ProviderType::Local) {
                 break url;
             }
             println!("Invalid URL format. Please try again.");
         };
 
-        let token = loop {
-            let token = prompt("API Token: ")?;
-            if !token.is_empty() {
-                break token;
+        // Local repositories are accessed directly and need no token. A leading
+        // '!' stores a command printing the token instead of the token itself.
+        let is_local = detect_provider(&api_url) == ProviderType::Local;
+        let (token, token_command) = if is_local {
+            (String::new(), String::new())
+        } else {
+            loop {
+                let input = prompt("API Token (or !<command> printing it): ")?;
+                match input.strip_prefix('!').map(str::trim) {
+                    Some(command) if !command.is_empty() => {
+                        break (String::new(), command.to_string());
+                    }
+                    None if !input.is_empty() => break (input, String::new()),
+                    _ => println!("Token cannot be empty. Please try again."),
+                }
+            }
+        };
+
+        let detected = if is_local {
+            ProviderType::Local
+        } else {
+            let probe_token = RemoteConfig {
+                token: token.clone(),
+                token_command: token_command.clone(),
+                ..Default::default()
             }
-            println!("Token cannot be empty. Please try again.");
+            .resolve_token()
+            .unwrap_or_default();
+            detect(&api_url, &probe_token)
+        };
+        let suggested = match detected {
+            ProviderType::Unknown(_) => "auto",
+            ref known => known.as_str(),
         };
+        let provider = prompt_with_default(
+            "Provider (Auto/Gitcode/GitLab/GitHub/Gitea/Gitee/Local)",
+            suggested,
+        )?;
+        let provider_type = ProviderType::parse(&provider);
+        if !Self::is_valid_api_url(&api_url, &provider_type) {
+            return Err(VktError::Config(format!(
+                "API URL {} cannot be used with provider {}",
+                api_url, provider
+            )));
+        }
 
         // Repo section
+        let checkout_project = checkout
+            .filter(|remote| remote.matches_api_url(&api_url))
+            .map(|remote| remote.project_id.as_str());
         let project_id = loop {
-            let id = prompt("Project ID (owner/repo): ")?;
-            if id.contains('/') {
+            let id = match checkout_project {
+                Some(project) => prompt_with_default("Project ID (owner/repo)", project)?,
+                None => prompt("Project ID (owner/repo): ")?,
+            };
+            if Self::is_valid_project_id(&id, &provider_type) {
                 break id;
             }
-            println!("Project ID must be in format: owner/repo");
+            println!(
+                "Project ID must be {}",
+                Self::project_id_format(&provider_type)
+            );
         };
 
         let default_branch = prompt_with_default("Default branch", "main")?;
@@ -369,12 +708,18 @@ impl Config {
                 provider,
                 api_url,
                 token,
+                token_command,
+                ..Default::default()
             },
             repo: RepoConfig {
                 project_id,
                 default_branch,
             },
             template: TemplateConfig { pr_prefix },
+            retry: Default::default(),
+            network: Default::default(),
+            cache: Default::default(),
+            default_profile: Default::default(),
         };
 
         // Validate before returning
@@ -383,10 +728,39 @@ impl Config {
         Ok(config)
     }
 
+    /// The access token for API requests, resolved from the configured source
+    ///
+    /// Offline runs never send requests, so they skip token commands and
+    /// credential helpers.
+    ///
+    /// # Errors
+    /// Fails if a forge needs a token and no source is configured
+    pub fn access_token(&self) -> Result<String> {
+        if self.cache.offline {
+            return Ok(self.remote.token.clone());
+        }
+        let needs_token = !matches!(
+            self.remote.provider_type(),
+            ProviderType::Local | ProviderType::Auto
+        );
+        if needs_token && !self.remote.has_token_source() {
+            return Err(VktError::Config(
+                "No access token configured. Run `vkt auth login`, or set remote.token_command, remote.token_file or remote.git_credential"
+                    .to_string(),
+            ));
+        }
+        self.remote.resolve_token()
+    }
+
     /// Load configuration from file
     pub fn parse_from_file(path: &PathBuf) -> Result<Self> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| VktError::Config(format!("Failed to read configuration file: {}", e)))?;
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            VktError::Config(format!(
+                "Failed to read configuration file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
         Self::parse_from_str(&content)
     }
 
@@ -399,12 +773,6 @@ impl Config {
         Ok(config)
     }
 
-    /// Load default configuration
-    pub fn load() -> Result<Self> {
-        let path = Self::default_path()?;
-        Self::parse_from_file(&path)
-    }
-
     /// Apply environment variable overrides
     fn apply_env_overrides(&mut self) {
         // User configuration
@@ -428,6 +796,15 @@ impl Config {
         if let Ok(token) = std::env::var(format!("{}_REMOTE_TOKEN", ENV_PREFIX)) {
             self.remote.token = token;
         }
+        if let Ok(command) = std::env::var(format!("{}_REMOTE_TOKEN_COMMAND", ENV_PREFIX)) {
+            self.remote.token_command = command;
+        }
+        if let Ok(file) = std::env::var(format!("{}_REMOTE_TOKEN_FILE", ENV_PREFIX)) {
+            self.remote.token_file = file;
+        }
+        if let Ok(enabled) = std::env::var(format!("{}_REMOTE_GIT_CREDENTIAL", ENV_PREFIX)) {
+            self.remote.git_credential = enabled.parse().unwrap_or(self.remote.git_credential);
+        }
 
         // Repository configuration
         if let Ok(project_id) = std::env::var(format!("{}_REPO_PROJECT_ID", ENV_PREFIX)) {
@@ -441,6 +818,60 @@ impl Config {
         if let Ok(pr_prefix) = std::env::var(format!("{}_TEMPLATE_PR_PREFIX", ENV_PREFIX)) {
             self.template.pr_prefix = pr_prefix;
         }
+
+        // Retry configuration
+        if let Ok(max_retries) = std::env::var(format!("{}_RETRY_MAX_RETRIES", ENV_PREFIX)) {
+            self.retry.max_retries = max_retries.parse().unwrap_or(self.retry.max_retries);
+        }
+        if let Ok(delay) = std::env::var(format!("{}_RETRY_INITIAL_DELAY_MS", ENV_PREFIX)) {
+            self.retry.initial_delay_ms = delay.parse().unwrap_or(self.retry.initial_delay_ms);
+        }
+        if let Ok(delay) = std::env::var(format!("{}_RETRY_MAX_DELAY_MS", ENV_PREFIX)) {
+            self.retry.max_delay_ms = delay.parse().unwrap_or(self.retry.max_delay_ms);
+        }
+        if let Ok(mutations) = std::env::var(format!("{}_RETRY_RETRY_MUTATIONS", ENV_PREFIX)) {
+            self.retry.retry_mutations = mutations.parse().unwrap_or(self.retry.retry_mutations);
+        }
+
+        // Network configuration
+        if let Ok(proxy) = std::env::var(format!("{}_NETWORK_PROXY", ENV_PREFIX)) {
+            self.network.proxy = proxy;
+        }
+        if let Ok(no_proxy) = std::env::var(format!("{}_NETWORK_NO_PROXY", ENV_PREFIX)) {
+            self.network.no_proxy = no_proxy;
+        }
+        if let Ok(ca_bundle) = std::env::var(format!("{}_NETWORK_CA_BUNDLE", ENV_PREFIX)) {
+            self.network.ca_bundle = ca_bundle;
+        }
+        if let Ok(client_cert) = std::env::var(format!("{}_NETWORK_CLIENT_CERT", ENV_PREFIX)) {
+            self.network.client_cert = client_cert;
+        }
+        if let Ok(client_key) = std::env::var(format!("{}_NETWORK_CLIENT_KEY", ENV_PREFIX)) {
+            self.network.client_key = client_key;
+        }
+        if let Ok(timeout) = std::env::var(format!("{}_NETWORK_CONNECT_TIMEOUT_SECS", ENV_PREFIX)) {
+            self.network.connect_timeout_secs =
+                timeout.parse().unwrap_or(self.network.connect_timeout_secs);
+        }
+        if let Ok(timeout) = std::env::var(format!("{}_NETWORK_READ_TIMEOUT_SECS", ENV_PREFIX)) {
+            self.network.read_timeout_secs =
+                timeout.parse().unwrap_or(self.network.read_timeout_secs);
+        }
+        if let Ok(user_agent) = std::env::var(format!("{}_NETWORK_USER_AGENT", ENV_PREFIX)) {
+            self.network.user_agent = user_agent;
+        }
+
+        // Cache configuration
+        if let Ok(enabled) = std::env::var(format!("{}_CACHE_ENABLED", ENV_PREFIX)) {
+            self.cache.enabled = enabled.parse().unwrap_or(self.cache.enabled);
+        }
+        if let Ok(dir) = std::env::var(format!("{}_CACHE_DIR", ENV_PREFIX)) {
+            self.cache.dir = dir;
+        }
+        if let Ok(offline) = std::env::var(format!("{}_OFFLINE", ENV_PREFIX)) {
+            self.cache.offline =
+                matches!(offline.to_lowercase().as_str(), "1" | "true" | "yes" | "on");
+        }
     }
 
     /// Validate configuration
@@ -466,23 +897,28 @@ impl Config {
         if self.remote.api_url.is_empty() {
             return Err(VktError::Config("API URL cannot be empty".to_string()));
         }
-        if !Self::is_valid_url(&self.remote.api_url) {
+        if !Self::is_valid_api_url(&self.remote.api_url, &self.remote.provider_type()) {
             return Err(VktError::Config(format!(
                 "Invalid API URL format: {}",
                 self.remote.api_url
             )));
         }
-        if self.remote.token.is_empty() {
-            return Err(VktError::Config("Access token cannot be empty".to_string()));
-        }
 
         // Repository configuration validation
         if self.repo.project_id.is_empty() {
             return Err(VktError::Config("Project ID cannot be empty".to_string()));
         }
-        if !self.repo.project_id.contains('/') {
+        if !Self::is_valid_project_id(&self.repo.project_id, &self.remote.provider_type()) {
+            return Err(VktError::Config(format!(
+                "Project ID must be {}",
+                Self::project_id_format(&self.remote.provider_type())
+            )));
+        }
+
+        // Network configuration validation
+        if self.network.client_cert.is_empty() != self.network.client_key.is_empty() {
             return Err(VktError::Config(
-                "Project ID format should be 'owner/repo'".to_string(),
+                "network.client_cert and network.client_key must be set together".to_string(),
             ));
         }
 
@@ -498,11 +934,48 @@ impl Config {
             && email.len() > 5
     }
 
+    /// Project ID validation
+    ///
+    /// GitLab also accepts numeric project IDs and URL-encoded paths (`group%2Frepo`).
+    fn is_valid_project_id(project_id: &str, provider: &ProviderType) -> bool {
+        if project_id.contains('/') {
+            return true;
+        }
+        match provider {
+            ProviderType::Local => !project_id.is_empty(),
+            // The probed provider checks the exact format once it is known
+            ProviderType::GitLab | ProviderType::Auto => {
+                (!project_id.is_empty() && project_id.chars().all(|c| c.is_ascii_digit()))
+                    || project_id.to_lowercase().contains("%2f")
+            }
+            _ => false,
+        }
+    }
+
+    /// Accepted project ID formats, for messages
+    fn project_id_format(provider: &ProviderType) -> &'static str {
+        match provider {
+            ProviderType::GitLab | ProviderType::Auto => {
+                "a path (owner/repo or group/subgroup/repo), a numeric ID or a URL-encoded path"
+            }
+            ProviderType::Local => "a name or owner/repo",
+            _ => "in format: owner/repo",
+        }
+    }
+
     /// Simple URL format validation
     fn is_valid_url(url: &str) -> bool {
         (url.starts_with("http://") || url.starts_with("https://")) && url.len() > 10
     }
 
+    /// API URL validation for a provider (local repositories use file:// URLs)
+    fn is_valid_api_url(url: &str, provider: &ProviderType) -> bool {
+        match provider {
+            ProviderType::Local => url.len() > "file://".len() && url.starts_with("file://"),
+            _ => Self::is_valid_url(url),
+        }
+    }
+
     /// Generate configuration example
     pub fn example() -> String {
         r#"# VKT Configuration File Example
@@ -549,23 +1022,7 @@ mod tests {
     use super::*;
 
     fn create_valid_config() -> Config {
-        Config {
-            user: UserConfig {
-                name: "Test".to_string(),
-                email: "test@example.com".to_string(),
-                auto_signoff: true,
-            },
-            remote: RemoteConfig {
-                provider: "Gitcode".to_string(),
-                api_url: "https://api.example.com".to_string(),
-                token: "token123".to_string(),
-            },
-            repo: RepoConfig {
-                project_id: "owner/repo".to_string(),
-                default_branch: "main".to_string(),
-            },
-            template: TemplateConfig::default(),
-        }
+        Config::for_tests("Gitcode", "https://api.example.com")
     }
 
     #[test]
@@ -600,6 +1057,23 @@ mod tests {
         let mut config = create_valid_config();
         config.repo.project_id = "invalid".to_string();
         assert!(config.validate().is_err());
+
+        config.remote.provider = "GitLab".to_string();
+        config.repo.project_id = "group/subgroup/repo".to_string();
+        assert!(config.validate().is_ok());
+        config.repo.project_id = "invalid".to_string();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("numeric ID"), "{}", err);
+    }
+
+    #[test]
+    fn test_config_validate_local_without_token() {
+        let mut config = create_valid_config();
+        config.remote.provider = "local".to_string();
+        config.remote.api_url = "file:///srv/git/scripts.git".to_string();
+        config.remote.token = String::new();
+        config.repo.project_id = "scripts".to_string();
+        assert!(config.validate().is_ok());
     }
 
     #[test]
@@ -655,6 +1129,53 @@ project_id = "owner/repo"
         assert!(!config.user.auto_signoff);
     }
 
+    #[test]
+    fn test_network_section() {
+        let toml = r#"
+[user]
+name = "Test"
+email = "test@example.com"
+
+[remote]
+provider = "Gitcode"
+api_url = "https://api.example.com"
+token = "token"
+
+[repo]
+project_id = "owner/repo"
+
+[network]
+proxy = "http://proxy.lab:3128"
+no_proxy = "localhost,.lab"
+connect_timeout_secs = 5
+"#;
+        let config = Config::parse_from_str(toml).unwrap();
+        assert_eq!(config.network.proxy, "http://proxy.lab:3128");
+        assert_eq!(config.network.no_proxy, "localhost,.lab");
+        assert_eq!(config.network.connect_timeout_secs, 5);
+        assert_eq!(config.network.read_timeout_secs, 30);
+        assert!(config.network.user_agent().starts_with("vkt/"));
+
+        let without_key = toml.replace(
+            "connect_timeout_secs = 5",
+            "client_cert = \"/etc/vkt/me.pem\"",
+        );
+        assert!(Config::parse_from_str(&without_key).is_err());
+    }
+
+    #[test]
+    fn test_cache_section() {
+        assert!(CacheConfig::default().enabled);
+        assert!(CacheConfig::default().is_default());
+
+        let mut config = create_valid_config();
+        config.set_value("cache.enabled", "true").unwrap();
+        config.set_value("cache.dir", "/tmp/vkt-cache").unwrap();
+        assert_eq!(config.get_value("cache.enabled").unwrap(), "true");
+        assert_eq!(config.get_value("cache.dir").unwrap(), "/tmp/vkt-cache");
+        assert!(config.set_value("cache.enabled", "maybe").is_err());
+    }
+
     #[test]
     fn test_email_validation() {
         assert!(Config::is_valid_email("test@example.com"));
@@ -665,12 +1186,42 @@ project_id = "owner/repo"
         assert!(!Config::is_valid_email("test"));
     }
 
+    #[test]
+    fn test_project_id_validation() {
+        assert!(Config::is_valid_project_id(
+            "owner/repo",
+            &ProviderType::GitCode
+        ));
+        assert!(!Config::is_valid_project_id("1234", &ProviderType::GitCode));
+        assert!(Config::is_valid_project_id(
+            "group/sub/repo",
+            &ProviderType::GitLab
+        ));
+        assert!(Config::is_valid_project_id("1234", &ProviderType::GitLab));
+        assert!(Config::is_valid_project_id(
+            "group%2Frepo",
+            &ProviderType::GitLab
+        ));
+        assert!(!Config::is_valid_project_id("repo", &ProviderType::GitLab));
+        assert!(!Config::is_valid_project_id("", &ProviderType::GitLab));
+    }
+
     #[test]
     fn test_url_validation() {
         assert!(Config::is_valid_url("https://api.example.com"));
         assert!(Config::is_valid_url("http://localhost:8080"));
         assert!(!Config::is_valid_url("not-a-url"));
         assert!(!Config::is_valid_url("ftp://example.com"));
+        assert!(!Config::is_valid_url("file:///srv/git/scripts.git"));
+        assert!(Config::is_valid_api_url(
+            "file:///srv/git/scripts.git",
+            &ProviderType::Local
+        ));
+        assert!(!Config::is_valid_api_url("file://", &ProviderType::Local));
+        assert!(!Config::is_valid_api_url(
+            "https://api.example.com",
+            &ProviderType::Local
+        ));
     }
 
     #[test]
@@ -698,6 +1249,9 @@ project_id = "owner/repo"
         assert_eq!(ProviderType::parse("gitlab"), ProviderType::GitLab);
         assert_eq!(ProviderType::parse("GitHub"), ProviderType::GitHub);
         assert_eq!(ProviderType::parse("github"), ProviderType::GitHub);
+        assert_eq!(ProviderType::parse("Gitea"), ProviderType::Gitea);
+        assert_eq!(ProviderType::parse("Forgejo"), ProviderType::Gitea);
+        assert_eq!(ProviderType::parse("gitee"), ProviderType::Gitee);
         assert_eq!(
             ProviderType::parse("Unknown"),
             ProviderType::Unknown("unknown".to_string())
@@ -709,6 +1263,10 @@ project_id = "owner/repo"
         assert_eq!(ProviderType::GitCode.as_str(), "gitcode");
         assert_eq!(ProviderType::GitLab.as_str(), "gitlab");
         assert_eq!(ProviderType::GitHub.as_str(), "github");
+        assert_eq!(ProviderType::Gitea.as_str(), "gitea");
+        assert_eq!(ProviderType::Gitee.as_str(), "gitee");
+        assert_eq!(ProviderType::parse("Auto"), ProviderType::Auto);
+        assert_eq!(ProviderType::Auto.as_str(), "auto");
         assert_eq!(
             ProviderType::Unknown("custom".to_string()).as_str(),
             "custom"
@@ -729,6 +1287,26 @@ project_id = "owner/repo"
             detect_provider("https://github.com/api/v3"),
             ProviderType::GitHub
         );
+        assert_eq!(
+            detect_provider("https://codeberg.org/api/v1"),
+            ProviderType::Gitea
+        );
+        assert_eq!(
+            detect_provider("https://gitee.com/api/v5"),
+            ProviderType::Gitee
+        );
+        assert_eq!(
+            detect_provider("file:///srv/git/scripts.git"),
+            ProviderType::Local
+        );
+        assert_eq!(
+            detect_provider("https://git.corp.example/api/v4/"),
+            ProviderType::GitLab
+        );
+        assert_eq!(
+            detect_provider("https://ghe.corp.example/api/v3"),
+            ProviderType::GitHub
+        );
         assert_eq!(
             detect_provider("https://unknown.example.com/api"),
             ProviderType::Unknown("unknown".to_string())
@@ -741,6 +1319,7 @@ project_id = "owner/repo"
             provider: "Gitcode".to_string(),
             api_url: "https://api.gitcode.com".to_string(),
             token: "test-token".to_string(),
+            ..Default::default()
         };
         assert_eq!(remote.provider_type(), ProviderType::GitCode);
     }
@@ -759,6 +1338,10 @@ project_id = "owner/repo"
             ProviderType::GitHub.default_api_url(),
             Some("https://api.github.com")
         );
+        assert_eq!(
+            ProviderType::Gitee.default_api_url(),
+            Some("https://gitee.com/api/v5")
+        );
         assert_eq!(
             ProviderType::Unknown("custom".to_string()).default_api_url(),
             None
@@ -767,20 +1350,22 @@ project_id = "owner/repo"
 
     #[test]
     fn test_parse_key_valid() {
-        let (section, field) = Config::parse_key("user.name").unwrap();
-        assert_eq!(section, "user");
-        assert_eq!(field, "name");
-
-        let (section, field) = Config::parse_key("remote.token").unwrap();
-        assert_eq!(section, "remote");
-        assert_eq!(field, "token");
+        assert_eq!(document::parse_key("user.name").unwrap(), ["user", "name"]);
+        assert_eq!(
+            document::parse_key("default_profile").unwrap(),
+            ["default_profile"]
+        );
+        assert_eq!(
+            document::parse_key("profiles.work.remote.token").unwrap(),
+            ["profiles", "work", "remote", "token"]
+        );
     }
 
     #[test]
     fn test_parse_key_invalid() {
-        assert!(Config::parse_key("name").is_err());
-        assert!(Config::parse_key("user.name.extra").is_err());
-        assert!(Config::parse_key("").is_err());
+        assert!(document::parse_key("user.").is_err());
+        assert!(document::parse_key("user..name").is_err());
+        assert!(document::parse_key("").is_err());
     }
 
     #[test]
@@ -0,0 +1,122 @@
+//! Repository Override
+//!
+//! The global `-R/--repo [HOST/]OWNER/REPO[@REF]` flag points a single
+//! invocation at another repository. A leading segment that looks like a host
+//! name (it contains a dot or a port, or is `localhost`) selects the forge;
+//! without one the configured forge is used. Clone URLs are accepted too.
+
+use super::checkout::{GitRemote, api_url_on_host};
+use crate::error::{Result, VktError};
+
+/// Repository named on the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoSpec {
+    /// Forge host, if given
+    pub host: Option<String>,
+    /// Repository path (e.g. "owner/repo" or "group/subgroup/repo")
+    pub project_id: String,
+    /// Branch, tag or commit to read from and base pull requests on
+    pub reference: Option<String>,
+}
+
+impl RepoSpec {
+    /// Parse `[HOST/]OWNER/REPO[@REF]` or a clone URL with an optional `@REF`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = || {
+            VktError::Validation(format!(
+                "Invalid repository '{}'. Use [HOST/]OWNER/REPO[@REF]",
+                spec
+            ))
+        };
+
+        // A '@' before the first '/' belongs to an SSH user, not a ref
+        let spec = spec.trim();
+        let (repo, reference) = match spec.rsplit_once('@') {
+            Some((repo, reference)) if repo.contains('/') => {
+                if reference.is_empty() {
+                    return Err(invalid());
+                }
+                (repo, Some(reference.to_string()))
+            }
+            _ => (spec, None),
+        };
+
+        let is_url = repo.contains("://")
+            || repo
+                .split_once(':')
+                .is_some_and(|(authority, _)| authority.contains('@'));
+        if is_url {
+            let remote = GitRemote::parse("--repo", repo).ok_or_else(invalid)?;
+            return Ok(Self {
+                host: Some(remote.host),
+                project_id: remote.project_id,
+                reference,
+            });
+        }
+
+        let repo = repo.trim_matches('/');
+        let (host, project_id) = match repo.split_once('/') {
+            Some((first, rest)) if is_host(first) => (Some(first.to_lowercase()), rest),
+            _ => (None, repo),
+        };
+        let project_id = project_id.trim_end_matches(".git");
+        if project_id.split('/').count() < 2 || project_id.split('/').any(str::is_empty) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            host,
+            project_id: project_id.to_string(),
+            reference,
+        })
+    }
+
+    /// Whether the repository lives on the forge serving `api_url`
+    ///
+    /// A spec without a host matches any forge.
+    pub fn matches_api_url(&self, api_url: &str) -> bool {
+        self.host
+            .as_deref()
+            .is_none_or(|host| api_url_on_host(api_url, host))
+    }
+}
+
+/// Whether a path segment names a host rather than an owner
+fn is_host(segment: &str) -> bool {
+    segment.contains('.') || segment.contains(':') || segment == "localhost"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_repo_specs() {
+        let spec = RepoSpec::parse("alice/scripts").unwrap();
+        assert_eq!(spec.host, None);
+        assert_eq!(spec.project_id, "alice/scripts");
+        assert_eq!(spec.reference, None);
+
+        let spec = RepoSpec::parse("gitlab.corp.example/virt/kvm/tools@feature/x").unwrap();
+        assert_eq!(spec.host.as_deref(), Some("gitlab.corp.example"));
+        assert_eq!(spec.project_id, "virt/kvm/tools");
+        assert_eq!(spec.reference.as_deref(), Some("feature/x"));
+
+        let spec = RepoSpec::parse("group/sub/repo").unwrap();
+        assert_eq!(spec.host, None);
+        assert_eq!(spec.project_id, "group/sub/repo");
+
+        let spec = RepoSpec::parse("git@github.com:torvalds/linux.git@v6.9").unwrap();
+        assert_eq!(spec.host.as_deref(), Some("github.com"));
+        assert_eq!(spec.project_id, "torvalds/linux");
+        assert_eq!(spec.reference.as_deref(), Some("v6.9"));
+
+        let spec = RepoSpec::parse("https://gitcode.com/virt/tools").unwrap();
+        assert!(spec.matches_api_url("https://api.gitcode.com/api/v5"));
+        assert!(!spec.matches_api_url("https://api.github.com"));
+
+        for invalid in ["repo", "github.com/owner", "owner/repo@", "owner//repo"] {
+            assert!(RepoSpec::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Runtime Context
+//!
+//! Settings that apply to a whole invocation rather than to one command:
+//! which configuration files to use, how much to log and how to format output.
+//! They come from the global command line flags and the environment, and are
+//! handed to every [`Command`](crate::commands::Command).
+
+use std::io::IsTerminal;
+
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::cli::Cli;
+use crate::config::{Config, ConfigFiles, GitRemote, LayeredConfig, PROFILE_ENV, RepoSpec};
+use crate::error::{Result, VktError};
+
+/// Resolved global settings for one invocation
+#[derive(Debug, Clone)]
+pub struct RuntimeContext {
+    /// Configuration files to layer; `vkt config` writes to the user file
+    pub config_files: ConfigFiles,
+    /// Profile selected by `--profile` or `VKT_PROFILE`
+    pub profile: Option<String>,
+    /// Repository selected by `-R/--repo`
+    pub repo: Option<RepoSpec>,
+    /// Most detailed level of vkt's own log events printed (unless `RUST_LOG` is set)
+    pub log_level: Level,
+    /// Answer from the response cache only, without network access
+    pub offline: bool,
+    /// Colorize output
+    pub color: bool,
+}
+
+impl RuntimeContext {
+    /// Resolve the context from parsed command line arguments
+    ///
+    /// The user configuration file is taken from `--config`, then
+    /// `VKT_CONFIG`, then the default location, and the profile from
+    /// `--profile`, then `VKT_PROFILE`. The project file and the git checkout
+    /// are searched from the current directory. Color is disabled by
+    /// `NO_COLOR` or when stdout is not a terminal.
+    ///
+    /// # Errors
+    /// Fails if `--remote` names a remote the checkout does not have, or
+    /// `--repo` is malformed
+    pub fn from_cli(cli: &Cli) -> Result<Self> {
+        let user = Config::resolve_path(cli.config.as_deref())?;
+        let cwd = std::env::current_dir()?;
+        let mut config_files = ConfigFiles::discover(user, &cwd);
+        if let Some(name) = &cli.remote {
+            config_files.git_remote = Some(GitRemote::discover(&cwd, name).ok_or_else(|| {
+                VktError::Config(format!(
+                    "No git remote '{}' pointing at a forge repository in {}",
+                    name,
+                    cwd.display()
+                ))
+            })?);
+        }
+        Ok(Self {
+            config_files,
+            profile: cli
+                .profile
+                .clone()
+                .or_else(|| std::env::var(PROFILE_ENV).ok())
+                .filter(|profile| !profile.is_empty()),
+            repo: cli.repo.as_deref().map(RepoSpec::parse).transpose()?,
+            log_level: if cli.verbose {
+                Level::DEBUG
+            } else {
+                Level::INFO
+            },
+            offline: cli.offline,
+            color: std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal(),
+        })
+    }
+
+    /// Load the layered configuration, applying the command line flags
+    ///
+    /// With `--repo` on another host, the configuration is loaded with the
+    /// profile whose API URL is on that host, so its credentials are used.
+    pub fn load_layered(&self) -> Result<LayeredConfig> {
+        let mut layered = LayeredConfig::load(&self.config_files, self.profile.as_deref())?;
+        if let Some(repo) = &self.repo {
+            if !repo.matches_api_url(&layered.config.remote.api_url) {
+                let host = repo.host.as_deref().unwrap_or_default();
+                let profile = layered.profile_for_host(host).map(str::to_string);
+                let Some(profile) = profile else {
+                    return Err(VktError::Config(format!(
+                        "No profile for {} (set up one with `vkt -p <NAME> config --setup`)",
+                        host
+                    )));
+                };
+                tracing::debug!("Using profile {} for {}", profile, host);
+                layered = LayeredConfig::load(&self.config_files, Some(&profile))?;
+            }
+            layered.set_flag("repo.project_id", &repo.project_id, "--repo")?;
+            if let Some(reference) = &repo.reference {
+                layered.set_flag("repo.default_branch", reference, "--repo")?;
+            }
+        }
+        layered.config.cache.offline |= self.offline;
+        Ok(layered)
+    }
+
+    /// Load the effective configuration
+    pub fn load_config(&self) -> Result<Config> {
+        Ok(self.load_layered()?.config)
+    }
+
+    /// Install the log subscriber and output styling for this process
+    ///
+    /// Logs go to stderr so they never mix with command output.
+    pub fn init_output(&self) {
+        colored::control::set_override(self.color);
+        let filter = self.log_filter(std::env::var("RUST_LOG").ok().as_deref());
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::level_filters::LevelFilter::TRACE)
+            .with_ansi(self.color && std::io::stderr().is_terminal())
+            .with_writer(std::io::stderr)
+            .finish()
+            .with(filter)
+            .init();
+    }
+
+    /// Which log events to print
+    ///
+    /// Only vkt's own events, up to `log_level`, so that `-v` does not bury the
+    /// output under HTTP client internals. A `RUST_LOG` setting in the
+    /// `target=level,...` form replaces this.
+    fn log_filter(&self, rust_log: Option<&str>) -> Targets {
+        if let Some(spec) = rust_log.filter(|spec| !spec.trim().is_empty()) {
+            match spec.parse::<Targets>() {
+                Ok(targets) => return targets,
+                Err(e) => eprintln!("Ignoring invalid RUST_LOG '{}': {}", spec, e),
+            }
+        }
+        Targets::new().with_target("vkt", self.log_level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::parse_args_from;
+    use crate::config::Origin;
+
+    #[test]
+    fn test_from_cli() {
+        let cli = parse_args_from(&["vkt", "-v", "-c", "/tmp/project.toml", "list"]).unwrap();
+        let ctx = RuntimeContext::from_cli(&cli).unwrap();
+        assert_eq!(
+            ctx.config_files.user,
+            std::path::PathBuf::from("/tmp/project.toml")
+        );
+        assert_eq!(ctx.log_level, Level::DEBUG);
+        assert!(!ctx.offline);
+
+        let cli = parse_args_from(&["vkt", "--offline", "list"]).unwrap();
+        let ctx = RuntimeContext::from_cli(&cli).unwrap();
+        assert_eq!(ctx.log_level, Level::INFO);
+        assert!(ctx.offline);
+    }
+
+    #[test]
+    fn test_log_filter_is_scoped_to_vkt() {
+        let cli = parse_args_from(&["vkt", "-v", "list"]).unwrap();
+        let ctx = RuntimeContext::from_cli(&cli).unwrap();
+
+        let filter = ctx.log_filter(None);
+        assert!(filter.would_enable("vkt::api::transport", &Level::DEBUG));
+        assert!(!filter.would_enable("vkt::api::transport", &Level::TRACE));
+        assert!(!filter.would_enable("hyper_util::client", &Level::DEBUG));
+        assert!(!filter.would_enable("reqwest::connect", &Level::INFO));
+
+        let filter = ctx.log_filter(Some("vkt=info,hyper_util=trace"));
+        assert!(filter.would_enable("hyper_util::client", &Level::TRACE));
+        assert!(!filter.would_enable("vkt::api::transport", &Level::DEBUG));
+
+        let filter = ctx.log_filter(Some("not a=valid=spec"));
+        assert!(filter.would_enable("vkt::api::transport", &Level::DEBUG));
+    }
+
+    #[test]
+    fn test_load_config_reads_configured_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("project.toml");
+        std::fs::write(
+            &path,
+            r#"
+[user]
+name = "Test"
+email = "test@example.com"
+
+[remote]
+provider = "Gitcode"
+api_url = "https://api.example.com"
+token = "token"
+
+[repo]
+project_id = "team/scripts"
+"#,
+        )
+        .unwrap();
+
+        let ctx = RuntimeContext {
+            config_files: ConfigFiles::user_only(path),
+            profile: None,
+            repo: None,
+            log_level: Level::INFO,
+            offline: true,
+            color: false,
+        };
+        let config = ctx.load_config().unwrap();
+        assert_eq!(config.repo.project_id, "team/scripts");
+        assert!(config.cache.offline);
+    }
+
+    #[test]
+    fn test_repo_override_uses_matching_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            r#"
+[user]
+name = "Test"
+email = "test@example.com"
+
+[remote]
+provider = "GitHub"
+api_url = "https://api.github.com"
+token = "github-token"
+
+[repo]
+project_id = "me/dotfiles"
+
+[profiles.corp.remote]
+provider = "GitLab"
+api_url = "https://gitlab.corp.example/api/v4"
+token = "corp-token"
+
+[profiles.lab.remote]
+provider = "Gitea"
+api_url = "https://git.lab.example/api/v1"
+"#,
+        )
+        .unwrap();
+        let ctx = |repo: &str| RuntimeContext {
+            config_files: ConfigFiles::user_only(&path),
+            profile: None,
+            repo: Some(RepoSpec::parse(repo).unwrap()),
+            log_level: Level::INFO,
+            offline: false,
+            color: false,
+        };
+
+        let layered = ctx("alice/scripts@dev").load_layered().unwrap();
+        assert_eq!(layered.config.repo.project_id, "alice/scripts");
+        assert_eq!(layered.config.repo.default_branch, "dev");
+        assert_eq!(layered.config.remote.token, "github-token");
+        assert_eq!(
+            layered.origin("repo.project_id"),
+            Origin::Flag("--repo".to_string())
+        );
+
+        let config = ctx("gitlab.corp.example/virt/kvm").load_config().unwrap();
+        assert_eq!(config.repo.project_id, "virt/kvm");
+        assert_eq!(config.remote.token, "corp-token");
+
+        // A profile without its own token does not get the GitHub one
+        let config = ctx("git.lab.example/infra/ci").load_config().unwrap();
+        assert_eq!(config.repo.project_id, "infra/ci");
+        assert!(!config.remote.has_token_source());
+
+        let err = ctx("gitee.com/o/r").load_config().unwrap_err();
+        assert!(
+            err.to_string().contains("No profile for gitee.com"),
+            "{}",
+            err
+        );
+    }
+}
@@ -24,7 +24,7 @@ pub enum VktError {
     Validation(String),
 
     /// Authentication failed (401)
-    #[error("Authentication failed: {0}")]
+    #[error("Authentication failed: {0} (check the token with `vkt auth status`)")]
     AuthInvalid(String),
 
     /// Permission denied (403)
@@ -42,6 +42,10 @@ pub enum VktError {
     /// Resource conflict (409)
     #[error("Resource conflict: {0}")]
     Conflict(String),
+
+    /// Network access needed while offline
+    #[error("Offline: {0}")]
+    Offline(String),
 }
 
 impl From<reqwest::Error> for VktError {
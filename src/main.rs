@@ -1,27 +1,37 @@
 use anyhow::Result;
 use vkt::cli::{Commands, parse_args};
-use vkt::commands::{Command, config::ConfigCommand, get::GetCommand, list::ListCommand, submit::SubmitCommand};
+use vkt::commands::{Command, auth::AuthCommand, cache::CacheCommand, config::ConfigCommand, get::GetCommand, list::ListCommand, submit::SubmitCommand};
+use vkt::context::RuntimeContext;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt::init();
     let cli = parse_args();
+    let ctx = RuntimeContext::from_cli(&cli)?;
+    ctx.init_output();
     match cli.command {
         Commands::List(args) => {
             let cmd = ListCommand::new(args);
-            cmd.execute().await?;
+            cmd.execute(&ctx).await?;
         }
         Commands::Get(args) => {
             let cmd = GetCommand::new(args);
-            cmd.execute().await?;
+            cmd.execute(&ctx).await?;
         }
         Commands::Submit(args) => {
             let cmd = SubmitCommand::new(args);
-            cmd.execute().await?;
+            cmd.execute(&ctx).await?;
         }
         Commands::Config(args) => {
             let cmd = ConfigCommand::new(args);
-            cmd.execute().await?;
+            cmd.execute(&ctx).await?;
+        }
+        Commands::Auth(args) => {
+            let cmd = AuthCommand::new(args);
+            cmd.execute(&ctx).await?;
+        }
+        Commands::Cache(args) => {
+            let cmd = CacheCommand::new(args);
+            cmd.execute(&ctx).await?;
         }
     }
     Ok(())